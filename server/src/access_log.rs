@@ -0,0 +1,113 @@
+use actix_web::dev::{BodySize, MessageBody, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header;
+use actix_web::Error;
+use futures::future::{ok, Ready};
+use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use crate::request_id::request_id;
+
+/// Log target of the access log lines, printed without the usual prefix.
+pub const ACCESS_LOG_TARGET: &str = "mystore::access";
+
+/// One access log line.
+#[derive(Serialize, Debug)]
+pub struct AccessEntry {
+    pub time: String,
+    pub remote: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub latency_ms: f64,
+    pub bytes: Option<u64>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+}
+
+/// Logs every request as a JSON object at info level, with the id
+/// `AssignRequestId` stored in the request.
+pub struct AccessLog;
+
+impl<S, B> Transform<S> for AccessLog
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = AccessLogMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(AccessLogMiddleware { service })
+    }
+}
+
+pub struct AccessLogMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service for AccessLogMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: ServiceRequest) -> Self::Future {
+        let started = Instant::now();
+        let response = self.service.call(request);
+        Box::pin(async move {
+            let response = response.await?;
+            if log::log_enabled!(target: ACCESS_LOG_TARGET, log::Level::Info) {
+                let entry = entry(&response, started);
+                if let Ok(line) = serde_json::to_string(&entry) {
+                    log::info!(target: ACCESS_LOG_TARGET, "{}", line);
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+fn entry<B: MessageBody>(response: &ServiceResponse<B>, started: Instant) -> AccessEntry {
+    let request = response.request();
+    let bytes = match response.response().body().size() {
+        BodySize::Sized(bytes) => Some(bytes as u64),
+        BodySize::Sized64(bytes) => Some(bytes),
+        BodySize::Empty | BodySize::None => Some(0),
+        BodySize::Stream => None,
+    };
+    AccessEntry {
+        time: chrono::Utc::now().to_rfc3339(),
+        remote: request
+            .connection_info()
+            .remote()
+            .map(|remote| remote.to_string()),
+        method: request.method().to_string(),
+        path: request.path().to_string(),
+        status: response.status().as_u16(),
+        latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+        bytes,
+        user_agent: request
+            .headers()
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        request_id: request_id(request),
+    }
+}
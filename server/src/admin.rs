@@ -0,0 +1,89 @@
+use actix_web::client::Client;
+use actix_web::http::header;
+use actix_web::{web, HttpResponse};
+use serde_json::json;
+use std::fs;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::embedded;
+use crate::metrics::serve_metrics;
+
+/// How long `/readyz` waits for the proxy upstream before calling it down.
+const UPSTREAM_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Registers the probe and metrics endpoints at their configured paths.
+/// Expects `Config` and `Metrics` in the app data.
+pub fn configure(config: &Config) -> impl FnOnce(&mut web::ServiceConfig) + '_ {
+    move |service_config| {
+        service_config
+            .route(&config.admin.health_path, web::get().to(healthz))
+            .route(&config.admin.ready_path, web::get().to(readyz))
+            .route(&config.admin.metrics_path, web::get().to(serve_metrics));
+    }
+}
+
+/// The process is up and answering.
+pub async fn healthz() -> HttpResponse {
+    HttpResponse::Ok()
+        .header(header::CACHE_CONTROL, "no-store")
+        .body("ok")
+}
+
+/// The assets can be served and, when proxying, the upstream backend
+/// answers. Any HTTP response from the upstream counts as reachable.
+pub async fn readyz(config: web::Data<Config>) -> HttpResponse {
+    let assets = match fs::read_dir(&config.assets_dir) {
+        Ok(_) => Ok(()),
+        Err(_) if !embedded::assets().is_empty() => Ok(()),
+        Err(error) => Err(format!("{}: {}", config.assets_dir.display(), error)),
+    };
+    let upstream = match &config.proxy {
+        Some(proxy) => Client::build()
+            .timeout(UPSTREAM_TIMEOUT)
+            .finish()
+            .head(proxy.upstream.base())
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(|error| format!("{}: {}", proxy.upstream.base(), error)),
+        None => Ok(()),
+    };
+
+    let mut response = if assets.is_ok() && upstream.is_ok() {
+        HttpResponse::Ok()
+    } else {
+        HttpResponse::ServiceUnavailable()
+    };
+    response
+        .header(header::CACHE_CONTROL, "no-store")
+        .json(json!({
+            "assets": check_status(&assets),
+            "upstream": config.proxy.as_ref().map(|_| check_status(&upstream)),
+        }))
+}
+
+fn check_status(check: &Result<(), String>) -> String {
+    match check {
+        Ok(()) => "ok".to_string(),
+        Err(error) => error.clone(),
+    }
+}
+
+/// Paths with a fixed meaning, labelled as themselves in the metrics.
+pub fn metric_routes(config: &Config) -> Vec<String> {
+    let mut routes = vec![
+        config.admin.health_path.clone(),
+        config.admin.ready_path.clone(),
+        config.admin.metrics_path.clone(),
+        crate::pwa::MANIFEST_PATH.to_string(),
+        crate::pwa::SERVICE_WORKER_PATH.to_string(),
+    ];
+    if let Some(proxy) = &config.proxy {
+        routes.extend(proxy.paths.iter().cloned());
+    }
+    if config.mock.is_some() {
+        routes.extend(crate::mock::ROUTES.iter().map(|route| route.to_string()));
+    }
+    routes
+}
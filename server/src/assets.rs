@@ -0,0 +1,346 @@
+use actix_files::{file_extension_to_mime, NamedFile};
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::{web, Error, HttpRequest, HttpResponse};
+use percent_encoding::percent_decode_str;
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::dev;
+use crate::embedded::{self, EmbeddedFile};
+
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+const REVALIDATE: &str = "no-cache";
+/// Shortest run of hex digits taken as a content hash in a file name, as in
+/// `app-1a2b3c4d.wasm` or `styles.1a2b3c4d.css`.
+const MIN_HASH_LEN: usize = 8;
+/// Characters that set a content hash apart from the rest of a file name.
+const HASH_SEPARATORS: [char; 3] = ['-', '.', '_'];
+
+/// Points the app's API requests at this server instead of its default API
+/// URL, for when the server proxies or mocks the backend.
+const SAME_ORIGIN_API: &str = r#"<meta name="mystore-api-url" content="">"#;
+
+/// The `#[to]` paths of the client's `AppRoute` (src/routing.rs), but for
+/// "/". `{name}` matches any one segment; tests/spa_fallback.rs checks the
+/// list against the client.
+pub const APP_ROUTES: [&str; 11] = [
+    "/register",
+    "/login",
+    "/dashboard",
+    "/reports",
+    "/sales/{id}",
+    "/sales",
+    "/products/import",
+    "/products",
+    "/prices",
+    "/pos",
+    "/page-not-found",
+];
+
+/// Serves a file from the assets directory, or else from the copy embedded
+/// in the binary, so files on disk can override embedded ones. Navigation
+/// to a client-side route gets the index file; any other missing file is a
+/// 404, so a broken wasm or stylesheet URL does not come back as HTML.
+pub async fn serve(request: HttpRequest, config: web::Data<Config>) -> Result<HttpResponse, Error> {
+    if let Some(path) = resolve(&config.assets_dir, &config.index_file, request.path()) {
+        return send(&request, &config, &path);
+    }
+    if let Some(name) = resolve_embedded(&config.index_file, request.path()) {
+        return Ok(send_embedded(&request, &config, &name));
+    }
+    if !is_navigation(&request) {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    let index_path = config.index_path();
+    if index_path.is_file() || !embedded::assets().contains(&config.index_file) {
+        send(&request, &config, &index_path)
+    } else {
+        Ok(send_embedded(&request, &config, &config.index_file))
+    }
+}
+
+fn send(request: &HttpRequest, config: &Config, path: &Path) -> Result<HttpResponse, Error> {
+    if rewrites_index(config) && path == config.index_path() {
+        Ok(serve_index(config, &fs::read_to_string(path)?))
+    } else {
+        serve_file(request, path)
+    }
+}
+
+fn send_embedded(request: &HttpRequest, config: &Config, name: &str) -> HttpResponse {
+    if rewrites_index(config) && name == config.index_file {
+        if let Some(file) = embedded::assets().get(name) {
+            return serve_index(config, &String::from_utf8_lossy(&file.data));
+        }
+    }
+    serve_embedded(request, name)
+}
+
+fn rewrites_index(config: &Config) -> bool {
+    config.dev.is_some() || serves_api(config)
+}
+
+fn serves_api(config: &Config) -> bool {
+    config.proxy.is_some() || config.mock.is_some()
+}
+
+/// The index file as this server's app needs it: told to call the API
+/// same-origin when the server proxies or mocks it, and with the live
+/// reload script in dev mode.
+pub fn serve_index(config: &Config, html: &str) -> HttpResponse {
+    let html = if serves_api(config) {
+        inject_api_url(html)
+    } else {
+        html.to_string()
+    };
+    let (html, cache_control) = if config.dev.is_some() {
+        (dev::inject_snippet(&html), "no-store")
+    } else {
+        (html, REVALIDATE)
+    };
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .header(header::CACHE_CONTROL, cache_control)
+        .body(html)
+}
+
+/// `html` with the same-origin API meta tag added before `</head>`.
+pub fn inject_api_url(html: &str) -> String {
+    match html.find("</head>") {
+        Some(end) => format!("{}{}\n{}", &html[..end], SAME_ORIGIN_API, &html[end..]),
+        None => format!("{}\n{}", SAME_ORIGIN_API, html),
+    }
+}
+
+/// A request is navigational when the browser asks for HTML, when the path
+/// has no file extension, or when it is under a known client-side route.
+pub fn is_navigation(request: &HttpRequest) -> bool {
+    let path = request.path();
+    let accepts_html = request
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |accept| accept.contains("text/html"));
+    let has_extension = path
+        .rsplit('/')
+        .next()
+        .map_or(false, |file_name| file_name.contains('.'));
+    accepts_html || !has_extension || is_app_route(path)
+}
+
+/// Whether `path` is a client-side route or lies under one.
+pub fn is_app_route(path: &str) -> bool {
+    let segments: Vec<&str> = path.split('/').skip(1).collect();
+    APP_ROUTES.iter().any(|route| {
+        let route: Vec<&str> = route.split('/').skip(1).collect();
+        route.len() <= segments.len()
+            && route
+                .iter()
+                .zip(&segments)
+                .all(|(expected, segment)| expected.starts_with('{') || expected == segment)
+    })
+}
+
+/// Sends `path`, or its precompressed `.br`/`.gz` sibling when the client
+/// accepts it, with caching headers that depend on the file name.
+pub fn serve_file(request: &HttpRequest, path: &Path) -> Result<HttpResponse, Error> {
+    let mut response = match precompressed(request, path) {
+        Some((sibling, encoding)) => {
+            let mime = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .map(file_extension_to_mime)
+                .unwrap_or(mime::APPLICATION_OCTET_STREAM);
+            let mut response = NamedFile::open(sibling)?
+                .set_content_type(mime)
+                .disable_content_disposition()
+                .into_response(request)?;
+            response
+                .headers_mut()
+                .insert(header::CONTENT_ENCODING, HeaderValue::from_static(encoding));
+            response
+        }
+        None => NamedFile::open(path)?.into_response(request)?,
+    };
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(cache_control(path)),
+    );
+    headers.insert(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+    Ok(response)
+}
+
+/// Sends an embedded file, or its precompressed sibling, with the same
+/// caching headers as files on disk and an ETag from its contents.
+pub fn serve_embedded(request: &HttpRequest, name: &str) -> HttpResponse {
+    let assets = embedded::assets();
+    let found = accepted_encodings(request)
+        .find_map(|(encoding, suffix)| {
+            let file = assets.get(&format!("{}.{}", name, suffix))?;
+            Some((file, Some(encoding)))
+        })
+        .or_else(|| assets.get(name).map(|file| (file, None)));
+    let (file, encoding) = match found {
+        Some(found) => found,
+        None => return HttpResponse::NotFound().finish(),
+    };
+
+    let fresh = is_fresh(request, file);
+    let mut response = if fresh {
+        HttpResponse::NotModified()
+    } else {
+        HttpResponse::Ok()
+    };
+    response
+        .header(header::ETAG, file.etag.as_str())
+        .header(header::CACHE_CONTROL, cache_control(Path::new(name)))
+        .header(header::VARY, "Accept-Encoding");
+    if fresh {
+        return response.finish();
+    }
+    let mime = Path::new(name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(file_extension_to_mime)
+        .unwrap_or(mime::APPLICATION_OCTET_STREAM);
+    response.content_type(mime.to_string());
+    if let Some(encoding) = encoding {
+        response.header(header::CONTENT_ENCODING, encoding);
+    }
+    match &file.data {
+        Cow::Borrowed(data) => response.body(*data),
+        Cow::Owned(data) => response.body(data.clone()),
+    }
+}
+
+fn is_fresh(request: &HttpRequest, file: &EmbeddedFile) -> bool {
+    request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |tags| {
+            tags.split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag.trim_start_matches("W/") == file.etag)
+        })
+}
+
+/// Decoded segments of a request path, or `None` when one of them would
+/// escape the assets root.
+fn segments(request_path: &str) -> Option<Vec<String>> {
+    let mut segments = vec![];
+    for segment in request_path.split('/') {
+        let segment = percent_decode_str(segment).decode_utf8().ok()?;
+        match segment.as_ref() {
+            "" | "." => continue,
+            ".." => return None,
+            segment if segment.contains('\\') || segment.contains('\0') => return None,
+            segment => segments.push(segment.to_string()),
+        }
+    }
+    Some(segments)
+}
+
+/// Maps a request path onto the name of an embedded file. Directories
+/// resolve to their index file.
+pub fn resolve_embedded(index_file: &str, request_path: &str) -> Option<String> {
+    let assets = embedded::assets();
+    let name = segments(request_path)?.join("/");
+    let index = if name.is_empty() {
+        index_file.to_string()
+    } else {
+        format!("{}/{}", name, index_file)
+    };
+    if assets.contains(&name) {
+        Some(name)
+    } else if assets.contains(&index) {
+        Some(index)
+    } else {
+        None
+    }
+}
+
+/// Maps a request path onto a file under `root`, refusing anything that
+/// would escape it. Directories resolve to their index file.
+pub fn resolve(root: &Path, index_file: &str, request_path: &str) -> Option<PathBuf> {
+    let mut path = root.to_path_buf();
+    path.extend(segments(request_path)?);
+    if path.is_dir() {
+        path.push(index_file);
+    }
+    if path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Content encodings the client accepts that may have a precompressed
+/// sibling, best first, with the file suffix of that sibling.
+fn accepted_encodings(
+    request: &HttpRequest,
+) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+    let accepted = request
+        .headers()
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    [("br", "br"), ("gzip", "gz")]
+        .iter()
+        .copied()
+        .filter(move |(encoding, _)| accepts(accepted, encoding))
+}
+
+fn precompressed(request: &HttpRequest, path: &Path) -> Option<(PathBuf, &'static str)> {
+    let file_name = path.file_name()?.to_str()?;
+    accepted_encodings(request)
+        .map(|(encoding, suffix)| {
+            let sibling = path.with_file_name(format!("{}.{}", file_name, suffix));
+            (sibling, encoding)
+        })
+        .find(|(sibling, _)| sibling.is_file())
+}
+
+fn accepts(accept_encoding: &str, encoding: &str) -> bool {
+    accept_encoding.split(',').any(|item| {
+        let mut parts = item.split(';').map(str::trim);
+        let name = parts.next().unwrap_or_default();
+        let refused = parts.any(|param| {
+            param
+                .strip_prefix("q=")
+                .and_then(|q| q.parse::<f32>().ok())
+                .map_or(false, |q| q == 0.0)
+        });
+        (name == encoding || name == "*") && !refused
+    })
+}
+
+/// Hashed file names never change content, so they can be cached for good.
+/// Everything else, index.html included, is revalidated with its ETag.
+pub fn cache_control(path: &Path) -> &'static str {
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    let hashed = stem.split(&HASH_SEPARATORS[..]).skip(1).any(is_hash);
+    if hashed
+        && path
+            .extension()
+            .map_or(true, |extension| extension != "html")
+    {
+        IMMUTABLE
+    } else {
+        REVALIDATE
+    }
+}
+
+/// A name segment that is a content hash: hex digits with at least one
+/// letter, so dates and version numbers such as `20200601` do not count.
+fn is_hash(segment: &str) -> bool {
+    segment.len() >= MIN_HASH_LEN
+        && segment.chars().all(|c| c.is_ascii_hexdigit())
+        && segment.chars().any(|c| c.is_ascii_alphabetic())
+}
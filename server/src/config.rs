@@ -0,0 +1,585 @@
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+use crate::embedded;
+use crate::proxy::Upstream;
+use crate::security::SecurityHeaders;
+
+const DEFAULT_BIND: &str = "0.0.0.0";
+const DEFAULT_PORT: u16 = 8000;
+const DEFAULT_WORKERS: usize = 1;
+const DEFAULT_SHUTDOWN_TIMEOUT: u64 = 30;
+const DEFAULT_ASSETS_DIR: &str = "../static";
+const DEFAULT_INDEX_FILE: &str = "index.html";
+pub const DEFAULT_LOG_LEVEL: &str = "actix_server=info,server=info,mystore::access=info";
+const DEFAULT_HEALTH_PATH: &str = "/healthz";
+const DEFAULT_READY_PATH: &str = "/readyz";
+const DEFAULT_METRICS_PATH: &str = "/metrics";
+const DEFAULT_PROXY_PATHS: [&str; 4] = ["/graphql", "/login", "/register", "/logout"];
+
+/// Command line options. Each one falls back to its environment variable,
+/// then to the config file, then to the built-in default.
+#[derive(StructOpt, Debug)]
+#[structopt(name = "server", about = "Serves the My Store web client")]
+pub struct Opt {
+    /// Address to listen on
+    #[structopt(long, env = "MYSTORE_BIND")]
+    bind: Option<String>,
+    /// Port to listen on
+    #[structopt(long, env = "MYSTORE_PORT")]
+    port: Option<u16>,
+    /// Number of worker threads
+    #[structopt(long, env = "MYSTORE_WORKERS")]
+    workers: Option<usize>,
+    /// Seconds requests in flight get to finish after SIGTERM or SIGINT
+    #[structopt(long, env = "MYSTORE_SHUTDOWN_TIMEOUT")]
+    shutdown_timeout: Option<u64>,
+    /// Directory with index.html, the wasm bundle and the stylesheets; with
+    /// embedded assets, files in it take precedence over the embedded ones
+    #[structopt(long, env = "MYSTORE_ASSETS_DIR", parse(from_os_str))]
+    assets_dir: Option<PathBuf>,
+    /// File inside the assets directory served for client-side routes
+    #[structopt(long, env = "MYSTORE_INDEX_FILE")]
+    index_file: Option<String>,
+    /// env_logger filter, e.g. "info" or "actix_web=debug"; RUST_LOG is used when unset
+    #[structopt(long, env = "MYSTORE_LOG_LEVEL")]
+    log_level: Option<String>,
+    /// Backend to forward API requests to, e.g. http://localhost:8088
+    #[structopt(long, env = "MYSTORE_PROXY_UPSTREAM")]
+    proxy_upstream: Option<String>,
+    /// API path forwarded to the upstream backend; repeat for several paths
+    #[structopt(long = "proxy-path", env = "MYSTORE_PROXY_PATHS", use_delimiter = true)]
+    proxy_paths: Vec<String>,
+    /// Serve an in-memory store backend instead of proxying to a real one
+    #[structopt(long, env = "MYSTORE_MOCK_BACKEND")]
+    mock_backend: bool,
+    /// JSON fixture the mock backend starts from
+    #[structopt(long, env = "MYSTORE_MOCK_FIXTURE", parse(from_os_str))]
+    mock_fixture: Option<PathBuf>,
+    /// Do not add security headers to responses
+    #[structopt(long, env = "MYSTORE_NO_SECURITY_HEADERS")]
+    no_security_headers: bool,
+    /// Content-Security-Policy, without frame-ancestors
+    #[structopt(long, env = "MYSTORE_CONTENT_SECURITY_POLICY")]
+    content_security_policy: Option<String>,
+    /// Send the CSP as Content-Security-Policy-Report-Only
+    #[structopt(long, env = "MYSTORE_CSP_REPORT_ONLY")]
+    csp_report_only: bool,
+    /// PEM certificate chain; serves HTTPS together with --tls-key
+    #[structopt(long, env = "MYSTORE_TLS_CERT", parse(from_os_str))]
+    tls_cert: Option<PathBuf>,
+    /// PEM private key for --tls-cert
+    #[structopt(long, env = "MYSTORE_TLS_KEY", parse(from_os_str))]
+    tls_key: Option<PathBuf>,
+    /// Plain HTTP port that redirects to HTTPS
+    #[structopt(long, env = "MYSTORE_HTTP_REDIRECT_PORT")]
+    http_redirect_port: Option<u16>,
+    /// Serve the health, readiness and metrics endpoints on this port only
+    #[structopt(long, env = "MYSTORE_ADMIN_PORT")]
+    admin_port: Option<u16>,
+    /// Path of the liveness probe
+    #[structopt(long, env = "MYSTORE_HEALTH_PATH")]
+    health_path: Option<String>,
+    /// Path of the readiness probe
+    #[structopt(long, env = "MYSTORE_READY_PATH")]
+    ready_path: Option<String>,
+    /// Path of the Prometheus metrics
+    #[structopt(long, env = "MYSTORE_METRICS_PATH")]
+    metrics_path: Option<String>,
+    /// TOML file with any of the options above
+    #[structopt(long, short, env = "MYSTORE_CONFIG", parse(from_os_str))]
+    config: Option<PathBuf>,
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Serves with live reload: watches the assets directory and reloads
+    /// open pages when a file changes
+    Dev(DevOpt),
+    /// Writes a self-signed certificate and key for local HTTPS
+    DevCert(DevCertOpt),
+}
+
+#[derive(StructOpt, Debug)]
+pub struct DevOpt {
+    /// Another directory to watch, e.g. the wasm-pack output; repeat for
+    /// several
+    #[structopt(long = "watch", parse(from_os_str))]
+    pub watch: Vec<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+pub struct DevCertOpt {
+    /// Where to write the PEM certificate
+    #[structopt(long, default_value = "dev-cert.pem", parse(from_os_str))]
+    pub cert: PathBuf,
+    /// Where to write the PEM private key
+    #[structopt(long, default_value = "dev-key.pem", parse(from_os_str))]
+    pub key: PathBuf,
+    /// Host name or IP address the certificate is valid for; repeat for
+    /// several. Defaults to localhost, 127.0.0.1 and ::1
+    #[structopt(long = "host")]
+    pub hosts: Vec<String>,
+    /// Overwrite existing files
+    #[structopt(long)]
+    pub force: bool,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct FileConfig {
+    bind: Option<String>,
+    port: Option<u16>,
+    workers: Option<usize>,
+    shutdown_timeout: Option<u64>,
+    assets_dir: Option<PathBuf>,
+    index_file: Option<String>,
+    log_level: Option<String>,
+    proxy: ProxyFileConfig,
+    mock: MockFileConfig,
+    security: SecurityFileConfig,
+    tls: TlsFileConfig,
+    admin: AdminFileConfig,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct AdminFileConfig {
+    port: Option<u16>,
+    health_path: Option<String>,
+    ready_path: Option<String>,
+    metrics_path: Option<String>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct TlsFileConfig {
+    cert: Option<PathBuf>,
+    key: Option<PathBuf>,
+    redirect_port: Option<u16>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct SecurityFileConfig {
+    enabled: Option<bool>,
+    content_security_policy: Option<String>,
+    frame_ancestors: Option<String>,
+    report_only: Option<bool>,
+    /// An empty string disables the header.
+    hsts: Option<String>,
+    referrer_policy: Option<String>,
+    permissions_policy: Option<String>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct ProxyFileConfig {
+    upstream: Option<String>,
+    paths: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct MockFileConfig {
+    enabled: bool,
+    fixture: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MockConfig {
+    pub fixture: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProxyConfig {
+    pub upstream: Upstream,
+    pub paths: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+    /// Plain HTTP port answering with redirects to `port`.
+    pub redirect_port: Option<u16>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DevConfig {
+    /// Watched on top of the assets directory.
+    pub watch: Vec<PathBuf>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AdminConfig {
+    /// Separate listener for the endpoints below; they are served with the
+    /// app when unset.
+    pub port: Option<u16>,
+    pub health_path: String,
+    pub ready_path: String,
+    pub metrics_path: String,
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            port: None,
+            health_path: DEFAULT_HEALTH_PATH.to_string(),
+            ready_path: DEFAULT_READY_PATH.to_string(),
+            metrics_path: DEFAULT_METRICS_PATH.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub bind: IpAddr,
+    pub port: u16,
+    pub workers: usize,
+    /// Seconds to drain requests in flight on shutdown.
+    pub shutdown_timeout: u64,
+    pub assets_dir: PathBuf,
+    pub index_file: String,
+    pub log_level: Option<String>,
+    pub proxy: Option<ProxyConfig>,
+    pub mock: Option<MockConfig>,
+    pub security: Option<SecurityHeaders>,
+    pub tls: Option<TlsConfig>,
+    pub admin: AdminConfig,
+    /// Set by `server dev`.
+    pub dev: Option<DevConfig>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    ReadFile(PathBuf, std::io::Error),
+    ParseFile(PathBuf, toml::de::Error),
+    InvalidBind(String),
+    InvalidWorkers,
+    InvalidUpstream(String),
+    InvalidProxyPath(String),
+    MockWithProxy,
+    MissingFixture(PathBuf),
+    MissingAssetsDir(PathBuf),
+    MissingIndexFile(PathBuf),
+    IncompleteTls,
+    MissingTlsFile(PathBuf),
+    RedirectWithoutTls,
+    RedirectOnServerPort,
+    InvalidAdminPath(String),
+    AdminPortInUse(u16),
+    MissingWatchDir(PathBuf),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::ReadFile(path, error) => {
+                write!(
+                    f,
+                    "could not read config file {}: {}",
+                    path.display(),
+                    error
+                )
+            }
+            ConfigError::ParseFile(path, error) => {
+                write!(f, "invalid config file {}: {}", path.display(), error)
+            }
+            ConfigError::InvalidBind(bind) => write!(f, "invalid bind address '{}'", bind),
+            ConfigError::InvalidWorkers => write!(f, "workers must be at least 1"),
+            ConfigError::InvalidUpstream(url) => {
+                write!(
+                    f,
+                    "invalid proxy upstream '{}', expected an http(s) URL",
+                    url
+                )
+            }
+            ConfigError::InvalidProxyPath(path) => {
+                write!(f, "invalid proxy path '{}', it must start with '/'", path)
+            }
+            ConfigError::MockWithProxy => {
+                write!(
+                    f,
+                    "the mock backend and the proxy cannot be enabled together"
+                )
+            }
+            ConfigError::MissingFixture(path) => {
+                write!(f, "mock fixture {} does not exist", path.display())
+            }
+            ConfigError::MissingAssetsDir(path) => {
+                write!(f, "assets directory {} does not exist", path.display())
+            }
+            ConfigError::MissingIndexFile(path) => {
+                write!(f, "index file {} does not exist", path.display())
+            }
+            ConfigError::IncompleteTls => {
+                write!(f, "HTTPS needs both a certificate and a private key")
+            }
+            ConfigError::MissingTlsFile(path) => {
+                write!(f, "TLS file {} does not exist", path.display())
+            }
+            ConfigError::RedirectWithoutTls => {
+                write!(f, "the HTTP redirect port needs HTTPS to be configured")
+            }
+            ConfigError::InvalidAdminPath(path) => {
+                write!(f, "invalid admin path '{}', it must start with '/'", path)
+            }
+            ConfigError::AdminPortInUse(port) => {
+                write!(
+                    f,
+                    "the admin port {} is already used by another listener",
+                    port
+                )
+            }
+            ConfigError::MissingWatchDir(path) => {
+                write!(f, "watched directory {} does not exist", path.display())
+            }
+            ConfigError::RedirectOnServerPort => {
+                write!(f, "the HTTP redirect port must differ from the HTTPS port")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::from_opt(Opt::from_args())
+    }
+
+    pub fn from_opt(opt: Opt) -> Result<Self, ConfigError> {
+        let file = match &opt.config {
+            Some(path) => read_file(path)?,
+            None => FileConfig::default(),
+        };
+
+        let bind = opt
+            .bind
+            .or(file.bind)
+            .unwrap_or_else(|| DEFAULT_BIND.to_string());
+        let tls = tls_config(opt.tls_cert, opt.tls_key, opt.http_redirect_port, file.tls)?;
+        let config = Config {
+            bind: bind.parse().map_err(|_| ConfigError::InvalidBind(bind))?,
+            port: opt.port.or(file.port).unwrap_or(DEFAULT_PORT),
+            workers: opt.workers.or(file.workers).unwrap_or(DEFAULT_WORKERS),
+            shutdown_timeout: opt
+                .shutdown_timeout
+                .or(file.shutdown_timeout)
+                .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT),
+            assets_dir: opt
+                .assets_dir
+                .or(file.assets_dir)
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_ASSETS_DIR)),
+            index_file: opt
+                .index_file
+                .or(file.index_file)
+                .unwrap_or_else(|| DEFAULT_INDEX_FILE.to_string()),
+            log_level: opt.log_level.or(file.log_level),
+            proxy: proxy_config(opt.proxy_upstream, opt.proxy_paths, file.proxy)?,
+            security: security_headers(
+                opt.no_security_headers,
+                opt.content_security_policy,
+                opt.csp_report_only,
+                tls.is_some(),
+                file.security,
+            ),
+            tls,
+            dev: match opt.command {
+                Some(Command::Dev(dev)) => Some(DevConfig { watch: dev.watch }),
+                _ => None,
+            },
+            admin: AdminConfig {
+                port: opt.admin_port.or(file.admin.port),
+                health_path: opt
+                    .health_path
+                    .or(file.admin.health_path)
+                    .unwrap_or_else(|| DEFAULT_HEALTH_PATH.to_string()),
+                ready_path: opt
+                    .ready_path
+                    .or(file.admin.ready_path)
+                    .unwrap_or_else(|| DEFAULT_READY_PATH.to_string()),
+                metrics_path: opt
+                    .metrics_path
+                    .or(file.admin.metrics_path)
+                    .unwrap_or_else(|| DEFAULT_METRICS_PATH.to_string()),
+            },
+            mock: if opt.mock_backend || file.mock.enabled {
+                Some(MockConfig {
+                    fixture: opt.mock_fixture.or(file.mock.fixture),
+                })
+            } else {
+                None
+            },
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Built-in defaults serving `assets_dir`, without reading the command
+    /// line, the environment or a config file.
+    pub fn with_assets_dir(assets_dir: PathBuf) -> Self {
+        Config {
+            bind: DEFAULT_BIND.parse().expect("valid default bind address"),
+            port: DEFAULT_PORT,
+            workers: DEFAULT_WORKERS,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            assets_dir,
+            index_file: DEFAULT_INDEX_FILE.to_string(),
+            log_level: None,
+            proxy: None,
+            mock: None,
+            security: Some(SecurityHeaders {
+                hsts: None,
+                ..SecurityHeaders::default()
+            }),
+            tls: None,
+            admin: AdminConfig::default(),
+            dev: None,
+        }
+    }
+
+    pub fn index_path(&self) -> PathBuf {
+        self.assets_dir.join(&self.index_file)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.workers == 0 {
+            return Err(ConfigError::InvalidWorkers);
+        }
+        // With embedded assets the directory only holds overrides.
+        let embedded = embedded::assets();
+        if !self.assets_dir.is_dir() && embedded.is_empty() {
+            return Err(ConfigError::MissingAssetsDir(self.assets_dir.clone()));
+        }
+        if !self.index_path().is_file() && !embedded.contains(&self.index_file) {
+            return Err(ConfigError::MissingIndexFile(self.index_path()));
+        }
+        if let Some(mock) = &self.mock {
+            if self.proxy.is_some() {
+                return Err(ConfigError::MockWithProxy);
+            }
+            if let Some(fixture) = mock.fixture.as_ref().filter(|fixture| !fixture.is_file()) {
+                return Err(ConfigError::MissingFixture(fixture.clone()));
+            }
+        }
+        if let Some(proxy) = &self.proxy {
+            if let Some(path) = proxy.paths.iter().find(|path| !path.starts_with('/')) {
+                return Err(ConfigError::InvalidProxyPath(path.clone()));
+            }
+        }
+        if let Some(tls) = &self.tls {
+            if let Some(missing) = [&tls.cert, &tls.key].iter().find(|path| !path.is_file()) {
+                return Err(ConfigError::MissingTlsFile(missing.to_path_buf()));
+            }
+            if tls.redirect_port == Some(self.port) {
+                return Err(ConfigError::RedirectOnServerPort);
+            }
+        }
+        if let Some(dev) = &self.dev {
+            if let Some(dir) = dev.watch.iter().find(|dir| !dir.is_dir()) {
+                return Err(ConfigError::MissingWatchDir(dir.clone()));
+            }
+        }
+        let admin = &self.admin;
+        if let Some(path) = [&admin.health_path, &admin.ready_path, &admin.metrics_path]
+            .iter()
+            .find(|path| !path.starts_with('/'))
+        {
+            return Err(ConfigError::InvalidAdminPath(path.to_string()));
+        }
+        if let Some(port) = admin.port {
+            let redirect_port = self.tls.as_ref().and_then(|tls| tls.redirect_port);
+            if port == self.port || Some(port) == redirect_port {
+                return Err(ConfigError::AdminPortInUse(port));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_file(path: &Path) -> Result<FileConfig, ConfigError> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| ConfigError::ReadFile(path.to_path_buf(), error))?;
+    toml::from_str(&contents).map_err(|error| ConfigError::ParseFile(path.to_path_buf(), error))
+}
+
+fn proxy_config(
+    upstream: Option<String>,
+    paths: Vec<String>,
+    file: ProxyFileConfig,
+) -> Result<Option<ProxyConfig>, ConfigError> {
+    let upstream = match upstream.or(file.upstream) {
+        Some(upstream) => Upstream::parse(&upstream).map_err(ConfigError::InvalidUpstream)?,
+        None => return Ok(None),
+    };
+    let paths = if paths.is_empty() {
+        file.paths.unwrap_or_else(|| {
+            DEFAULT_PROXY_PATHS
+                .iter()
+                .map(|path| path.to_string())
+                .collect()
+        })
+    } else {
+        paths
+    };
+    Ok(Some(ProxyConfig { upstream, paths }))
+}
+
+fn tls_config(
+    cert: Option<PathBuf>,
+    key: Option<PathBuf>,
+    redirect_port: Option<u16>,
+    file: TlsFileConfig,
+) -> Result<Option<TlsConfig>, ConfigError> {
+    let redirect_port = redirect_port.or(file.redirect_port);
+    match (cert.or(file.cert), key.or(file.key)) {
+        (Some(cert), Some(key)) => Ok(Some(TlsConfig {
+            cert,
+            key,
+            redirect_port,
+        })),
+        (None, None) if redirect_port.is_some() => Err(ConfigError::RedirectWithoutTls),
+        (None, None) => Ok(None),
+        _ => Err(ConfigError::IncompleteTls),
+    }
+}
+
+fn security_headers(
+    disabled: bool,
+    content_security_policy: Option<String>,
+    report_only: bool,
+    tls: bool,
+    file: SecurityFileConfig,
+) -> Option<SecurityHeaders> {
+    if disabled || file.enabled == Some(false) {
+        return None;
+    }
+    let defaults = SecurityHeaders::default();
+    Some(SecurityHeaders {
+        content_security_policy: content_security_policy
+            .or(file.content_security_policy)
+            .unwrap_or(defaults.content_security_policy),
+        frame_ancestors: file.frame_ancestors.unwrap_or(defaults.frame_ancestors),
+        report_only: report_only || file.report_only.unwrap_or(defaults.report_only),
+        // Browsers ignore HSTS over plain HTTP, and a server that cannot
+        // answer HTTPS must not pin its subdomains to it.
+        hsts: match file.hsts {
+            _ if !tls => None,
+            Some(hsts) if hsts.is_empty() => None,
+            Some(hsts) => Some(hsts),
+            None => defaults.hsts,
+        },
+        referrer_policy: file.referrer_policy.unwrap_or(defaults.referrer_policy),
+        permissions_policy: file
+            .permissions_policy
+            .unwrap_or(defaults.permissions_policy),
+    })
+}
@@ -0,0 +1,138 @@
+use actix_web::dev::BodyEncoding;
+use actix_web::http::{header, ContentEncoding};
+use actix_web::web::Bytes;
+use actix_web::{web, Error, HttpResponse};
+use futures::channel::mpsc::{self, UnboundedSender};
+use futures::StreamExt;
+use notify::{DebouncedEvent, RecursiveMode, Watcher};
+use std::io;
+use std::path::PathBuf;
+use std::sync::{mpsc as std_mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Server-sent events stream the injected script listens to.
+pub const LIVE_RELOAD_PATH: &str = "/__livereload";
+pub const LIVE_RELOAD_SCRIPT_PATH: &str = "/__livereload.js";
+
+const LIVE_RELOAD_SCRIPT: &str = r#"// Injected by `server dev`: reloads the page when a watched file changes.
+(function () {
+    const source = new EventSource("/__livereload");
+    source.addEventListener("reload", () => window.location.reload());
+})();
+"#;
+const SNIPPET: &str = r#"<script src="/__livereload.js"></script>"#;
+
+/// Replaces the caching service worker in dev mode. It unregisters itself
+/// so the browser stops answering from a stale precache.
+const SERVICE_WORKER: &str = r#"// Dev mode: drop the precache and step aside.
+self.addEventListener("install", () => self.skipWaiting());
+self.addEventListener("activate", (event) => {
+    event.waitUntil(
+        caches.keys()
+            .then((names) => Promise.all(names.map((name) => caches.delete(name))))
+            .then(() => self.registration.unregister())
+    );
+});
+"#;
+
+/// Batches the bursts of events an editor save or a wasm-pack build makes.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Open live reload streams, told to reload whenever a watched file
+/// changes.
+#[derive(Clone, Default)]
+pub struct LiveReload {
+    clients: Arc<Mutex<Vec<UnboundedSender<Bytes>>>>,
+}
+
+impl LiveReload {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn subscribe(&self) -> mpsc::UnboundedReceiver<Bytes> {
+        let (sender, receiver) = mpsc::unbounded();
+        let _ = sender.unbounded_send(Bytes::from_static(b": connected\n\n"));
+        self.lock().push(sender);
+        receiver
+    }
+
+    /// Sends a reload event to every client, forgetting the ones that went
+    /// away.
+    pub fn reload(&self) {
+        self.lock().retain(|client| {
+            client
+                .unbounded_send(Bytes::from_static(b"event: reload\ndata: \n\n"))
+                .is_ok()
+        });
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<Vec<UnboundedSender<Bytes>>> {
+        self.clients
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Watches `dirs` recursively on a background thread and reloads the
+    /// clients after each change.
+    pub fn watch(&self, dirs: &[PathBuf]) -> io::Result<()> {
+        let (sender, events) = std_mpsc::channel();
+        let mut watcher = notify::watcher(sender, DEBOUNCE).map_err(watch_error)?;
+        for dir in dirs {
+            watcher
+                .watch(dir, RecursiveMode::Recursive)
+                .map_err(watch_error)?;
+        }
+        let live_reload = self.clone();
+        thread::spawn(move || {
+            // Dropping the watcher would stop the events.
+            let _watcher = watcher;
+            for event in events {
+                match event {
+                    DebouncedEvent::NoticeWrite(_) | DebouncedEvent::NoticeRemove(_) => {}
+                    DebouncedEvent::Error(error, path) => {
+                        log::warn!("watch error on {:?}: {}", path, error)
+                    }
+                    _ => live_reload.reload(),
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+fn watch_error(error: notify::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error.to_string())
+}
+
+pub async fn serve_events(live_reload: web::Data<LiveReload>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache")
+        // Compressing would hold events back until a buffer fills.
+        .encoding(ContentEncoding::Identity)
+        .streaming(live_reload.subscribe().map(Ok::<_, Error>))
+}
+
+pub async fn serve_script() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/javascript")
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(LIVE_RELOAD_SCRIPT)
+}
+
+pub async fn serve_service_worker() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/javascript")
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(SERVICE_WORKER)
+}
+
+/// The index file with the live reload script added before `</body>`.
+pub fn inject_snippet(html: &str) -> String {
+    match html.rfind("</body>") {
+        Some(end) => format!("{}{}\n{}", &html[..end], SNIPPET, &html[end..]),
+        None => format!("{}{}\n", html, SNIPPET),
+    }
+}
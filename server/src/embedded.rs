@@ -0,0 +1,78 @@
+use once_cell::sync::Lazy;
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::Hasher;
+
+/// `static/` as it was at compile time, built in with the `embed` feature.
+#[cfg(feature = "embed")]
+#[derive(rust_embed::RustEmbed)]
+#[folder = "../static/"]
+struct StaticDir;
+
+static EMBEDDED: Lazy<Embedded> = Lazy::new(Embedded::load);
+
+/// The assets compiled into the binary. Empty unless the server was built
+/// with `--features embed`.
+pub fn assets() -> &'static Embedded {
+    &EMBEDDED
+}
+
+pub struct EmbeddedFile {
+    pub data: Cow<'static, [u8]>,
+    /// Quoted strong validator derived from the contents.
+    pub etag: String,
+}
+
+impl EmbeddedFile {
+    fn new(data: Cow<'static, [u8]>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&data);
+        Self {
+            etag: format!("\"{:016x}\"", hasher.finish()),
+            data,
+        }
+    }
+}
+
+/// Embedded files keyed by their path under `static/`, e.g.
+/// `css/bootstrap-4.5.0-dist/css/bootstrap.min.css`.
+pub struct Embedded {
+    files: HashMap<String, EmbeddedFile>,
+}
+
+impl Embedded {
+    #[cfg(feature = "embed")]
+    fn load() -> Self {
+        let files = StaticDir::iter()
+            .filter_map(|name| {
+                let data = StaticDir::get(&name)?;
+                Some((name.into_owned(), EmbeddedFile::new(data)))
+            })
+            .collect();
+        Self { files }
+    }
+
+    #[cfg(not(feature = "embed"))]
+    fn load() -> Self {
+        Self {
+            files: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&EmbeddedFile> {
+        self.files.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.files.contains_key(name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = (&str, &EmbeddedFile)> {
+        self.files.iter().map(|(name, file)| (name.as_str(), file))
+    }
+}
@@ -0,0 +1,15 @@
+pub mod access_log;
+pub mod admin;
+pub mod assets;
+pub mod config;
+pub mod dev;
+pub mod embedded;
+pub mod listeners;
+pub mod metrics;
+pub mod mock;
+pub mod proxy;
+pub mod pwa;
+pub mod request_id;
+pub mod security;
+pub mod shutdown;
+pub mod tls;
@@ -0,0 +1,39 @@
+use listenfd::ListenFd;
+use std::io;
+use std::net::TcpListener;
+
+/// Sockets handed over by systemd socket activation or `systemfd`, through
+/// `LISTEN_FDS`. They go in order to the listeners that are configured:
+/// the app, then the HTTP redirect, then the admin endpoints. A listener
+/// with no socket left binds its own.
+pub struct InheritedSockets {
+    fds: ListenFd,
+    next: usize,
+}
+
+impl InheritedSockets {
+    pub fn from_env() -> Self {
+        Self {
+            fds: ListenFd::from_env(),
+            next: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.fds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fds.len() == 0
+    }
+
+    /// The next inherited TCP socket, or `None` once they are used up.
+    pub fn next_tcp(&mut self) -> io::Result<Option<TcpListener>> {
+        if self.next >= self.fds.len() {
+            return Ok(None);
+        }
+        let listener = self.fds.take_tcp_listener(self.next)?;
+        self.next += 1;
+        Ok(listener)
+    }
+}
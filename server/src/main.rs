@@ -1,32 +1,220 @@
-use actix_files::NamedFile;
-use actix_web::{middleware, web, App, Error, HttpServer};
+use actix_web::client::Client;
+use actix_web::{middleware, web, App, HttpServer};
+use futures::future;
+use std::fmt::Display;
+use std::io::Write;
+use std::sync::Arc;
+use structopt::StructOpt;
 
-const ASSETS_DIR: &str = "../static";
+use server::access_log::{AccessLog, ACCESS_LOG_TARGET};
+use server::config::{Command, Config, DevCertOpt, Opt, DEFAULT_LOG_LEVEL};
+use server::dev::{self, LiveReload};
+use server::listeners::InheritedSockets;
+use server::metrics::Metrics;
+use server::mock::{self, MockBackend};
+use server::pwa::{serve_manifest, serve_service_worker, Pwa, MANIFEST_PATH, SERVICE_WORKER_PATH};
+use server::request_id::AssignRequestId;
+use server::tls::{self, CertificateResolver, HttpsPort, DEFAULT_DEV_HOSTS};
+use server::{admin, assets, proxy, shutdown};
 
-async fn serve_index_html() -> Result<NamedFile, Error> {
-    const INDEX_HTML: &str = "index.html";
-    let index_file = format!("{}/{}", ASSETS_DIR, INDEX_HTML);
+fn exit_with(error: impl Display) -> ! {
+    eprintln!("Error: {}", error);
+    std::process::exit(1);
+}
 
-    Ok(NamedFile::open(index_file)?)
+fn dev_cert(opt: &DevCertOpt) {
+    let hosts = if opt.hosts.is_empty() {
+        DEFAULT_DEV_HOSTS
+            .iter()
+            .map(|host| host.to_string())
+            .collect()
+    } else {
+        opt.hosts.clone()
+    };
+    tls::generate_dev_cert(&hosts, &opt.cert, &opt.key, opt.force)
+        .unwrap_or_else(|error| exit_with(error));
+    println!(
+        "Wrote {} and {} for {}",
+        opt.cert.display(),
+        opt.key.display(),
+        hosts.join(", ")
+    );
 }
 
 #[actix_rt::main]
 async fn main() -> std::io::Result<()> {
-    std::env::set_var("RUST_LOG", "actix_server=info,actix_web=info");
-    env_logger::init();
-
-    let localhost: &str = "0.0.0.0";
-    let port: u16 = 8000;
-    let addr = (localhost, port);
-
-    HttpServer::new(move || {
-        App::new()
-            .wrap(middleware::Logger::default())
-            .service(actix_files::Files::new("/", ASSETS_DIR).index_file("index.html"))
-            .default_service(web::get().to(serve_index_html))
-    })
-    .bind(addr)?
-    .workers(1)
-    .run()
-    .await
-}
\ No newline at end of file
+    let opt = Opt::from_args();
+    if let Some(Command::DevCert(dev_cert_opt)) = &opt.command {
+        dev_cert(dev_cert_opt);
+        return Ok(());
+    }
+    let config = Config::from_opt(opt).unwrap_or_else(|error| exit_with(error));
+
+    // A configured log level replaces RUST_LOG rather than adding to it.
+    let mut logger = match &config.log_level {
+        Some(log_level) => {
+            let mut logger = env_logger::Builder::new();
+            logger.parse_filters(log_level);
+            logger
+        }
+        None => env_logger::Builder::from_env(
+            env_logger::Env::default().default_filter_or(DEFAULT_LOG_LEVEL),
+        ),
+    };
+    logger
+        .format(|buf, record| {
+            // Access log lines are JSON objects, one per line.
+            if record.target() == ACCESS_LOG_TARGET {
+                writeln!(buf, "{}", record.args())
+            } else {
+                writeln!(
+                    buf,
+                    "[{} {} {}] {}",
+                    buf.timestamp(),
+                    record.level(),
+                    record.target(),
+                    record.args()
+                )
+            }
+        })
+        .init();
+
+    let addr = (config.bind, config.port);
+    let workers = config.workers;
+    let shutdown_timeout = config.shutdown_timeout;
+    let redirect_port = config.tls.as_ref().and_then(|tls| tls.redirect_port);
+    let certificates = match &config.tls {
+        Some(tls) => {
+            let resolver =
+                Arc::new(CertificateResolver::load(tls).unwrap_or_else(|error| exit_with(error)));
+            tls::reload_on_hangup(resolver.clone())?;
+            Some(resolver)
+        }
+        None => None,
+    };
+    let pwa = web::Data::new(Pwa::build(&config.assets_dir)?);
+    let mock = match &config.mock {
+        Some(mock) => Some(web::Data::new(MockBackend::from_fixture(
+            mock.fixture.as_deref(),
+        )?)),
+        None => None,
+    };
+    let live_reload = match &config.dev {
+        Some(dev) => {
+            let live_reload = LiveReload::new();
+            let mut watched = vec![config.assets_dir.clone()];
+            watched.extend(dev.watch.iter().cloned());
+            live_reload.watch(&watched)?;
+            log::info!("dev mode: watching {:?}", watched);
+            Some(web::Data::new(live_reload))
+        }
+        None => None,
+    };
+    let metrics = web::Data::new(Metrics::new(admin::metric_routes(&config)));
+    let admin_port = config.admin.port;
+    let config = web::Data::new(config);
+    let admin_config = config.clone();
+    let admin_metrics = metrics.clone();
+
+    let server = HttpServer::new(move || {
+        let security_headers = match &config.security {
+            Some(security) => security.middleware(),
+            None => middleware::DefaultHeaders::new(),
+        };
+        let mut app = App::new()
+            .wrap(metrics.middleware())
+            .wrap(security_headers)
+            .wrap(AccessLog)
+            .wrap(AssignRequestId)
+            .wrap(middleware::Compress::default())
+            .app_data(config.clone())
+            .app_data(metrics.clone())
+            .app_data(pwa.clone());
+        if admin_port.is_none() {
+            app = app.configure(admin::configure(&config));
+        }
+        if let Some(proxy) = &config.proxy {
+            app = app
+                .data(Client::new())
+                .data(proxy.upstream.clone())
+                .configure(proxy::configure(&proxy.paths));
+        }
+        if let Some(mock) = &mock {
+            app = app.app_data(mock.clone()).configure(mock::configure);
+        }
+        app = match &live_reload {
+            Some(live_reload) => app
+                .app_data(live_reload.clone())
+                .route(dev::LIVE_RELOAD_PATH, web::get().to(dev::serve_events))
+                .route(
+                    dev::LIVE_RELOAD_SCRIPT_PATH,
+                    web::get().to(dev::serve_script),
+                )
+                .route(
+                    SERVICE_WORKER_PATH,
+                    web::get().to(dev::serve_service_worker),
+                ),
+            None => app.route(SERVICE_WORKER_PATH, web::get().to(serve_service_worker)),
+        };
+        app.route(MANIFEST_PATH, web::get().to(serve_manifest))
+            .default_service(web::get().to(assets::serve))
+    });
+    let mut inherited = InheritedSockets::from_env();
+    if !inherited.is_empty() {
+        log::info!("using {} inherited socket(s)", inherited.len());
+    }
+    let server = match (inherited.next_tcp()?, &certificates) {
+        (Some(listener), Some(resolver)) => {
+            server.listen_rustls(listener, resolver.server_config())?
+        }
+        (Some(listener), None) => server.listen(listener)?,
+        (None, Some(resolver)) => server.bind_rustls(addr, resolver.server_config())?,
+        (None, None) => server.bind(addr)?,
+    }
+    .workers(workers)
+    .shutdown_timeout(shutdown_timeout)
+    .disable_signals()
+    .run();
+
+    let mut servers = vec![server];
+    if let Some(redirect_port) = redirect_port {
+        let https_port = HttpsPort(addr.1);
+        let redirect = HttpServer::new(move || {
+            App::new()
+                .wrap(AccessLog)
+                .wrap(AssignRequestId)
+                .data(https_port)
+                .default_service(web::to(tls::redirect))
+        });
+        let redirect = match inherited.next_tcp()? {
+            Some(listener) => redirect.listen(listener)?,
+            None => redirect.bind((addr.0, redirect_port))?,
+        }
+        .workers(1)
+        .shutdown_timeout(shutdown_timeout)
+        .disable_signals()
+        .run();
+        servers.push(redirect);
+    }
+    if let Some(admin_port) = admin_port {
+        let admin = HttpServer::new(move || {
+            App::new()
+                .wrap(AccessLog)
+                .wrap(AssignRequestId)
+                .app_data(admin_config.clone())
+                .app_data(admin_metrics.clone())
+                .configure(admin::configure(&admin_config))
+        });
+        let admin = match inherited.next_tcp()? {
+            Some(listener) => admin.listen(listener)?,
+            None => admin.bind((addr.0, admin_port))?,
+        }
+        .workers(1)
+        .shutdown_timeout(shutdown_timeout)
+        .disable_signals()
+        .run();
+        servers.push(admin);
+    }
+    shutdown::stop_on_signal(servers.clone())?;
+    future::try_join_all(servers).await.map(|_| ())
+}
@@ -0,0 +1,254 @@
+use actix_web::dev::{BodySize, MessageBody, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header;
+use actix_web::{web, Error, HttpResponse};
+use futures::future::{ok, Ready};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Upper bounds, in seconds, of the request duration histogram buckets.
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+/// Route label for static files, so every asset URL does not get its own
+/// time series.
+const ASSETS_ROUTE: &str = "assets";
+/// Route label for navigations answered with the index file.
+const APP_ROUTE: &str = "app";
+
+#[derive(Default)]
+struct RouteMetrics {
+    /// Request count per method and status class, e.g. `("GET", "2xx")`.
+    requests: BTreeMap<(String, &'static str), u64>,
+    /// Cumulative count per entry of `LATENCY_BUCKETS`.
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    latency_count: u64,
+    latency_sum: f64,
+    bytes: u64,
+}
+
+/// Request counters shared by every worker, rendered in the Prometheus
+/// text format.
+#[derive(Clone, Default)]
+pub struct Metrics {
+    routes: Arc<HashSet<String>>,
+    recorded: Arc<Mutex<BTreeMap<String, RouteMetrics>>>,
+}
+
+impl Metrics {
+    /// `routes` are the paths labelled as themselves; any other request is
+    /// counted under "assets" or "app".
+    pub fn new(routes: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            routes: Arc::new(routes.into_iter().collect()),
+            recorded: Arc::default(),
+        }
+    }
+
+    pub fn route_label(&self, path: &str) -> String {
+        if self.routes.contains(path) {
+            return path.to_string();
+        }
+        let has_extension = path
+            .rsplit('/')
+            .next()
+            .map_or(false, |file_name| file_name.contains('.'));
+        if has_extension {
+            ASSETS_ROUTE.to_string()
+        } else {
+            APP_ROUTE.to_string()
+        }
+    }
+
+    pub fn record(&self, route: String, method: &str, status: u16, elapsed: Duration, bytes: u64) {
+        let mut recorded = self
+            .recorded
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let metrics = recorded.entry(route).or_default();
+        *metrics
+            .requests
+            .entry((method.to_string(), status_class(status)))
+            .or_insert(0) += 1;
+
+        let seconds = elapsed.as_secs_f64();
+        for (bucket, bound) in metrics.buckets.iter_mut().zip(LATENCY_BUCKETS.iter()) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+        metrics.latency_count += 1;
+        metrics.latency_sum += seconds;
+        metrics.bytes += bytes;
+    }
+
+    pub fn render(&self) -> String {
+        let recorded = self
+            .recorded
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut out = String::new();
+
+        out.push_str("# HELP mystore_http_requests_total Requests handled, by route, method and status class.\n");
+        out.push_str("# TYPE mystore_http_requests_total counter\n");
+        for (route, metrics) in recorded.iter() {
+            for ((method, status), count) in &metrics.requests {
+                let _ = writeln!(
+                    out,
+                    "mystore_http_requests_total{{route=\"{}\",method=\"{}\",status=\"{}\"}} {}",
+                    escape(route),
+                    escape(method),
+                    status,
+                    count
+                );
+            }
+        }
+
+        out.push_str(
+            "# HELP mystore_http_request_duration_seconds Time to produce a response, by route.\n",
+        );
+        out.push_str("# TYPE mystore_http_request_duration_seconds histogram\n");
+        for (route, metrics) in recorded.iter() {
+            let route = escape(route);
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(metrics.buckets.iter()) {
+                let _ = writeln!(
+                    out,
+                    "mystore_http_request_duration_seconds_bucket{{route=\"{}\",le=\"{}\"}} {}",
+                    route, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "mystore_http_request_duration_seconds_bucket{{route=\"{}\",le=\"+Inf\"}} {}",
+                route, metrics.latency_count
+            );
+            let _ = writeln!(
+                out,
+                "mystore_http_request_duration_seconds_sum{{route=\"{}\"}} {}",
+                route, metrics.latency_sum
+            );
+            let _ = writeln!(
+                out,
+                "mystore_http_request_duration_seconds_count{{route=\"{}\"}} {}",
+                route, metrics.latency_count
+            );
+        }
+
+        out.push_str("# HELP mystore_http_response_bytes_total Response body bytes before compression, by route.\n");
+        out.push_str("# TYPE mystore_http_response_bytes_total counter\n");
+        for (route, metrics) in recorded.iter() {
+            let _ = writeln!(
+                out,
+                "mystore_http_response_bytes_total{{route=\"{}\"}} {}",
+                escape(route),
+                metrics.bytes
+            );
+        }
+        out
+    }
+
+    /// Middleware recording every request that goes through the app.
+    pub fn middleware(&self) -> RecordMetrics {
+        RecordMetrics {
+            metrics: self.clone(),
+        }
+    }
+}
+
+fn status_class(status: u16) -> &'static str {
+    match status / 100 {
+        1 => "1xx",
+        2 => "2xx",
+        3 => "3xx",
+        4 => "4xx",
+        _ => "5xx",
+    }
+}
+
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+pub async fn serve_metrics(metrics: web::Data<Metrics>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(metrics.render())
+}
+
+pub struct RecordMetrics {
+    metrics: Metrics,
+}
+
+impl<S, B> Transform<S> for RecordMetrics
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RecordMetricsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RecordMetricsMiddleware {
+            service,
+            metrics: self.metrics.clone(),
+        })
+    }
+}
+
+pub struct RecordMetricsMiddleware<S> {
+    service: S,
+    metrics: Metrics,
+}
+
+impl<S, B> Service for RecordMetricsMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: ServiceRequest) -> Self::Future {
+        let started = Instant::now();
+        let route = self.metrics.route_label(request.path());
+        let method = request.method().to_string();
+        let metrics = self.metrics.clone();
+        let response = self.service.call(request);
+        Box::pin(async move {
+            let response = response.await;
+            let (status, bytes) = match &response {
+                Ok(response) => {
+                    let bytes = match response.response().body().size() {
+                        BodySize::Sized(bytes) => bytes as u64,
+                        BodySize::Sized64(bytes) => bytes,
+                        _ => 0,
+                    };
+                    (response.status().as_u16(), bytes)
+                }
+                Err(error) => (error.as_response_error().status_code().as_u16(), 0),
+            };
+            metrics.record(route, &method, status, started.elapsed(), bytes);
+            response
+        })
+    }
+}
@@ -0,0 +1,136 @@
+use actix_web::http::{Cookie, StatusCode};
+use actix_web::{web, HttpRequest, HttpResponse};
+use juniper::http::GraphQLRequest;
+use serde_json::json;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+mod models;
+mod schema;
+mod store;
+
+use models::{LoginUser, RegisterUser};
+use schema::{create_schema, Context, Schema};
+use store::Store;
+
+const TOKEN_HEADER: &str = "x-csrf-token";
+const SESSION_COOKIE: &str = "mystore-session";
+/// Paths `configure` registers.
+pub const ROUTES: [&str; 4] = ["/register", "/login", "/logout", "/graphql"];
+
+/// Stand-in for the store backend: the same REST endpoints and GraphQL
+/// schema, backed by an in-memory `Store`.
+pub struct MockBackend {
+    store: Arc<Mutex<Store>>,
+    schema: Schema,
+}
+
+impl MockBackend {
+    pub fn new(store: Store) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(store)),
+            schema: create_schema(),
+        }
+    }
+
+    pub fn from_fixture(path: Option<&Path>) -> io::Result<Self> {
+        let store = match path {
+            Some(path) => Store::from_fixture(path)?,
+            None => Store::default(),
+        };
+        Ok(Self::new(store))
+    }
+
+    fn store(&self) -> MutexGuard<Store> {
+        self.store
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    config
+        .route("/register", web::post().to(register))
+        .route("/login", web::post().to(login))
+        .route("/logout", web::delete().to(logout))
+        .route("/graphql", web::post().to(graphql));
+}
+
+fn error(status: StatusCode, message: &str) -> HttpResponse {
+    HttpResponse::build(status).json(json!({ "errors": [{ "message": message }] }))
+}
+
+/// The session token, from the `x-csrf-token` header or the session cookie.
+fn session_token(request: &HttpRequest) -> Option<String> {
+    request
+        .headers()
+        .get(TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|token| token.to_string())
+        .or_else(|| {
+            request
+                .cookie(SESSION_COOKIE)
+                .map(|cookie| cookie.value().to_string())
+        })
+}
+
+async fn register(backend: web::Data<MockBackend>, form: web::Json<RegisterUser>) -> HttpResponse {
+    let mut store = backend.store();
+    match store.register(form.into_inner()) {
+        Ok(user) => HttpResponse::Ok().json(models::CurrentUser::from(user)),
+        Err(err) => error(StatusCode::BAD_REQUEST, &err.to_string()),
+    }
+}
+
+async fn login(backend: web::Data<MockBackend>, form: web::Json<LoginUser>) -> HttpResponse {
+    match backend.store().login(form.into_inner()) {
+        Ok((user, token)) => HttpResponse::Ok()
+            .header(TOKEN_HEADER, token.as_str())
+            .cookie(
+                Cookie::build(SESSION_COOKIE, token.clone())
+                    .path("/")
+                    .http_only(true)
+                    .finish(),
+            )
+            .json(user),
+        Err(err) => error(StatusCode::UNAUTHORIZED, &err.to_string()),
+    }
+}
+
+async fn logout(backend: web::Data<MockBackend>, request: HttpRequest) -> HttpResponse {
+    if let Some(token) = session_token(&request) {
+        backend.store().logout(&token);
+    }
+    HttpResponse::Ok()
+        .del_cookie(&Cookie::build(SESSION_COOKIE, "").path("/").finish())
+        .json("Logged out")
+}
+
+async fn graphql(
+    backend: web::Data<MockBackend>,
+    request: HttpRequest,
+    body: web::Json<GraphQLRequest>,
+) -> HttpResponse {
+    let token = match session_token(&request) {
+        Some(token) => token,
+        None => return error(StatusCode::UNAUTHORIZED, "Not logged in"),
+    };
+    let user_id = match backend.store().session(&token) {
+        Some(user_id) => user_id,
+        None => return error(StatusCode::UNAUTHORIZED, "Session expired"),
+    };
+    let context = Context {
+        store: backend.store.clone(),
+        user_id,
+    };
+    let response = body.execute(&backend.schema, &context);
+    let status = if response.is_ok() {
+        StatusCode::OK
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+    HttpResponse::build(status)
+        .header(TOKEN_HEADER, token.as_str())
+        .json(response)
+}
@@ -0,0 +1,257 @@
+use chrono::NaiveDate;
+use juniper::{GraphQLEnum, GraphQLInputObject, GraphQLObject};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub id: i32,
+    pub email: String,
+    pub company: String,
+    pub password: String,
+    #[serde(default = "now")]
+    pub created_at: String,
+}
+
+fn now() -> String {
+    chrono::Local::now().naive_local().to_string()
+}
+
+impl User {
+    pub fn new(id: i32, email: String, company: String, password: String) -> Self {
+        Self {
+            id,
+            email,
+            company,
+            password,
+            created_at: now(),
+        }
+    }
+}
+
+/// What `/register` and `/login` answer with; the client reads it as
+/// `CurrentUser`.
+#[derive(Serialize, Debug)]
+pub struct CurrentUser {
+    pub id: i32,
+    pub email: String,
+    pub company: String,
+    pub created_at: String,
+}
+
+impl From<&User> for CurrentUser {
+    fn from(user: &User) -> Self {
+        Self {
+            id: user.id,
+            email: user.email.clone(),
+            company: user.company.clone(),
+            created_at: user.created_at.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RegisterUser {
+    pub email: String,
+    pub company: String,
+    pub password: String,
+    pub password_confirmation: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LoginUser {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(GraphQLEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SaleState {
+    Draft,
+    Approved,
+    PartiallyPayed,
+    Payed,
+    Cancelled,
+}
+
+impl SaleState {
+    /// A sale is drafted, approved, then paid in one or several payments.
+    /// It can be cancelled until it is fully paid.
+    pub fn can_become(self, next: SaleState) -> bool {
+        use SaleState::*;
+        match (self, next) {
+            (Draft, Approved) => true,
+            (Approved, PartiallyPayed) => true,
+            (Approved, Payed) | (PartiallyPayed, Payed) => true,
+            (Draft, Cancelled) | (Approved, Cancelled) | (PartiallyPayed, Cancelled) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(GraphQLObject, Serialize, Deserialize, Clone, Debug)]
+#[graphql(description = "Product")]
+#[serde(rename_all = "camelCase")]
+pub struct Product {
+    pub id: i32,
+    pub name: String,
+    pub stock: f64,
+    pub cost: Option<i32>,
+    pub description: Option<String>,
+    pub user_id: i32,
+}
+
+#[derive(GraphQLObject, Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Price {
+    pub id: i32,
+    pub name: String,
+    pub user_id: i32,
+}
+
+#[derive(GraphQLObject, Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceProduct {
+    pub id: i32,
+    pub price_id: i32,
+    pub product_id: i32,
+    pub user_id: i32,
+    pub amount: Option<i32>,
+}
+
+#[derive(GraphQLObject, Serialize, Deserialize, Clone, Debug)]
+#[graphql(description = "Sale Bill")]
+#[serde(rename_all = "camelCase")]
+pub struct Sale {
+    pub id: i32,
+    pub user_id: i32,
+    pub sale_date: NaiveDate,
+    pub total: f64,
+    pub bill_number: Option<String>,
+    pub state: SaleState,
+}
+
+#[derive(GraphQLObject, Serialize, Deserialize, Clone, Debug)]
+#[graphql(description = "Relationship between sale and products")]
+#[serde(rename_all = "camelCase")]
+pub struct SaleProduct {
+    pub id: i32,
+    pub product_id: i32,
+    pub sale_id: i32,
+    pub amount: f64,
+    pub discount: i32,
+    pub tax: i32,
+    pub price: i32,
+    pub total: f64,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct FullPriceProduct {
+    pub price_product: PriceProduct,
+    pub price: Price,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct FullProduct {
+    pub product: Product,
+    pub price_products: Vec<FullPriceProduct>,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct FullSaleProduct {
+    pub sale_product: SaleProduct,
+    pub product: Product,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+pub struct FullSale {
+    pub sale: Sale,
+    pub sale_products: Vec<FullSaleProduct>,
+}
+
+#[derive(GraphQLObject, Debug)]
+pub struct ListPrice {
+    pub data: Vec<Price>,
+}
+
+#[derive(GraphQLObject, Debug)]
+pub struct ListProduct {
+    pub data: Vec<FullProduct>,
+}
+
+#[derive(GraphQLObject, Debug)]
+pub struct ListSale {
+    pub data: Vec<FullSale>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub struct FormPrice {
+    pub id: Option<i32>,
+    pub name: Option<String>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub struct FormPriceProduct {
+    pub id: Option<i32>,
+    pub price_id: i32,
+    pub product_id: Option<i32>,
+    pub user_id: Option<i32>,
+    pub amount: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub struct PriceProductToUpdate {
+    pub price_product: FormPriceProduct,
+    pub to_delete: bool,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub struct FormPriceProductsToUpdate {
+    pub data: Vec<PriceProductToUpdate>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub struct FormProduct {
+    pub id: Option<i32>,
+    pub name: Option<String>,
+    pub stock: Option<f64>,
+    pub cost: Option<i32>,
+    pub description: Option<String>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(description = "Sale Bill")]
+pub struct FormSale {
+    pub id: Option<i32>,
+    pub sale_date: Option<NaiveDate>,
+    pub user_id: Option<i32>,
+    pub total: Option<f64>,
+    pub bill_number: Option<String>,
+    pub state: Option<SaleState>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(description = "Relationship between sale and products")]
+pub struct FormSaleProduct {
+    pub id: Option<i32>,
+    pub product_id: Option<i32>,
+    pub sale_id: Option<i32>,
+    pub amount: Option<f64>,
+    pub discount: Option<i32>,
+    pub tax: Option<i32>,
+    pub price: Option<i32>,
+    pub total: Option<f64>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub struct FullFormSaleProduct {
+    pub sale_product: FormSaleProduct,
+    pub product: FormProduct,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub struct FormSaleProducts {
+    pub data: Vec<FullFormSaleProduct>,
+}
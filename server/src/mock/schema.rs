@@ -0,0 +1,159 @@
+use juniper::{FieldResult, RootNode};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::models::*;
+use super::store::Store;
+
+pub struct Context {
+    pub store: Arc<Mutex<Store>>,
+    pub user_id: i32,
+}
+
+impl juniper::Context for Context {}
+
+impl Context {
+    fn store(&self) -> MutexGuard<Store> {
+        self.store
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+pub struct QueryRoot;
+
+#[juniper::object(Context = Context)]
+impl QueryRoot {
+    fn dashboard(context: &Context) -> FieldResult<String> {
+        Ok(context.store().dashboard(context.user_id))
+    }
+
+    fn list_sale(context: &Context, search: Option<FormSale>, limit: i32) -> FieldResult<ListSale> {
+        Ok(context.store().list_sale(context.user_id, search, limit))
+    }
+
+    fn show_sale(context: &Context, sale_id: i32) -> FieldResult<FullSale> {
+        Ok(context.store().show_sale(context.user_id, sale_id)?)
+    }
+
+    fn list_product(
+        context: &Context,
+        search: String,
+        limit: i32,
+        rank: f64,
+    ) -> FieldResult<ListProduct> {
+        let _ = rank;
+        Ok(context
+            .store()
+            .list_product(context.user_id, &search, limit))
+    }
+
+    fn show_product(context: &Context, product_id: i32) -> FieldResult<FullProduct> {
+        Ok(context.store().show_product(context.user_id, product_id)?)
+    }
+
+    #[graphql(name = "ListPrice")]
+    fn list_price(context: &Context) -> FieldResult<ListPrice> {
+        Ok(context.store().list_price(context.user_id))
+    }
+
+    fn find_price(context: &Context, price_id: i32) -> FieldResult<Price> {
+        Ok(context.store().find_price(context.user_id, price_id)?)
+    }
+}
+
+pub struct MutationRoot;
+
+#[juniper::object(Context = Context)]
+impl MutationRoot {
+    fn create_sale(
+        context: &Context,
+        form: FormSale,
+        form_sale_products: FormSaleProducts,
+    ) -> FieldResult<FullSale> {
+        Ok(context
+            .store()
+            .create_sale(context.user_id, form, form_sale_products)?)
+    }
+
+    fn update_sale(
+        context: &Context,
+        form: FormSale,
+        form_sale_products: FormSaleProducts,
+    ) -> FieldResult<FullSale> {
+        Ok(context
+            .store()
+            .update_sale(context.user_id, form, form_sale_products)?)
+    }
+
+    fn approve_sale(context: &Context, sale_id: i32) -> FieldResult<bool> {
+        Ok(context
+            .store()
+            .transition_sale(context.user_id, sale_id, SaleState::Approved)?)
+    }
+
+    fn cancel_sale(context: &Context, sale_id: i32) -> FieldResult<bool> {
+        Ok(context
+            .store()
+            .transition_sale(context.user_id, sale_id, SaleState::Cancelled)?)
+    }
+
+    fn pay_sale(context: &Context, sale_id: i32) -> FieldResult<bool> {
+        Ok(context
+            .store()
+            .transition_sale(context.user_id, sale_id, SaleState::Payed)?)
+    }
+
+    fn partially_pay_sale(context: &Context, sale_id: i32) -> FieldResult<bool> {
+        Ok(context
+            .store()
+            .transition_sale(context.user_id, sale_id, SaleState::PartiallyPayed)?)
+    }
+
+    fn destroy_sale(context: &Context, sale_id: i32) -> FieldResult<bool> {
+        Ok(context.store().destroy_sale(context.user_id, sale_id)?)
+    }
+
+    fn create_product(
+        context: &Context,
+        form: FormProduct,
+        form_price_products: FormPriceProductsToUpdate,
+    ) -> FieldResult<FullProduct> {
+        Ok(context
+            .store()
+            .create_product(context.user_id, form, form_price_products)?)
+    }
+
+    fn update_product(
+        context: &Context,
+        form: FormProduct,
+        form_price_products: FormPriceProductsToUpdate,
+    ) -> FieldResult<FullProduct> {
+        Ok(context
+            .store()
+            .update_product(context.user_id, form, form_price_products)?)
+    }
+
+    fn destroy_product(context: &Context, product_id: i32) -> FieldResult<bool> {
+        Ok(context
+            .store()
+            .destroy_product(context.user_id, product_id)?)
+    }
+
+    fn create_price(context: &Context, form: FormPrice) -> FieldResult<Price> {
+        Ok(context.store().create_price(context.user_id, form)?)
+    }
+
+    fn update_price(context: &Context, form: FormPrice) -> FieldResult<Price> {
+        Ok(context.store().update_price(context.user_id, form)?)
+    }
+
+    fn destroy_price(context: &Context, price_id: i32) -> FieldResult<bool> {
+        Ok(context.store().destroy_price(context.user_id, price_id)?)
+    }
+}
+
+pub type Schema = RootNode<'static, QueryRoot, MutationRoot>;
+
+pub fn create_schema() -> Schema {
+    Schema::new(QueryRoot, MutationRoot)
+}
@@ -0,0 +1,586 @@
+use chrono::{Datelike, Local};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::path::Path;
+
+use super::models::*;
+
+#[derive(Debug, PartialEq)]
+pub struct StoreError(pub String);
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+fn not_found(what: &str, id: i32) -> StoreError {
+    StoreError(format!("{} {} not found", what, id))
+}
+
+fn next_id<T>(items: &[T], id: fn(&T) -> i32) -> i32 {
+    items.iter().map(id).max().unwrap_or(0) + 1
+}
+
+/// In-memory data of the mock backend. Everything but users is scoped by
+/// `user_id`, like the real backend does.
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct Store {
+    users: Vec<User>,
+    products: Vec<Product>,
+    prices: Vec<Price>,
+    price_products: Vec<PriceProduct>,
+    sales: Vec<Sale>,
+    sale_products: Vec<SaleProduct>,
+    #[serde(skip)]
+    sessions: HashMap<String, i32>,
+}
+
+impl Store {
+    /// Loads a JSON fixture with the same field names as the GraphQL schema,
+    /// plus a `users` list with plain-text passwords.
+    pub fn from_fixture(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+
+    pub fn register(&mut self, form: RegisterUser) -> Result<&User, StoreError> {
+        if form.password != form.password_confirmation {
+            return Err(StoreError(
+                "Password confirmation does not match".to_string(),
+            ));
+        }
+        if self.users.iter().any(|user| user.email == form.email) {
+            return Err(StoreError(format!("{} is already registered", form.email)));
+        }
+        let id = next_id(&self.users, |user| user.id);
+        self.users
+            .push(User::new(id, form.email, form.company, form.password));
+        Ok(&self.users[self.users.len() - 1])
+    }
+
+    /// Checks the credentials and opens a session, returning its token.
+    pub fn login(&mut self, form: LoginUser) -> Result<(CurrentUser, String), StoreError> {
+        let user = self
+            .users
+            .iter()
+            .find(|user| user.email == form.email && user.password == form.password)
+            .ok_or_else(|| StoreError("Invalid email or password".to_string()))?;
+        let token = new_token(user.id);
+        self.sessions.insert(token.clone(), user.id);
+        Ok((CurrentUser::from(user), token))
+    }
+
+    pub fn logout(&mut self, token: &str) {
+        self.sessions.remove(token);
+    }
+
+    pub fn session(&self, token: &str) -> Option<i32> {
+        self.sessions.get(token).copied()
+    }
+
+    /// The dashboard field is a JSON document serialized as a string.
+    pub fn dashboard(&self, user_id: i32) -> String {
+        let today = Local::today().naive_local();
+        let sales: Vec<&Sale> = self
+            .sales
+            .iter()
+            .filter(|sale| sale.user_id == user_id && sale.state != SaleState::Cancelled)
+            .collect();
+        let this_month = |sale: &&Sale| {
+            sale.sale_date.year() == today.year() && sale.sale_date.month() == today.month()
+        };
+        json!({
+            "todaySales": sales
+                .iter()
+                .filter(|sale| sale.sale_date == today)
+                .map(|sale| sale.total)
+                .sum::<f64>(),
+            "monthSales": sales
+                .iter()
+                .filter(|sale| this_month(sale))
+                .map(|sale| sale.total)
+                .sum::<f64>(),
+            "salesCount": sales.len(),
+            "productsCount": self
+                .products
+                .iter()
+                .filter(|product| product.user_id == user_id)
+                .count(),
+        })
+        .to_string()
+    }
+
+    pub fn list_sale(&self, user_id: i32, search: Option<FormSale>, limit: i32) -> ListSale {
+        let data = self
+            .sales
+            .iter()
+            .filter(|sale| sale.user_id == user_id)
+            .filter(|sale| match &search {
+                Some(search) => matches_sale(sale, search),
+                None => true,
+            })
+            .take(limit.max(0) as usize)
+            .map(|sale| self.full_sale(sale))
+            .collect();
+        ListSale { data }
+    }
+
+    pub fn show_sale(&self, user_id: i32, sale_id: i32) -> Result<FullSale, StoreError> {
+        self.find_sale(user_id, sale_id)
+            .map(|sale| self.full_sale(sale))
+    }
+
+    pub fn create_sale(
+        &mut self,
+        user_id: i32,
+        form: FormSale,
+        products: FormSaleProducts,
+    ) -> Result<FullSale, StoreError> {
+        let lines = self.sale_lines(user_id, products)?;
+        let id = next_id(&self.sales, |sale| sale.id);
+        self.sales.push(Sale {
+            id,
+            user_id,
+            sale_date: form
+                .sale_date
+                .unwrap_or_else(|| Local::today().naive_local()),
+            total: 0.0,
+            bill_number: form.bill_number,
+            state: SaleState::Draft,
+        });
+        self.replace_sale_products(user_id, id, lines, form.total)?;
+        self.show_sale(user_id, id)
+    }
+
+    pub fn update_sale(
+        &mut self,
+        user_id: i32,
+        form: FormSale,
+        products: FormSaleProducts,
+    ) -> Result<FullSale, StoreError> {
+        let id = form
+            .id
+            .ok_or_else(|| StoreError("Sale id is required".to_string()))?;
+        if self.find_sale(user_id, id)?.state != SaleState::Draft {
+            return Err(StoreError(format!("Sale {} is not a draft", id)));
+        }
+        let lines = self.sale_lines(user_id, products)?;
+        let sale = self.find_sale_mut(user_id, id)?;
+        if let Some(sale_date) = form.sale_date {
+            sale.sale_date = sale_date;
+        }
+        if form.bill_number.is_some() {
+            sale.bill_number = form.bill_number;
+        }
+        self.replace_sale_products(user_id, id, lines, form.total)?;
+        self.show_sale(user_id, id)
+    }
+
+    /// Moves a sale to `next`, refusing transitions `SaleState` does not allow.
+    pub fn transition_sale(
+        &mut self,
+        user_id: i32,
+        sale_id: i32,
+        next: SaleState,
+    ) -> Result<bool, StoreError> {
+        let sale = self.find_sale_mut(user_id, sale_id)?;
+        if !sale.state.can_become(next) {
+            return Err(StoreError(format!(
+                "Sale {} cannot go from {:?} to {:?}",
+                sale_id, sale.state, next
+            )));
+        }
+        sale.state = next;
+        Ok(true)
+    }
+
+    pub fn destroy_sale(&mut self, user_id: i32, sale_id: i32) -> Result<bool, StoreError> {
+        if self.find_sale(user_id, sale_id)?.state != SaleState::Draft {
+            return Err(StoreError(format!("Sale {} is not a draft", sale_id)));
+        }
+        self.sales.retain(|sale| sale.id != sale_id);
+        self.sale_products
+            .retain(|sale_product| sale_product.sale_id != sale_id);
+        Ok(true)
+    }
+
+    /// Products whose name contains `search`, case-insensitively. `rank` is
+    /// the real backend's full-text threshold and has no effect here.
+    pub fn list_product(&self, user_id: i32, search: &str, limit: i32) -> ListProduct {
+        let search = search.to_lowercase();
+        let data = self
+            .products
+            .iter()
+            .filter(|product| product.user_id == user_id)
+            .filter(|product| product.name.to_lowercase().contains(&search))
+            .take(limit.max(0) as usize)
+            .map(|product| self.full_product(product))
+            .collect();
+        ListProduct { data }
+    }
+
+    pub fn show_product(&self, user_id: i32, product_id: i32) -> Result<FullProduct, StoreError> {
+        self.find_product(user_id, product_id)
+            .map(|product| self.full_product(product))
+    }
+
+    pub fn create_product(
+        &mut self,
+        user_id: i32,
+        form: FormProduct,
+        price_products: FormPriceProductsToUpdate,
+    ) -> Result<FullProduct, StoreError> {
+        let id = next_id(&self.products, |product| product.id);
+        let name = form
+            .name
+            .ok_or_else(|| StoreError("Product name is required".to_string()))?;
+        self.check_price_products(user_id, id, &price_products)?;
+        self.products.push(Product {
+            id,
+            name,
+            stock: form.stock.unwrap_or(0.0),
+            cost: form.cost,
+            description: form.description,
+            user_id,
+        });
+        self.update_price_products(user_id, id, price_products);
+        self.show_product(user_id, id)
+    }
+
+    pub fn update_product(
+        &mut self,
+        user_id: i32,
+        form: FormProduct,
+        price_products: FormPriceProductsToUpdate,
+    ) -> Result<FullProduct, StoreError> {
+        let id = form
+            .id
+            .ok_or_else(|| StoreError("Product id is required".to_string()))?;
+        self.find_product(user_id, id)?;
+        self.check_price_products(user_id, id, &price_products)?;
+        let product = self
+            .products
+            .iter_mut()
+            .find(|product| product.id == id && product.user_id == user_id)
+            .ok_or_else(|| not_found("Product", id))?;
+        if let Some(name) = form.name {
+            product.name = name;
+        }
+        if let Some(stock) = form.stock {
+            product.stock = stock;
+        }
+        if form.cost.is_some() {
+            product.cost = form.cost;
+        }
+        if form.description.is_some() {
+            product.description = form.description;
+        }
+        self.update_price_products(user_id, id, price_products);
+        self.show_product(user_id, id)
+    }
+
+    pub fn destroy_product(&mut self, user_id: i32, product_id: i32) -> Result<bool, StoreError> {
+        self.find_product(user_id, product_id)?;
+        if self
+            .sale_products
+            .iter()
+            .any(|sale_product| sale_product.product_id == product_id)
+        {
+            return Err(StoreError(format!(
+                "Product {} is used by a sale",
+                product_id
+            )));
+        }
+        self.products.retain(|product| product.id != product_id);
+        self.price_products
+            .retain(|price_product| price_product.product_id != product_id);
+        Ok(true)
+    }
+
+    pub fn list_price(&self, user_id: i32) -> ListPrice {
+        let data = self
+            .prices
+            .iter()
+            .filter(|price| price.user_id == user_id)
+            .cloned()
+            .collect();
+        ListPrice { data }
+    }
+
+    pub fn find_price(&self, user_id: i32, price_id: i32) -> Result<Price, StoreError> {
+        self.prices
+            .iter()
+            .find(|price| price.id == price_id && price.user_id == user_id)
+            .cloned()
+            .ok_or_else(|| not_found("Price", price_id))
+    }
+
+    pub fn create_price(&mut self, user_id: i32, form: FormPrice) -> Result<Price, StoreError> {
+        let price = Price {
+            id: next_id(&self.prices, |price| price.id),
+            name: form
+                .name
+                .ok_or_else(|| StoreError("Price name is required".to_string()))?,
+            user_id,
+        };
+        self.prices.push(price.clone());
+        Ok(price)
+    }
+
+    pub fn update_price(&mut self, user_id: i32, form: FormPrice) -> Result<Price, StoreError> {
+        let id = form
+            .id
+            .ok_or_else(|| StoreError("Price id is required".to_string()))?;
+        let price = self
+            .prices
+            .iter_mut()
+            .find(|price| price.id == id && price.user_id == user_id)
+            .ok_or_else(|| not_found("Price", id))?;
+        if let Some(name) = form.name {
+            price.name = name;
+        }
+        Ok(price.clone())
+    }
+
+    pub fn destroy_price(&mut self, user_id: i32, price_id: i32) -> Result<bool, StoreError> {
+        self.find_price(user_id, price_id)?;
+        self.prices.retain(|price| price.id != price_id);
+        self.price_products
+            .retain(|price_product| price_product.price_id != price_id);
+        Ok(true)
+    }
+
+    fn find_sale(&self, user_id: i32, sale_id: i32) -> Result<&Sale, StoreError> {
+        self.sales
+            .iter()
+            .find(|sale| sale.id == sale_id && sale.user_id == user_id)
+            .ok_or_else(|| not_found("Sale", sale_id))
+    }
+
+    fn find_sale_mut(&mut self, user_id: i32, sale_id: i32) -> Result<&mut Sale, StoreError> {
+        self.sales
+            .iter_mut()
+            .find(|sale| sale.id == sale_id && sale.user_id == user_id)
+            .ok_or_else(|| not_found("Sale", sale_id))
+    }
+
+    fn find_product(&self, user_id: i32, product_id: i32) -> Result<&Product, StoreError> {
+        self.products
+            .iter()
+            .find(|product| product.id == product_id && product.user_id == user_id)
+            .ok_or_else(|| not_found("Product", product_id))
+    }
+
+    fn full_sale(&self, sale: &Sale) -> FullSale {
+        let sale_products = self
+            .sale_products
+            .iter()
+            .filter(|sale_product| sale_product.sale_id == sale.id)
+            .filter_map(|sale_product| {
+                let product = self
+                    .products
+                    .iter()
+                    .find(|product| product.id == sale_product.product_id)?;
+                Some(FullSaleProduct {
+                    sale_product: sale_product.clone(),
+                    product: product.clone(),
+                })
+            })
+            .collect();
+        FullSale {
+            sale: sale.clone(),
+            sale_products,
+        }
+    }
+
+    fn full_product(&self, product: &Product) -> FullProduct {
+        let price_products = self
+            .price_products
+            .iter()
+            .filter(|price_product| price_product.product_id == product.id)
+            .filter_map(|price_product| {
+                let price = self
+                    .prices
+                    .iter()
+                    .find(|price| price.id == price_product.price_id)?;
+                Some(FullPriceProduct {
+                    price_product: price_product.clone(),
+                    price: price.clone(),
+                })
+            })
+            .collect();
+        FullProduct {
+            product: product.clone(),
+            price_products,
+        }
+    }
+
+    /// The lines of a sale form, checked against the user's products before
+    /// anything is changed. Their ids are assigned when they are stored.
+    fn sale_lines(
+        &self,
+        user_id: i32,
+        products: FormSaleProducts,
+    ) -> Result<Vec<SaleProduct>, StoreError> {
+        products
+            .data
+            .into_iter()
+            .map(
+                |FullFormSaleProduct {
+                     sale_product,
+                     product,
+                 }| {
+                    let product_id = sale_product
+                        .product_id
+                        .or(product.id)
+                        .ok_or_else(|| StoreError("Sale product needs a product id".to_string()))?;
+                    self.find_product(user_id, product_id)?;
+                    let amount = sale_product.amount.unwrap_or(1.0);
+                    let price = sale_product.price.unwrap_or(0);
+                    Ok(SaleProduct {
+                        id: 0,
+                        product_id,
+                        sale_id: 0,
+                        amount,
+                        discount: sale_product.discount.unwrap_or(0),
+                        tax: sale_product.tax.unwrap_or(0),
+                        price,
+                        total: sale_product.total.unwrap_or(amount * f64::from(price)),
+                    })
+                },
+            )
+            .collect()
+    }
+
+    /// Replaces the lines of a sale and recomputes its total, unless the
+    /// form gives one explicitly.
+    fn replace_sale_products(
+        &mut self,
+        user_id: i32,
+        sale_id: i32,
+        lines: Vec<SaleProduct>,
+        total: Option<f64>,
+    ) -> Result<(), StoreError> {
+        self.sale_products
+            .retain(|sale_product| sale_product.sale_id != sale_id);
+        let lines_total = lines.iter().map(|line| line.total).sum();
+        for line in lines {
+            let id = next_id(&self.sale_products, |sale_product| sale_product.id);
+            self.sale_products.push(SaleProduct {
+                id,
+                sale_id,
+                ..line
+            });
+        }
+        let sale = self.find_sale_mut(user_id, sale_id)?;
+        sale.total = total.unwrap_or(lines_total);
+        Ok(())
+    }
+
+    /// Fails unless every price exists for the user and every price product
+    /// named by id belongs to the user and to `product_id`.
+    fn check_price_products(
+        &self,
+        user_id: i32,
+        product_id: i32,
+        price_products: &FormPriceProductsToUpdate,
+    ) -> Result<(), StoreError> {
+        for PriceProductToUpdate {
+            price_product,
+            to_delete,
+        } in &price_products.data
+        {
+            if let Some(id) = price_product.id {
+                self.price_products
+                    .iter()
+                    .find(|existing| is_owned(existing, id, user_id, product_id))
+                    .ok_or_else(|| not_found("Price product", id))?;
+            }
+            if !to_delete {
+                self.find_price(user_id, price_product.price_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies price product changes already passed by `check_price_products`.
+    fn update_price_products(
+        &mut self,
+        user_id: i32,
+        product_id: i32,
+        price_products: FormPriceProductsToUpdate,
+    ) {
+        for PriceProductToUpdate {
+            price_product,
+            to_delete,
+        } in price_products.data
+        {
+            if to_delete {
+                if let Some(id) = price_product.id {
+                    self.price_products
+                        .retain(|existing| !is_owned(existing, id, user_id, product_id));
+                }
+                continue;
+            }
+            let existing = self.price_products.iter_mut().find(|existing| {
+                existing.user_id == user_id
+                    && existing.product_id == product_id
+                    && (Some(existing.id) == price_product.id
+                        || existing.price_id == price_product.price_id)
+            });
+            match existing {
+                Some(existing) => existing.amount = price_product.amount,
+                None => {
+                    let id = next_id(&self.price_products, |existing| existing.id);
+                    self.price_products.push(PriceProduct {
+                        id,
+                        price_id: price_product.price_id,
+                        product_id,
+                        user_id,
+                        amount: price_product.amount,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn is_owned(price_product: &PriceProduct, id: i32, user_id: i32, product_id: i32) -> bool {
+    price_product.id == id
+        && price_product.user_id == user_id
+        && price_product.product_id == product_id
+}
+
+fn matches_sale(sale: &Sale, search: &FormSale) -> bool {
+    search.id.map_or(true, |id| sale.id == id)
+        && search.sale_date.map_or(true, |date| sale.sale_date == date)
+        && search.state.map_or(true, |state| sale.state == state)
+        && search.bill_number.as_ref().map_or(true, |bill_number| {
+            sale.bill_number.as_ref() == Some(bill_number)
+        })
+}
+
+fn new_token(user_id: i32) -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_i32(user_id);
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default(),
+    );
+    let first = hasher.finish();
+    hasher.write_u64(first);
+    format!("{:016x}{:016x}", first, hasher.finish())
+}
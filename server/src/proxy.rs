@@ -0,0 +1,102 @@
+use actix_web::client::Client;
+use actix_web::http::{header, HeaderName, Uri};
+use actix_web::{web, Error, HttpRequest, HttpResponse};
+
+/// Headers that only make sense for a single connection and must not be
+/// forwarded in either direction.
+const HOP_BY_HOP: [&str; 8] = [
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// The one API path called with DELETE rather than POST.
+const LOGOUT_PATH: &str = "/logout";
+
+#[derive(Clone, Debug)]
+pub struct Upstream {
+    base: String,
+}
+
+impl Upstream {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let uri: Uri = url.parse().map_err(|_| url.to_string())?;
+        match (uri.scheme_str(), uri.host()) {
+            (Some("http"), Some(_)) | (Some("https"), Some(_)) => Ok(Self {
+                base: url.trim_end_matches('/').to_string(),
+            }),
+            _ => Err(url.to_string()),
+        }
+    }
+
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    pub fn url_for(&self, request: &HttpRequest) -> String {
+        match request.uri().path_and_query() {
+            Some(path_and_query) => format!("{}{}", self.base, path_and_query),
+            None => format!("{}{}", self.base, request.path()),
+        }
+    }
+}
+
+fn is_forwarded(name: &HeaderName) -> bool {
+    !HOP_BY_HOP.contains(&name.as_str()) && name != header::HOST
+}
+
+/// Forwards the API `paths` upstream, for the methods the API uses only:
+/// `/login` and `/register` are app pages too, and a browser GET of them
+/// must still get the index. Expects `Upstream` and `Client` in the app data.
+pub fn configure(paths: &[String]) -> impl FnOnce(&mut web::ServiceConfig) + '_ {
+    move |service_config| {
+        for path in paths {
+            let mut resource = web::resource(path).route(web::post().to(forward));
+            if path == LOGOUT_PATH {
+                resource = resource.route(web::delete().to(forward));
+            }
+            service_config.service(resource);
+        }
+    }
+}
+
+/// Streams the request to the upstream backend and its response back,
+/// keeping cookies and the `x-csrf-token` and `x-request-id` headers intact.
+pub async fn forward(
+    request: HttpRequest,
+    payload: web::Payload,
+    upstream: web::Data<Upstream>,
+    client: web::Data<Client>,
+) -> Result<HttpResponse, Error> {
+    let mut forwarded = client
+        .request(request.method().clone(), upstream.url_for(&request))
+        .no_decompress();
+    for (name, value) in request
+        .headers()
+        .iter()
+        .filter(|(name, _)| is_forwarded(name))
+    {
+        forwarded = forwarded.header(name.clone(), value.clone());
+    }
+    if let Some(addr) = request.head().peer_addr {
+        forwarded = forwarded.header("x-forwarded-for", addr.ip().to_string());
+    }
+    forwarded = forwarded.header("x-forwarded-host", request.connection_info().host());
+
+    let response = forwarded.send_stream(payload).await?;
+
+    let mut proxied = HttpResponse::build(response.status());
+    for (name, value) in response
+        .headers()
+        .iter()
+        .filter(|(name, _)| is_forwarded(name))
+    {
+        proxied.header(name.clone(), value.clone());
+    }
+    Ok(proxied.streaming(response))
+}
@@ -0,0 +1,135 @@
+use actix_web::http::header;
+use actix_web::{web, HttpResponse};
+use serde_json::json;
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::Hasher;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::embedded;
+
+pub const MANIFEST_PATH: &str = "/manifest.webmanifest";
+pub const SERVICE_WORKER_PATH: &str = "/sw.js";
+
+const SERVICE_WORKER_TEMPLATE: &str = include_str!("sw.js");
+const ICON_PATH: &str = "/css/fontawesome-free-5.13.0-web/svgs/solid/store.svg";
+
+/// Web app manifest and service worker, generated once from the assets
+/// directory so the precache list and cache version follow what is deployed.
+pub struct Pwa {
+    manifest: String,
+    service_worker: String,
+}
+
+impl Pwa {
+    /// Precaches the files under `root` together with the embedded assets,
+    /// files on disk taking precedence.
+    pub fn build(root: &Path) -> io::Result<Self> {
+        let mut contents: BTreeMap<String, Cow<[u8]>> = embedded::assets()
+            .files()
+            .map(|(name, file)| (name.to_string(), Cow::Borrowed(file.data.as_ref())))
+            .collect();
+        if root.is_dir() {
+            let mut files = vec![];
+            collect_files(root, &mut files)?;
+            for file in files {
+                contents.insert(relative_url(root, &file), Cow::Owned(fs::read(file)?));
+            }
+        }
+
+        let mut hasher = DefaultHasher::new();
+        let mut urls = vec!["/".to_string()];
+        for (name, data) in contents.iter().filter(|(name, _)| is_precached(name)) {
+            let url = format!("/{}", name);
+            hasher.write(url.as_bytes());
+            hasher.write(data);
+            urls.push(url);
+        }
+        let cache_name = format!("mystore-{:016x}", hasher.finish());
+
+        let service_worker = SERVICE_WORKER_TEMPLATE
+            .replace("__CACHE_NAME__", &cache_name)
+            .replace("__PRECACHE_URLS__", &json!(urls).to_string());
+
+        Ok(Self {
+            manifest: manifest().to_string(),
+            service_worker,
+        })
+    }
+}
+
+fn manifest() -> serde_json::Value {
+    json!({
+        "name": "My Store",
+        "short_name": "My Store",
+        "start_url": "/",
+        "scope": "/",
+        "display": "standalone",
+        "background_color": "#ffffff",
+        "theme_color": "#17a2b8",
+        "icons": [{
+            "src": ICON_PATH,
+            "sizes": "any",
+            "type": "image/svg+xml"
+        }]
+    })
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn relative_url(root: &Path, file: &Path) -> String {
+    file.strip_prefix(root)
+        .unwrap_or(file)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The app shell: index.html, the wasm bundle and its JS glue at the top
+/// level, the app's own stylesheets directly under css/, minified vendor
+/// stylesheets and web fonts. Source maps, icon metadata and the individual
+/// SVG icons are left to the network.
+fn is_precached(url: &str) -> bool {
+    let top_level = !url.contains('/');
+    match Path::new(url)
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("html") | Some("wasm") => top_level,
+        Some("js") => top_level && format!("/{}", url) != SERVICE_WORKER_PATH,
+        Some("css") => {
+            url.ends_with(".min.css") || Path::new(url).parent() == Some(Path::new("css"))
+        }
+        Some("woff2") => true,
+        _ => false,
+    }
+}
+
+pub async fn serve_manifest(pwa: web::Data<Pwa>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/manifest+json")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .body(pwa.manifest.clone())
+}
+
+pub async fn serve_service_worker(pwa: web::Data<Pwa>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/javascript")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header("Service-Worker-Allowed", "/")
+        .body(pwa.service_worker.clone())
+}
@@ -0,0 +1,84 @@
+use actix_web::middleware::DefaultHeaders;
+
+/// Allows the yew bundle to compile its wasm module without opening up
+/// `eval` for JavaScript. `connect-src` includes the default API origin the
+/// client talks to when the server does not proxy it.
+pub const DEFAULT_CONTENT_SECURITY_POLICY: &str = "default-src 'self'; \
+     script-src 'self' 'wasm-unsafe-eval'; \
+     style-src 'self'; \
+     img-src 'self' data:; \
+     font-src 'self'; \
+     connect-src 'self' http://localhost:8088; \
+     manifest-src 'self'; \
+     worker-src 'self'; \
+     base-uri 'self'; \
+     form-action 'self'";
+pub const DEFAULT_FRAME_ANCESTORS: &str = "'none'";
+pub const DEFAULT_HSTS: &str = "max-age=31536000; includeSubDomains";
+pub const DEFAULT_REFERRER_POLICY: &str = "same-origin";
+pub const DEFAULT_PERMISSIONS_POLICY: &str =
+    "camera=(), microphone=(), geolocation=(), payment=(), usb=()";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SecurityHeaders {
+    pub content_security_policy: String,
+    pub frame_ancestors: String,
+    /// Sends the policy as `Content-Security-Policy-Report-Only`, so
+    /// violations are reported without being blocked.
+    pub report_only: bool,
+    /// Cleared by the config unless the server is serving TLS.
+    pub hsts: Option<String>,
+    pub referrer_policy: String,
+    pub permissions_policy: String,
+}
+
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        Self {
+            content_security_policy: DEFAULT_CONTENT_SECURITY_POLICY.to_string(),
+            frame_ancestors: DEFAULT_FRAME_ANCESTORS.to_string(),
+            report_only: false,
+            hsts: Some(DEFAULT_HSTS.to_string()),
+            referrer_policy: DEFAULT_REFERRER_POLICY.to_string(),
+            permissions_policy: DEFAULT_PERMISSIONS_POLICY.to_string(),
+        }
+    }
+}
+
+impl SecurityHeaders {
+    pub fn content_security_policy(&self) -> String {
+        format!(
+            "{}; frame-ancestors {}",
+            self.content_security_policy
+                .trim_end_matches(|c| c == ';' || c == ' '),
+            self.frame_ancestors
+        )
+    }
+
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        let csp_header = if self.report_only {
+            "Content-Security-Policy-Report-Only"
+        } else {
+            "Content-Security-Policy"
+        };
+        let mut headers = vec![
+            (csp_header, self.content_security_policy()),
+            ("X-Content-Type-Options", "nosniff".to_string()),
+            ("Referrer-Policy", self.referrer_policy.clone()),
+            ("Permissions-Policy", self.permissions_policy.clone()),
+        ];
+        if let Some(hsts) = &self.hsts {
+            headers.push(("Strict-Transport-Security", hsts.clone()));
+        }
+        headers
+    }
+
+    /// Adds the headers to every response that does not set them itself.
+    pub fn middleware(&self) -> DefaultHeaders {
+        self.headers()
+            .into_iter()
+            .fold(DefaultHeaders::new(), |middleware, (name, value)| {
+                middleware.header(name, value)
+            })
+    }
+}
@@ -0,0 +1,30 @@
+use actix_web::dev::Server;
+use futures::future::{self, Either};
+use std::io;
+use tokio::signal::unix::{signal, Signal, SignalKind};
+
+/// Stops `servers` on SIGTERM or SIGINT. They stop accepting connections
+/// at once, and requests in flight get up to the shutdown timeout to
+/// finish. A second signal stops them without waiting.
+///
+/// The servers must be built with `disable_signals`, or actix stops them
+/// on its own, SIGINT without draining.
+pub fn stop_on_signal(servers: Vec<Server>) -> io::Result<()> {
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    actix_rt::spawn(async move {
+        next_signal(&mut terminate, &mut interrupt).await;
+        log::info!("shutting down, draining requests in flight");
+        let graceful = future::join_all(servers.iter().map(|server| server.stop(true)));
+        let again = next_signal(&mut terminate, &mut interrupt);
+        if let Either::Right(_) = future::select(Box::pin(graceful), Box::pin(again)).await {
+            log::warn!("second signal, stopping now");
+            future::join_all(servers.iter().map(|server| server.stop(false))).await;
+        }
+    });
+    Ok(())
+}
+
+async fn next_signal(terminate: &mut Signal, interrupt: &mut Signal) {
+    future::select(Box::pin(terminate.recv()), Box::pin(interrupt.recv())).await;
+}
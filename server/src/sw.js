@@ -0,0 +1,43 @@
+// Generated by the server: the cache name and precache list are filled in
+// from the assets directory at startup.
+const CACHE_NAME = "__CACHE_NAME__";
+const PRECACHE_URLS = __PRECACHE_URLS__;
+
+self.addEventListener("install", (event) => {
+    event.waitUntil(
+        caches.open(CACHE_NAME)
+            .then((cache) => cache.addAll(PRECACHE_URLS))
+            .then(() => self.skipWaiting())
+    );
+});
+
+self.addEventListener("activate", (event) => {
+    event.waitUntil(
+        caches.keys()
+            .then((names) => Promise.all(
+                names
+                    .filter((name) => name.startsWith("mystore-") && name !== CACHE_NAME)
+                    .map((name) => caches.delete(name))
+            ))
+            .then(() => self.clients.claim())
+    );
+});
+
+self.addEventListener("fetch", (event) => {
+    const request = event.request;
+    if (request.method !== "GET" || new URL(request.url).origin !== self.location.origin) {
+        return;
+    }
+
+    if (request.mode === "navigate") {
+        // Routes are handled by the yew router, so every page is index.html.
+        event.respondWith(
+            fetch(request).catch(() => caches.match("/index.html"))
+        );
+        return;
+    }
+
+    event.respondWith(
+        caches.match(request).then((cached) => cached || fetch(request))
+    );
+});
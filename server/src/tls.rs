@@ -0,0 +1,227 @@
+use actix_web::http::header;
+use actix_web::{web, HttpRequest, HttpResponse};
+use rcgen::{CertificateParams, SanType};
+use rustls::internal::pemfile;
+use rustls::sign::{self, CertifiedKey};
+use rustls::{ClientHello, NoClientAuth, ResolvesServerCert, ServerConfig};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Write};
+use std::net::IpAddr;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tokio::signal::unix::{signal, SignalKind};
+
+use crate::config::TlsConfig;
+
+/// Names a generated development certificate is valid for when none are
+/// given.
+pub const DEFAULT_DEV_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "::1"];
+
+#[derive(Debug)]
+pub enum TlsError {
+    Read(PathBuf, io::Error),
+    Write(PathBuf, io::Error),
+    NoCertificates(PathBuf),
+    NoPrivateKey(PathBuf),
+    UnsupportedKey(PathBuf),
+    AlreadyExists(PathBuf),
+    Generate(rcgen::RcgenError),
+}
+
+impl fmt::Display for TlsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TlsError::Read(path, error) => {
+                write!(f, "could not read {}: {}", path.display(), error)
+            }
+            TlsError::Write(path, error) => {
+                write!(f, "could not write {}: {}", path.display(), error)
+            }
+            TlsError::NoCertificates(path) => {
+                write!(f, "no PEM certificate found in {}", path.display())
+            }
+            TlsError::NoPrivateKey(path) => {
+                write!(
+                    f,
+                    "no PKCS#8 or RSA private key found in {}",
+                    path.display()
+                )
+            }
+            TlsError::UnsupportedKey(path) => {
+                write!(f, "unsupported private key type in {}", path.display())
+            }
+            TlsError::AlreadyExists(path) => {
+                write!(
+                    f,
+                    "{} already exists, pass --force to overwrite it",
+                    path.display()
+                )
+            }
+            TlsError::Generate(error) => write!(f, "could not generate certificate: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for TlsError {}
+
+/// Hands rustls the certificate loaded from disk, and swaps it for a fresh
+/// copy on `reload` without restarting the server.
+pub struct CertificateResolver {
+    cert: PathBuf,
+    key: PathBuf,
+    current: RwLock<CertifiedKey>,
+}
+
+impl CertificateResolver {
+    pub fn load(config: &TlsConfig) -> Result<Self, TlsError> {
+        Ok(Self {
+            cert: config.cert.clone(),
+            key: config.key.clone(),
+            current: RwLock::new(certified_key(&config.cert, &config.key)?),
+        })
+    }
+
+    /// Re-reads the certificate and key. On error the previous pair stays
+    /// in use.
+    pub fn reload(&self) -> Result<(), TlsError> {
+        let key = certified_key(&self.cert, &self.key)?;
+        if let Ok(mut current) = self.current.write() {
+            *current = key;
+        }
+        Ok(())
+    }
+
+    pub fn server_config(self: &Arc<Self>) -> ServerConfig {
+        let mut config = ServerConfig::new(NoClientAuth::new());
+        config.cert_resolver = self.clone();
+        config
+    }
+}
+
+impl ResolvesServerCert for CertificateResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<CertifiedKey> {
+        self.current.read().ok().map(|current| current.clone())
+    }
+}
+
+fn certified_key(cert: &Path, key: &Path) -> Result<CertifiedKey, TlsError> {
+    let certs = pemfile::certs(&mut open(cert)?)
+        .ok()
+        .filter(|certs| !certs.is_empty())
+        .ok_or_else(|| TlsError::NoCertificates(cert.to_path_buf()))?;
+
+    let mut keys = pemfile::pkcs8_private_keys(&mut open(key)?).unwrap_or_default();
+    if keys.is_empty() {
+        keys = pemfile::rsa_private_keys(&mut open(key)?).unwrap_or_default();
+    }
+    let private_key = keys
+        .into_iter()
+        .next()
+        .ok_or_else(|| TlsError::NoPrivateKey(key.to_path_buf()))?;
+    let signing_key = sign::any_supported_type(&private_key)
+        .map_err(|_| TlsError::UnsupportedKey(key.to_path_buf()))?;
+
+    Ok(CertifiedKey::new(certs, Arc::new(signing_key)))
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, TlsError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|error| TlsError::Read(path.to_path_buf(), error))
+}
+
+/// Reloads the certificate every time the process receives SIGHUP, e.g.
+/// after a renewal job replaced the files.
+pub fn reload_on_hangup(resolver: Arc<CertificateResolver>) -> io::Result<()> {
+    let mut hangups = signal(SignalKind::hangup())?;
+    actix_rt::spawn(async move {
+        while hangups.recv().await.is_some() {
+            match resolver.reload() {
+                Ok(()) => log::info!("reloaded TLS certificate {}", resolver.cert.display()),
+                Err(error) => log::error!("keeping the current TLS certificate: {}", error),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Port the HTTPS listener is on, for building redirects.
+#[derive(Clone, Copy, Debug)]
+pub struct HttpsPort(pub u16);
+
+/// Answers every plain HTTP request with a permanent redirect to the same
+/// path over HTTPS. 308 keeps the method, so a stray POST is not turned
+/// into a GET.
+pub async fn redirect(request: HttpRequest, https_port: web::Data<HttpsPort>) -> HttpResponse {
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map_or_else(|| request.path(), |path_and_query| path_and_query.as_str());
+    let location = https_location(
+        request.connection_info().host(),
+        https_port.0,
+        path_and_query,
+    );
+    HttpResponse::PermanentRedirect()
+        .header(header::LOCATION, location)
+        .finish()
+}
+
+/// `https://` URL for `path_and_query` on `host`, with the port of the
+/// plain listener swapped for `port`.
+pub fn https_location(host: &str, port: u16, path_and_query: &str) -> String {
+    let host_name = match host.rfind(']') {
+        // Bracketed IPv6 address, with or without a port after it.
+        Some(end) => &host[..=end],
+        None => host.split(':').next().unwrap_or(host),
+    };
+    if port == 443 {
+        format!("https://{}{}", host_name, path_and_query)
+    } else {
+        format!("https://{}:{}{}", host_name, port, path_and_query)
+    }
+}
+
+/// Writes a self-signed certificate and its private key as PEM files, for
+/// serving HTTPS on a machine with no access to a certificate authority.
+pub fn generate_dev_cert(
+    hosts: &[String],
+    cert: &Path,
+    key: &Path,
+    force: bool,
+) -> Result<(), TlsError> {
+    if !force {
+        if let Some(existing) = [cert, key].iter().find(|path| path.exists()) {
+            return Err(TlsError::AlreadyExists(existing.to_path_buf()));
+        }
+    }
+
+    let mut params = CertificateParams::new(hosts.to_vec());
+    params.subject_alt_names = hosts
+        .iter()
+        .map(|host| match host.parse::<IpAddr>() {
+            Ok(ip) => SanType::IpAddress(ip),
+            Err(_) => SanType::DnsName(host.clone()),
+        })
+        .collect();
+    let certificate = rcgen::Certificate::from_params(params).map_err(TlsError::Generate)?;
+    let cert_pem = certificate.serialize_pem().map_err(TlsError::Generate)?;
+
+    fs::write(cert, cert_pem).map_err(|error| TlsError::Write(cert.to_path_buf(), error))?;
+    // The key is only readable by its owner. `mode` only applies to a new
+    // file, so an existing one is tightened before the key goes in.
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(key)
+        .and_then(|mut file| {
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+            file.write_all(certificate.serialize_private_key_pem().as_bytes())
+        })
+        .map_err(|error| TlsError::Write(key.to_path_buf(), error))?;
+    Ok(())
+}
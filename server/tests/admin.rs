@@ -0,0 +1,89 @@
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App};
+use std::path::PathBuf;
+
+use server::admin;
+use server::assets;
+use server::config::Config;
+use server::metrics::Metrics;
+
+fn static_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../static")
+}
+
+async fn get(config: Config, uris: &[&str]) -> Vec<(StatusCode, String)> {
+    let metrics = web::Data::new(Metrics::new(admin::metric_routes(&config)));
+    let config = web::Data::new(config);
+    let mut app = test::init_service(
+        App::new()
+            .wrap(metrics.middleware())
+            .app_data(config.clone())
+            .app_data(metrics.clone())
+            .configure(admin::configure(&config))
+            .default_service(web::get().to(assets::serve)),
+    )
+    .await;
+    let mut responses = vec![];
+    for uri in uris {
+        let response =
+            test::call_service(&mut app, test::TestRequest::get().uri(uri).to_request()).await;
+        let status = response.status();
+        let body = test::read_body(response).await;
+        responses.push((status, String::from_utf8(body.to_vec()).unwrap()));
+    }
+    responses
+}
+
+#[actix_rt::test]
+async fn healthz_answers_ok() {
+    let responses = get(Config::with_assets_dir(static_dir()), &["/healthz"]).await;
+
+    assert_eq!(responses[0], (StatusCode::OK, "ok".to_string()));
+}
+
+#[actix_rt::test]
+async fn readyz_checks_the_assets_dir() {
+    let responses = get(Config::with_assets_dir(static_dir()), &["/readyz"]).await;
+
+    assert_eq!(responses[0].0, StatusCode::OK);
+    assert!(responses[0].1.contains("\"assets\":\"ok\""));
+}
+
+#[cfg(not(feature = "embed"))]
+#[actix_rt::test]
+async fn readyz_fails_without_assets() {
+    let config = Config::with_assets_dir(static_dir().join("missing"));
+    let responses = get(config, &["/readyz"]).await;
+
+    assert_eq!(responses[0].0, StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[actix_rt::test]
+async fn paths_are_configurable() {
+    let mut config = Config::with_assets_dir(static_dir());
+    config.admin.health_path = "/-/alive".to_string();
+    let responses = get(config, &["/-/alive"]).await;
+
+    assert_eq!(responses[0].0, StatusCode::OK);
+}
+
+#[actix_rt::test]
+async fn metrics_count_requests_per_route_and_status_class() {
+    let responses = get(
+        Config::with_assets_dir(static_dir()),
+        &["/healthz", "/main.js", "/missing.wasm", "/metrics"],
+    )
+    .await;
+    let (status, metrics) = &responses[3];
+
+    assert_eq!(*status, StatusCode::OK);
+    assert!(metrics.contains(
+        "mystore_http_requests_total{route=\"/healthz\",method=\"GET\",status=\"2xx\"} 1"
+    ));
+    assert!(metrics
+        .contains("mystore_http_requests_total{route=\"assets\",method=\"GET\",status=\"2xx\"} 1"));
+    assert!(metrics
+        .contains("mystore_http_requests_total{route=\"assets\",method=\"GET\",status=\"4xx\"} 1"));
+    assert!(metrics.contains("mystore_http_request_duration_seconds_count{route=\"/healthz\"} 1"));
+    assert!(metrics.contains("mystore_http_response_bytes_total{route=\"/healthz\"} 2"));
+}
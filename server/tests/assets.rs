@@ -0,0 +1,140 @@
+use actix_web::dev::ServiceResponse;
+use actix_web::http::header;
+use actix_web::{test, web, App};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use server::assets;
+use server::config::Config;
+
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+fn assets_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mystore-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("index.html"),
+        "<html><head></head><body></body></html>",
+    )
+    .unwrap();
+    fs::write(dir.join("app-1a2b3c4d.js"), "plain").unwrap();
+    fs::write(dir.join("app-1a2b3c4d.js.br"), "brotli").unwrap();
+    fs::write(dir.join("app-1a2b3c4d.js.gz"), "gzip").unwrap();
+    dir
+}
+
+async fn get(dir: PathBuf, uri: &str, accept_encoding: Option<&str>) -> ServiceResponse {
+    let mut app = test::init_service(
+        App::new()
+            .data(Config::with_assets_dir(dir))
+            .default_service(web::get().to(assets::serve)),
+    )
+    .await;
+    let mut request = test::TestRequest::get().uri(uri);
+    if let Some(accept_encoding) = accept_encoding {
+        request = request.header(header::ACCEPT_ENCODING, accept_encoding);
+    }
+    test::call_service(&mut app, request.to_request()).await
+}
+
+fn header_value<'a>(response: &'a ServiceResponse, name: header::HeaderName) -> Option<&'a str> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+#[actix_rt::test]
+async fn serves_the_brotli_sibling_first() {
+    let response = get(
+        assets_dir("assets-br"),
+        "/app-1a2b3c4d.js",
+        Some("gzip, deflate, br"),
+    )
+    .await;
+
+    assert_eq!(
+        header_value(&response, header::CONTENT_ENCODING),
+        Some("br")
+    );
+    assert_eq!(
+        header_value(&response, header::VARY),
+        Some("Accept-Encoding")
+    );
+    assert_eq!(test::read_body(response).await, "brotli");
+}
+
+#[actix_rt::test]
+async fn serves_the_gzip_sibling_when_brotli_is_refused() {
+    let response = get(
+        assets_dir("assets-gz"),
+        "/app-1a2b3c4d.js",
+        Some("gzip, br;q=0"),
+    )
+    .await;
+
+    assert_eq!(
+        header_value(&response, header::CONTENT_ENCODING),
+        Some("gzip")
+    );
+    assert_eq!(test::read_body(response).await, "gzip");
+}
+
+#[actix_rt::test]
+async fn serves_the_plain_file_without_accept_encoding() {
+    let response = get(assets_dir("assets-plain"), "/app-1a2b3c4d.js", None).await;
+
+    assert_eq!(header_value(&response, header::CONTENT_ENCODING), None);
+    assert_eq!(
+        header_value(&response, header::VARY),
+        Some("Accept-Encoding")
+    );
+    assert_eq!(test::read_body(response).await, "plain");
+}
+
+#[actix_rt::test]
+async fn hashed_files_are_immutable_and_the_index_is_revalidated() {
+    let dir = assets_dir("assets-cache");
+    let hashed = get(dir.clone(), "/app-1a2b3c4d.js", None).await;
+    let index = get(dir, "/", None).await;
+
+    assert_eq!(
+        header_value(&hashed, header::CACHE_CONTROL),
+        Some(IMMUTABLE)
+    );
+    assert_eq!(
+        header_value(&index, header::CACHE_CONTROL),
+        Some("no-cache")
+    );
+}
+
+#[test]
+fn only_hash_segments_make_a_file_immutable() {
+    for name in &[
+        "app-1a2b3c4d.wasm",
+        "styles.1a2b3c4d.css",
+        "vendor_0123456789abcdef.js",
+    ] {
+        assert_eq!(
+            assets::cache_control(Path::new(name)),
+            IMMUTABLE,
+            "{}",
+            name
+        );
+    }
+    for name in &[
+        "report-20200601.css",
+        "release.12345678.js",
+        "deadbeefcafe.js",
+        "app-1a2b3c.js",
+        "index-1a2b3c4d.html",
+        "bootstrap.min.css",
+    ] {
+        assert_eq!(
+            assets::cache_control(Path::new(name)),
+            "no-cache",
+            "{}",
+            name
+        );
+    }
+}
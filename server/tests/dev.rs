@@ -0,0 +1,77 @@
+use actix_web::http::{header, StatusCode};
+use actix_web::{middleware, test, web, App};
+use std::path::PathBuf;
+
+use server::assets;
+use server::config::{Config, DevConfig};
+use server::dev::{self, LiveReload};
+
+fn static_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../static")
+}
+
+async fn index_html(config: Config) -> String {
+    let mut app = test::init_service(
+        App::new()
+            .data(config)
+            .default_service(web::get().to(assets::serve)),
+    )
+    .await;
+    let request = test::TestRequest::get()
+        .uri("/dashboard")
+        .header(header::ACCEPT, "text/html")
+        .to_request();
+    let body = test::read_response(&mut app, request).await;
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[test]
+fn snippet_goes_before_the_closing_body_tag() {
+    let html = dev::inject_snippet("<html><body><p>hi</p></body></html>");
+
+    assert_eq!(
+        html,
+        "<html><body><p>hi</p><script src=\"/__livereload.js\"></script>\n</body></html>"
+    );
+}
+
+#[actix_rt::test]
+async fn dev_mode_injects_the_live_reload_script() {
+    let mut config = Config::with_assets_dir(static_dir());
+    config.dev = Some(DevConfig { watch: vec![] });
+
+    assert!(index_html(config)
+        .await
+        .contains(dev::LIVE_RELOAD_SCRIPT_PATH));
+}
+
+#[actix_rt::test]
+async fn production_serves_the_index_untouched() {
+    let html = index_html(Config::with_assets_dir(static_dir())).await;
+
+    assert!(!html.contains(dev::LIVE_RELOAD_SCRIPT_PATH));
+}
+
+#[actix_rt::test]
+async fn events_are_streamed_uncompressed() {
+    let live_reload = web::Data::new(LiveReload::new());
+    let mut app = test::init_service(
+        App::new()
+            .wrap(middleware::Compress::default())
+            .app_data(live_reload.clone())
+            .route(dev::LIVE_RELOAD_PATH, web::get().to(dev::serve_events)),
+    )
+    .await;
+    let request = test::TestRequest::get()
+        .uri(dev::LIVE_RELOAD_PATH)
+        .header(header::ACCEPT_ENCODING, "gzip")
+        .to_request();
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE).unwrap(),
+        "text/event-stream"
+    );
+    assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+}
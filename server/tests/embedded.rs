@@ -0,0 +1,95 @@
+#![cfg(feature = "embed")]
+
+use actix_web::dev::ServiceResponse;
+use actix_web::http::{header, StatusCode};
+use actix_web::{test, web, App};
+use std::fs;
+use std::path::PathBuf;
+
+use server::assets;
+use server::config::Config;
+
+const BOOTSTRAP_CSS: &str = "/css/bootstrap-4.5.0-dist/css/bootstrap.min.css";
+
+fn override_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mystore-{}-{}", name, std::process::id()))
+}
+
+async fn get(assets_dir: PathBuf, request: test::TestRequest) -> ServiceResponse {
+    let mut app = test::init_service(
+        App::new()
+            .data(Config::with_assets_dir(assets_dir))
+            .default_service(web::get().to(assets::serve)),
+    )
+    .await;
+    test::call_service(&mut app, request.to_request()).await
+}
+
+#[actix_rt::test]
+async fn serves_embedded_files_without_an_assets_dir() {
+    let response = get(
+        override_dir("missing"),
+        test::TestRequest::get().uri(BOOTSTRAP_CSS),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE).unwrap(),
+        "text/css"
+    );
+    assert!(response.headers().contains_key(header::ETAG));
+}
+
+#[actix_rt::test]
+async fn answers_not_modified_for_a_matching_etag() {
+    let first = get(
+        override_dir("missing"),
+        test::TestRequest::get().uri(BOOTSTRAP_CSS),
+    )
+    .await;
+    let etag = first.headers().get(header::ETAG).unwrap().clone();
+
+    let second = get(
+        override_dir("missing"),
+        test::TestRequest::get()
+            .uri(BOOTSTRAP_CSS)
+            .header(header::IF_NONE_MATCH, etag),
+    )
+    .await;
+
+    assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[actix_rt::test]
+async fn falls_back_to_the_embedded_index_for_client_routes() {
+    let response = get(
+        override_dir("missing"),
+        test::TestRequest::get()
+            .uri("/dashboard")
+            .header(header::ACCEPT, "text/html"),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("text/html"));
+}
+
+#[actix_rt::test]
+async fn files_in_the_assets_dir_override_embedded_ones() {
+    let dir = override_dir("override");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("index.html"), "<p>hotfix</p>").unwrap();
+
+    let response = get(dir.clone(), test::TestRequest::get().uri("/")).await;
+    let body = test::read_body(response).await;
+
+    assert_eq!(body, "<p>hotfix</p>".as_bytes());
+    fs::remove_dir_all(dir).unwrap();
+}
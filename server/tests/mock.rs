@@ -0,0 +1,360 @@
+use actix_web::http::{header, StatusCode};
+use actix_web::{test, web, App};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+use server::mock::{self, MockBackend};
+
+const DEMO_EMAIL: &str = "demo@mystore.com";
+const DEMO_PASSWORD: &str = "12345678";
+
+fn demo_backend() -> web::Data<MockBackend> {
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/demo.json");
+    web::Data::new(MockBackend::from_fixture(Some(&fixture)).unwrap())
+}
+
+/// Sends one request to a fresh app sharing `backend`, so state carries
+/// over from one call to the next.
+async fn send(
+    backend: &web::Data<MockBackend>,
+    request: test::TestRequest,
+) -> (StatusCode, Option<String>, Value) {
+    let mut app = test::init_service(
+        App::new()
+            .app_data(backend.clone())
+            .configure(mock::configure),
+    )
+    .await;
+    let response = test::call_service(&mut app, request.to_request()).await;
+    let status = response.status();
+    let token = response
+        .headers()
+        .get("x-csrf-token")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = test::read_body(response).await;
+    (
+        status,
+        token,
+        serde_json::from_slice(&body).unwrap_or(Value::Null),
+    )
+}
+
+async fn login(backend: &web::Data<MockBackend>, email: &str, password: &str) -> String {
+    let (status, token, _) = send(
+        backend,
+        test::TestRequest::post()
+            .uri("/login")
+            .set_json(&json!({ "email": email, "password": password })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    token.expect("login issues a token")
+}
+
+/// Registers and logs in a second user, with no data of their own.
+async fn other_user(backend: &web::Data<MockBackend>) -> String {
+    let (status, _, _) = send(
+        backend,
+        test::TestRequest::post().uri("/register").set_json(&json!({
+            "email": "other@mystore.com",
+            "company": "Other Store",
+            "password": "secret",
+            "password_confirmation": "secret",
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    login(backend, "other@mystore.com", "secret").await
+}
+
+async fn graphql(backend: &web::Data<MockBackend>, token: &str, query: &str) -> Value {
+    let (_, _, body) = send(
+        backend,
+        test::TestRequest::post()
+            .uri("/graphql")
+            .header("x-csrf-token", token)
+            .set_json(&json!({ "query": query })),
+    )
+    .await;
+    body
+}
+
+fn error(response: &Value) -> &str {
+    response["errors"][0]["message"]
+        .as_str()
+        .unwrap_or_default()
+}
+
+async fn sale_state(backend: &web::Data<MockBackend>, token: &str, sale_id: i32) -> Value {
+    let response = graphql(
+        backend,
+        token,
+        &format!("{{ showSale(saleId: {}) {{ sale {{ state }} }} }}", sale_id),
+    )
+    .await;
+    response["data"]["showSale"]["sale"]["state"].clone()
+}
+
+#[actix_rt::test]
+async fn login_issues_a_csrf_token_and_session_cookie() {
+    let backend = demo_backend();
+    let mut app = test::init_service(
+        App::new()
+            .app_data(backend.clone())
+            .configure(mock::configure),
+    )
+    .await;
+    let request = test::TestRequest::post()
+        .uri("/login")
+        .set_json(&json!({ "email": DEMO_EMAIL, "password": DEMO_PASSWORD }))
+        .to_request();
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let token = response.headers().get("x-csrf-token").unwrap();
+    let cookie = response.headers().get(header::SET_COOKIE).unwrap();
+    assert!(cookie
+        .to_str()
+        .unwrap()
+        .starts_with(&format!("mystore-session={}", token.to_str().unwrap())));
+}
+
+#[actix_rt::test]
+async fn login_refuses_a_wrong_password() {
+    let (status, token, body) = send(
+        &demo_backend(),
+        test::TestRequest::post()
+            .uri("/login")
+            .set_json(&json!({ "email": DEMO_EMAIL, "password": "wrong" })),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(token, None);
+    assert_eq!(error(&body), "Invalid email or password");
+}
+
+#[actix_rt::test]
+async fn graphql_needs_a_session() {
+    let backend = demo_backend();
+    let (status, _, _) = send(
+        &backend,
+        test::TestRequest::post()
+            .uri("/graphql")
+            .set_json(&json!({ "query": "{ dashboard }" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let response = graphql(&backend, "not-a-token", "{ dashboard }").await;
+    assert_eq!(error(&response), "Session expired");
+}
+
+#[actix_rt::test]
+async fn serves_the_fixture_to_its_user() {
+    let backend = demo_backend();
+    let token = login(&backend, DEMO_EMAIL, DEMO_PASSWORD).await;
+    let response = graphql(
+        &backend,
+        &token,
+        "{ listSale(limit: 10) { data { sale { id total } } } }",
+    )
+    .await;
+
+    let sales = response["data"]["listSale"]["data"].as_array().unwrap();
+    assert_eq!(sales.len(), 3);
+    assert_eq!(sales[0]["sale"]["total"], 620.0);
+}
+
+#[actix_rt::test]
+async fn sales_move_through_allowed_states() {
+    let backend = demo_backend();
+    let token = login(&backend, DEMO_EMAIL, DEMO_PASSWORD).await;
+
+    let approved = graphql(&backend, &token, "mutation { approveSale(saleId: 3) }").await;
+    assert_eq!(approved["data"]["approveSale"], true);
+    let partially = graphql(&backend, &token, "mutation { partiallyPaySale(saleId: 3) }").await;
+    assert_eq!(partially["data"]["partiallyPaySale"], true);
+    let paid = graphql(&backend, &token, "mutation { paySale(saleId: 3) }").await;
+    assert_eq!(paid["data"]["paySale"], true);
+
+    assert_eq!(sale_state(&backend, &token, 3).await, "PAYED");
+}
+
+#[actix_rt::test]
+async fn sales_refuse_disallowed_states() {
+    let backend = demo_backend();
+    let token = login(&backend, DEMO_EMAIL, DEMO_PASSWORD).await;
+
+    let paid_draft = graphql(&backend, &token, "mutation { paySale(saleId: 3) }").await;
+    assert_eq!(error(&paid_draft), "Sale 3 cannot go from Draft to Payed");
+    let cancelled_paid = graphql(&backend, &token, "mutation { cancelSale(saleId: 1) }").await;
+    assert_eq!(
+        error(&cancelled_paid),
+        "Sale 1 cannot go from Payed to Cancelled"
+    );
+
+    assert_eq!(sale_state(&backend, &token, 3).await, "DRAFT");
+    assert_eq!(sale_state(&backend, &token, 1).await, "PAYED");
+}
+
+#[actix_rt::test]
+async fn users_only_see_and_change_their_own_data() {
+    let backend = demo_backend();
+    let token = other_user(&backend).await;
+
+    let sales = graphql(
+        &backend,
+        &token,
+        "{ listSale(limit: 10) { data { sale { id } } } }",
+    )
+    .await;
+    assert_eq!(sales["data"]["listSale"]["data"], json!([]));
+    let shown = graphql(&backend, &token, "{ showSale(saleId: 1) { sale { id } } }").await;
+    assert_eq!(error(&shown), "Sale 1 not found");
+    let destroyed = graphql(&backend, &token, "mutation { destroySale(saleId: 3) }").await;
+    assert_eq!(error(&destroyed), "Sale 3 not found");
+
+    let demo = login(&backend, DEMO_EMAIL, DEMO_PASSWORD).await;
+    assert_eq!(sale_state(&backend, &demo, 3).await, "DRAFT");
+}
+
+#[actix_rt::test]
+async fn price_products_of_other_products_are_left_alone() {
+    let backend = demo_backend();
+    let token = login(&backend, DEMO_EMAIL, DEMO_PASSWORD).await;
+
+    // Price product 1 belongs to product 1, not to product 2.
+    let response = graphql(
+        &backend,
+        &token,
+        r#"mutation {
+            updateProduct(
+                form: { id: 2, name: "Oat milk" },
+                formPriceProducts: { data: [
+                    { priceProduct: { id: 1, priceId: 1 }, toDelete: true }
+                ] }
+            ) { product { id } }
+        }"#,
+    )
+    .await;
+    assert_eq!(error(&response), "Price product 1 not found");
+
+    let coffee = graphql(
+        &backend,
+        &token,
+        "{ showProduct(productId: 1) { priceProducts { priceProduct { id } } } }",
+    )
+    .await;
+    assert_eq!(
+        coffee["data"]["showProduct"]["priceProducts"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+    let milk = graphql(
+        &backend,
+        &token,
+        "{ showProduct(productId: 2) { product { name } } }",
+    )
+    .await;
+    assert_eq!(milk["data"]["showProduct"]["product"]["name"], "Milk");
+}
+
+#[actix_rt::test]
+async fn failed_sale_creation_stores_nothing() {
+    let backend = demo_backend();
+    let token = login(&backend, DEMO_EMAIL, DEMO_PASSWORD).await;
+
+    let response = graphql(
+        &backend,
+        &token,
+        r#"mutation {
+            createSale(
+                form: { saleDate: "2020-06-04" },
+                formSaleProducts: { data: [
+                    { saleProduct: { productId: 1, amount: 1.0, price: 250 }, product: {} },
+                    { saleProduct: { productId: 99, amount: 1.0, price: 10 }, product: {} }
+                ] }
+            ) { sale { id } }
+        }"#,
+    )
+    .await;
+    assert_eq!(error(&response), "Product 99 not found");
+
+    let sales = graphql(
+        &backend,
+        &token,
+        "{ listSale(limit: 10) { data { sale { id } } } }",
+    )
+    .await;
+    assert_eq!(
+        sales["data"]["listSale"]["data"].as_array().unwrap().len(),
+        3
+    );
+}
+
+#[actix_rt::test]
+async fn failed_sale_update_keeps_the_old_lines() {
+    let backend = demo_backend();
+    let token = login(&backend, DEMO_EMAIL, DEMO_PASSWORD).await;
+
+    let response = graphql(
+        &backend,
+        &token,
+        r#"mutation {
+            updateSale(
+                form: { id: 3, billNumber: "0003" },
+                formSaleProducts: { data: [
+                    { saleProduct: { productId: 99, amount: 1.0, price: 10 }, product: {} }
+                ] }
+            ) { sale { id } }
+        }"#,
+    )
+    .await;
+    assert_eq!(error(&response), "Product 99 not found");
+
+    let sale = graphql(
+        &backend,
+        &token,
+        "{ showSale(saleId: 3) { sale { billNumber } saleProducts { saleProduct { productId } } } }",
+    )
+    .await;
+    assert_eq!(sale["data"]["showSale"]["sale"]["billNumber"], Value::Null);
+    assert_eq!(
+        sale["data"]["showSale"]["saleProducts"],
+        json!([{ "saleProduct": { "productId": 3 } }])
+    );
+}
+
+#[actix_rt::test]
+async fn failed_product_creation_stores_nothing() {
+    let backend = demo_backend();
+    let token = other_user(&backend).await;
+
+    // Price 1 belongs to the demo user.
+    let response = graphql(
+        &backend,
+        &token,
+        r#"mutation {
+            createProduct(
+                form: { name: "Tea" },
+                formPriceProducts: { data: [
+                    { priceProduct: { priceId: 1, amount: 100 }, toDelete: false }
+                ] }
+            ) { product { id } }
+        }"#,
+    )
+    .await;
+    assert_eq!(error(&response), "Price 1 not found");
+
+    let products = graphql(
+        &backend,
+        &token,
+        r#"{ listProduct(search: "", limit: 10, rank: 0.0) { data { product { id } } } }"#,
+    )
+    .await;
+    assert_eq!(products["data"]["listProduct"]["data"], json!([]));
+}
@@ -0,0 +1,27 @@
+use actix_web::{test, web, App};
+use std::path::PathBuf;
+
+use server::pwa::{serve_service_worker, Pwa, SERVICE_WORKER_PATH};
+
+async fn service_worker() -> String {
+    let static_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../static");
+    let mut app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(Pwa::build(&static_dir).unwrap()))
+            .route(SERVICE_WORKER_PATH, web::get().to(serve_service_worker)),
+    )
+    .await;
+    let request = test::TestRequest::get().uri(SERVICE_WORKER_PATH);
+    let body = test::read_body(test::call_service(&mut app, request.to_request()).await).await;
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[actix_rt::test]
+async fn precaches_the_app_stylesheets_and_minified_vendor_ones() {
+    let service_worker = service_worker().await;
+
+    assert!(service_worker.contains(r#""/css/print.css""#));
+    assert!(service_worker.contains(r#""/css/bootstrap-4.5.0-dist/css/bootstrap.min.css""#));
+    assert!(!service_worker.contains(r#""/css/bootstrap-4.5.0-dist/css/bootstrap.css""#));
+    assert!(!service_worker.contains(".css.map"));
+}
@@ -0,0 +1,57 @@
+use actix_web::http::header::HeaderValue;
+use actix_web::{test, web, App, HttpRequest, HttpResponse};
+
+use server::request_id::{request_id, AssignRequestId, REQUEST_ID_HEADER};
+
+/// Answers with the id the handler sees in the request headers.
+async fn echo(request: HttpRequest) -> HttpResponse {
+    let header = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    assert_eq!(request_id(&request).as_deref(), Some(header.as_str()));
+    HttpResponse::Ok().body(header)
+}
+
+async fn call(incoming: Option<&str>) -> (Option<HeaderValue>, String) {
+    let mut app = test::init_service(
+        App::new()
+            .wrap(AssignRequestId)
+            .route("/", web::get().to(echo)),
+    )
+    .await;
+    let mut request = test::TestRequest::get().uri("/");
+    if let Some(id) = incoming {
+        request = request.header(REQUEST_ID_HEADER, id);
+    }
+    let response = test::call_service(&mut app, request.to_request()).await;
+    let echoed = response.headers().get(REQUEST_ID_HEADER).cloned();
+    let body = test::read_body(response).await;
+    (echoed, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[actix_rt::test]
+async fn generates_an_id_when_none_is_sent() {
+    let (echoed, seen) = call(None).await;
+
+    assert_eq!(seen.len(), 36);
+    assert_eq!(echoed.unwrap(), seen.as_str());
+}
+
+#[actix_rt::test]
+async fn propagates_an_incoming_id() {
+    let (echoed, seen) = call(Some("client-42.a_b")).await;
+
+    assert_eq!(seen, "client-42.a_b");
+    assert_eq!(echoed.unwrap(), "client-42.a_b");
+}
+
+#[actix_rt::test]
+async fn replaces_a_malformed_id() {
+    let (echoed, seen) = call(Some("not ok\"")).await;
+
+    assert_ne!(seen, "not ok\"");
+    assert_eq!(echoed.unwrap(), seen.as_str());
+}
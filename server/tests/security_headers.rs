@@ -0,0 +1,95 @@
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App, HttpResponse};
+use std::fs;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+use server::config::{Config, Opt};
+use server::security::SecurityHeaders;
+use server::tls;
+
+fn static_dir() -> String {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../static")
+        .display()
+        .to_string()
+}
+
+async fn headers_for(security: SecurityHeaders) -> actix_web::dev::ServiceResponse {
+    let mut app = test::init_service(
+        App::new()
+            .wrap(security.middleware())
+            .route("/", web::get().to(|| HttpResponse::Ok())),
+    )
+    .await;
+    test::call_service(&mut app, test::TestRequest::get().uri("/").to_request()).await
+}
+
+#[actix_rt::test]
+async fn adds_default_security_headers() {
+    let response = headers_for(SecurityHeaders::default()).await;
+    let headers = response.headers();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let csp = headers
+        .get("content-security-policy")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(csp.contains("'wasm-unsafe-eval'"));
+    assert!(csp.ends_with("frame-ancestors 'none'"));
+    assert_eq!(headers.get("x-content-type-options").unwrap(), "nosniff");
+    assert!(headers.contains_key("strict-transport-security"));
+    assert!(headers.contains_key("referrer-policy"));
+    assert!(headers.contains_key("permissions-policy"));
+}
+
+#[actix_rt::test]
+async fn report_only_mode_uses_the_report_only_header() {
+    let response = headers_for(SecurityHeaders {
+        report_only: true,
+        hsts: None,
+        ..SecurityHeaders::default()
+    })
+    .await;
+    let headers = response.headers();
+
+    assert!(headers.contains_key("content-security-policy-report-only"));
+    assert!(!headers.contains_key("content-security-policy"));
+    assert!(!headers.contains_key("strict-transport-security"));
+}
+
+#[test]
+fn hsts_is_not_sent_over_plain_http() {
+    let opt = Opt::from_iter(&["server", "--assets-dir", &static_dir()]);
+    let security = Config::from_opt(opt).unwrap().security.unwrap();
+
+    assert_eq!(security.hsts, None);
+    assert!(!security
+        .headers()
+        .iter()
+        .any(|(name, _)| *name == "Strict-Transport-Security"));
+}
+
+#[test]
+fn hsts_is_sent_with_tls() {
+    let dir = std::env::temp_dir().join(format!("mystore-hsts-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let (cert, key) = (dir.join("cert.pem"), dir.join("key.pem"));
+    tls::generate_dev_cert(&["localhost".to_string()], &cert, &key, true).unwrap();
+    let opt = Opt::from_iter(&[
+        "server",
+        "--assets-dir",
+        &static_dir(),
+        "--tls-cert",
+        &cert.display().to_string(),
+        "--tls-key",
+        &key.display().to_string(),
+    ]);
+    let security = Config::from_opt(opt).unwrap().security.unwrap();
+
+    assert_eq!(
+        security.hsts.as_deref(),
+        Some(server::security::DEFAULT_HSTS)
+    );
+}
@@ -0,0 +1,209 @@
+use actix_web::dev::ServiceResponse;
+use actix_web::http::{header, StatusCode};
+use actix_web::{test, web, App};
+use std::path::PathBuf;
+
+use server::assets;
+use server::config::{Config, MockConfig};
+use server::proxy;
+
+const BOOTSTRAP_CSS: &str = "/css/bootstrap-4.5.0-dist/css/bootstrap.min.css";
+
+fn static_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../static")
+}
+
+async fn get(uri: &str, accept: Option<&str>) -> ServiceResponse {
+    get_with(Config::with_assets_dir(static_dir()), uri, accept).await
+}
+
+async fn get_with(config: Config, uri: &str, accept: Option<&str>) -> ServiceResponse {
+    let mut app = test::init_service(
+        App::new()
+            .data(config)
+            .default_service(web::get().to(assets::serve)),
+    )
+    .await;
+    let mut request = test::TestRequest::get().uri(uri);
+    if let Some(accept) = accept {
+        request = request.header(header::ACCEPT, accept);
+    }
+    test::call_service(&mut app, request.to_request()).await
+}
+
+fn content_type(response: &ServiceResponse) -> String {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[actix_rt::test]
+async fn serves_existing_assets() {
+    let response = get(BOOTSTRAP_CSS, Some("text/css,*/*;q=0.1")).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(content_type(&response).starts_with("text/css"));
+}
+
+#[actix_rt::test]
+async fn serves_index_for_the_root() {
+    let response = get("/", Some("text/html")).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(content_type(&response).starts_with("text/html"));
+    assert_eq!(
+        response.headers().get(header::CACHE_CONTROL).unwrap(),
+        "no-cache"
+    );
+}
+
+#[actix_rt::test]
+async fn falls_back_to_index_for_html_navigation() {
+    let response = get("/dashboard", Some("text/html,application/xhtml+xml")).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(content_type(&response).starts_with("text/html"));
+}
+
+#[actix_rt::test]
+async fn falls_back_to_index_for_app_routes_without_accept() {
+    for uri in &[
+        "/login",
+        "/register",
+        "/dashboard/sales",
+        "/reports",
+        "/sales/42",
+        "/products/import",
+        "/prices",
+        "/pos",
+        "/page-not-found",
+    ] {
+        let response = get(uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+        assert!(content_type(&response).starts_with("text/html"), "{}", uri);
+    }
+}
+
+#[actix_rt::test]
+async fn falls_back_to_index_for_paths_without_extension() {
+    let response = get("/sales/42", Some("*/*")).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(content_type(&response).starts_with("text/html"));
+}
+
+#[actix_rt::test]
+async fn missing_wasm_is_not_found() {
+    let response = get("/pkg/app_bg.wasm", Some("*/*")).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_rt::test]
+async fn misspelled_stylesheet_is_not_found() {
+    let response = get(
+        "/css/bootstrap-4.5.0-dist/css/bootstrap.mni.css",
+        Some("text/css,*/*;q=0.1"),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_rt::test]
+async fn app_route_prefix_must_match_a_whole_segment() {
+    let response = get("/login.js", Some("*/*")).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_rt::test]
+async fn does_not_escape_the_assets_directory() {
+    let response = get("/../server/Cargo.toml", Some("*/*")).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_rt::test]
+async fn index_calls_the_api_same_origin_when_the_backend_is_mocked() {
+    let mut config = Config::with_assets_dir(static_dir());
+    config.mock = Some(MockConfig { fixture: None });
+    let response = get_with(config, "/dashboard", Some("text/html")).await;
+    let body = test::read_body(response).await;
+
+    assert!(String::from_utf8(body.to_vec())
+        .unwrap()
+        .contains(r#"<meta name="mystore-api-url" content="">"#));
+}
+
+#[actix_rt::test]
+async fn proxied_api_paths_still_serve_their_app_pages() {
+    let paths: Vec<String> = ["/graphql", "/login", "/register", "/logout"]
+        .iter()
+        .map(|path| path.to_string())
+        .collect();
+    let mut app = test::init_service(
+        App::new()
+            .data(Config::with_assets_dir(static_dir()))
+            .configure(proxy::configure(&paths))
+            .default_service(web::get().to(assets::serve)),
+    )
+    .await;
+    for path in &["/login", "/register"] {
+        let request = test::TestRequest::get()
+            .uri(path)
+            .header(header::ACCEPT, "text/html")
+            .to_request();
+        let response = test::call_service(&mut app, request).await;
+
+        assert_eq!(response.status(), StatusCode::OK, "{}", path);
+        assert!(content_type(&response).starts_with("text/html"), "{}", path);
+    }
+}
+
+#[actix_rt::test]
+async fn index_keeps_the_default_api_url_without_a_backend() {
+    let response = get("/", Some("text/html")).await;
+    let body = test::read_body(response).await;
+
+    assert!(!String::from_utf8(body.to_vec())
+        .unwrap()
+        .contains("mystore-api-url"));
+}
+
+#[test]
+fn api_url_meta_goes_before_the_closing_head_tag() {
+    assert_eq!(
+        assets::inject_api_url("<html><head><title>x</title></head></html>"),
+        "<html><head><title>x</title><meta name=\"mystore-api-url\" content=\"\">\n</head></html>"
+    );
+}
+
+#[test]
+fn app_routes_match_their_sub_paths_and_parameters() {
+    assert!(assets::is_app_route("/sales/v1.2"));
+    assert!(assets::is_app_route("/products/import"));
+    assert!(assets::is_app_route("/dashboard/sales"));
+    assert!(!assets::is_app_route("/salesman.js"));
+    assert!(!assets::is_app_route("/css/print.min.css"));
+}
+
+#[test]
+fn app_routes_follow_the_client_router() {
+    let routing = std::fs::read_to_string(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../src/routing.rs"),
+    )
+    .unwrap();
+    let client_routes: Vec<&str> = routing
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("#[to = \""))
+        .filter_map(|rest| rest.strip_suffix("\"]"))
+        .filter(|route| *route != "/")
+        .collect();
+
+    assert_eq!(client_routes, assets::APP_ROUTES.to_vec());
+}
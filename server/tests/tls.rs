@@ -0,0 +1,112 @@
+use actix_web::http::{header, StatusCode};
+use actix_web::{test, web, App};
+use std::fs;
+use std::path::PathBuf;
+
+use server::config::TlsConfig;
+use server::tls::{self, CertificateResolver, HttpsPort, TlsError};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mystore-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn https_location_swaps_the_port() {
+    assert_eq!(
+        tls::https_location("shop.local:8080", 8443, "/login?next=%2F"),
+        "https://shop.local:8443/login?next=%2F"
+    );
+    assert_eq!(
+        tls::https_location("shop.local", 443, "/"),
+        "https://shop.local/"
+    );
+    assert_eq!(
+        tls::https_location("[::1]:8080", 8443, "/dashboard"),
+        "https://[::1]:8443/dashboard"
+    );
+}
+
+#[actix_rt::test]
+async fn plain_http_is_redirected_keeping_the_method() {
+    let mut app = test::init_service(
+        App::new()
+            .data(HttpsPort(8443))
+            .default_service(web::to(tls::redirect)),
+    )
+    .await;
+    let request = test::TestRequest::post()
+        .uri("/graphql?op=1")
+        .header(header::HOST, "127.0.0.1:8080")
+        .to_request();
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(
+        response.headers().get(header::LOCATION).unwrap(),
+        "https://127.0.0.1:8443/graphql?op=1"
+    );
+}
+
+#[test]
+fn generated_dev_cert_loads_and_reloads() {
+    let dir = scratch_dir("dev-cert");
+    let config = TlsConfig {
+        cert: dir.join("cert.pem"),
+        key: dir.join("key.pem"),
+        redirect_port: None,
+    };
+    let hosts = vec!["localhost".to_string(), "127.0.0.1".to_string()];
+
+    tls::generate_dev_cert(&hosts, &config.cert, &config.key, true).unwrap();
+    let resolver = CertificateResolver::load(&config).unwrap();
+
+    tls::generate_dev_cert(&hosts, &config.cert, &config.key, true).unwrap();
+    assert!(resolver.reload().is_ok());
+
+    fs::write(&config.key, "not a key").unwrap();
+    assert!(matches!(resolver.reload(), Err(TlsError::NoPrivateKey(_))));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn dev_cert_does_not_overwrite_without_force() {
+    let dir = scratch_dir("dev-cert-existing");
+    let cert = dir.join("cert.pem");
+    fs::write(&cert, "keep me").unwrap();
+
+    let result = tls::generate_dev_cert(
+        &["localhost".to_string()],
+        &cert,
+        &dir.join("key.pem"),
+        false,
+    );
+
+    assert!(matches!(result, Err(TlsError::AlreadyExists(_))));
+    assert_eq!(fs::read_to_string(&cert).unwrap(), "keep me");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn forced_dev_cert_makes_an_existing_key_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = scratch_dir("dev-cert-permissions");
+    let key = dir.join("key.pem");
+    fs::write(&key, "old key").unwrap();
+    fs::set_permissions(&key, fs::Permissions::from_mode(0o644)).unwrap();
+
+    tls::generate_dev_cert(
+        &["localhost".to_string()],
+        &dir.join("cert.pem"),
+        &key,
+        true,
+    )
+    .unwrap();
+
+    let mode = fs::metadata(&key).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,553 @@
+use graphql_client::{GraphQLQuery, QueryBody};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use wasm_bindgen_futures::spawn_local;
+use serde::de::DeserializeOwned;
+use yew::agent::{Agent, AgentLink, Bridge, Bridged, Context, HandlerId};
+use yew::services::ConsoleService;
+use yew::Callback;
+
+use crate::fetching::{get_token, send_request, FetchError, FetchState};
+use crate::models::decode;
+use crate::offline::{
+    created_id, is_online, load_snapshot, save_snapshot, ConnectivityListener, MutationQueue,
+};
+
+const ENTITY_TYPES: [&str; 5] = ["Sale", "Product", "Price", "SaleProduct", "PriceProduct"];
+const REF_KEY: &str = "__ref";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FetchPolicy {
+    CacheFirst,
+    NetworkOnly,
+    CacheAndNetwork,
+}
+
+/// Normalized store: entities are kept once, keyed by `__typename:id`, and
+/// query results only hold references to them.
+#[derive(Default)]
+pub struct Store {
+    entities: HashMap<String, Map<String, Value>>,
+    queries: HashMap<String, Value>,
+}
+
+impl Store {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write_query(&mut self, key: &str, data: &Value) {
+        let normalized = self.normalize(data);
+        self.queries.insert(key.to_string(), normalized);
+    }
+
+    pub fn read_query(&self, key: &str) -> Option<Value> {
+        self.queries.get(key).map(|data| self.denormalize(data))
+    }
+
+    /// Merges every entity found in `data` into the store, so queries that
+    /// reference them see the new values. Used for mutation results.
+    pub fn write_entities(&mut self, data: &Value) {
+        self.normalize(data);
+    }
+
+    pub fn entity(&self, typename: &str, id: i64) -> Option<Value> {
+        self.entities
+            .get(&entity_key(typename, id))
+            .map(|fields| self.denormalize(&Value::Object(fields.clone())))
+    }
+
+    pub fn evict(&mut self, typename: &str, id: i64) {
+        self.entities.remove(&entity_key(typename, id));
+    }
+
+    pub fn entities(&self) -> &HashMap<String, Map<String, Value>> {
+        &self.entities
+    }
+
+    pub fn queries(&self) -> &HashMap<String, Value> {
+        &self.queries
+    }
+
+    pub fn restore(
+        &mut self,
+        entities: HashMap<String, Map<String, Value>>,
+        queries: HashMap<String, Value>,
+    ) {
+        self.entities = entities;
+        self.queries = queries;
+    }
+
+    fn normalize(&mut self, data: &Value) -> Value {
+        match data {
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|item| self.normalize(item)).collect())
+            }
+            Value::Object(fields) => {
+                let normalized: Map<String, Value> = fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), self.normalize(value)))
+                    .collect();
+                match identify(fields) {
+                    Some(key) => {
+                        self.entities
+                            .entry(key.clone())
+                            .or_insert_with(Map::new)
+                            .extend(normalized);
+                        let mut reference = Map::new();
+                        reference.insert(REF_KEY.to_string(), Value::String(key));
+                        Value::Object(reference)
+                    }
+                    None => Value::Object(normalized),
+                }
+            }
+            value => value.clone(),
+        }
+    }
+
+    fn denormalize(&self, data: &Value) -> Value {
+        match data {
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|item| self.denormalize(item)).collect())
+            }
+            Value::Object(fields) => {
+                if let Some(Value::String(key)) = fields.get(REF_KEY) {
+                    return match self.entities.get(key) {
+                        Some(entity) => self.denormalize(&Value::Object(entity.clone())),
+                        None => Value::Null,
+                    };
+                }
+                Value::Object(
+                    fields
+                        .iter()
+                        .map(|(name, value)| (name.clone(), self.denormalize(value)))
+                        .collect(),
+                )
+            }
+            value => value.clone(),
+        }
+    }
+}
+
+fn entity_key(typename: &str, id: i64) -> String {
+    format!("{}:{}", typename, id)
+}
+
+fn identify(fields: &Map<String, Value>) -> Option<String> {
+    let typename = fields.get("__typename")?.as_str()?;
+    let id = fields.get("id")?.as_i64()?;
+    if ENTITY_TYPES.contains(&typename) {
+        Some(entity_key(typename, id))
+    } else {
+        None
+    }
+}
+
+/// Cache key of a query: its operation name plus its variables.
+pub fn query_key(body: &Value) -> String {
+    format!(
+        "{}:{}",
+        body["operationName"].as_str().unwrap_or_default(),
+        body["variables"]
+    )
+}
+
+pub fn query_body<Q: GraphQLQuery>(variables: Q::Variables) -> Value {
+    let body: QueryBody<Q::Variables> = Q::build_query(variables);
+    serde_json::to_value(&body).unwrap_or(Value::Null)
+}
+
+pub enum Request {
+    Query { body: Value, policy: FetchPolicy },
+    Mutate { body: Value },
+    /// Forgets the cached data and queued mutations, e.g. on logout.
+    Clear,
+}
+
+#[derive(Clone)]
+pub enum Response {
+    Query {
+        key: String,
+        state: FetchState<Value>,
+    },
+    Status {
+        online: bool,
+        pending: usize,
+    },
+    Conflict {
+        operation: String,
+        message: String,
+    },
+}
+
+/// Why a request to `/graphql` did not produce data.
+pub enum Failure {
+    /// The server could not be reached.
+    Network(FetchError),
+    /// The server answered with GraphQL errors.
+    Rejected(String),
+}
+
+impl From<Failure> for FetchError {
+    fn from(failure: Failure) -> Self {
+        match failure {
+            Failure::Network(error) => error,
+            Failure::Rejected(message) => FetchError::from(message.as_str()),
+        }
+    }
+}
+
+async fn post_graphql(body: Value) -> Result<Value, Failure> {
+    let response = send_request::<Value, Value>("/graphql", Some(&body), "POST")
+        .await
+        .map_err(Failure::Network)?;
+    let json = response
+        .data
+        .into_serde::<Value>()
+        .map_err(|error| Failure::Network(FetchError::from(error.to_string().as_str())))?;
+    match json["errors"].as_array() {
+        Some(errors) if !errors.is_empty() => Err(Failure::Rejected(
+            errors
+                .iter()
+                .filter_map(|error| error["message"].as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )),
+        _ => Ok(json["data"].clone()),
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Snapshot {
+    entities: HashMap<String, Map<String, Value>>,
+    queries: HashMap<String, Value>,
+    queue: MutationQueue,
+}
+
+pub enum Msg {
+    Fetched(HandlerId, String, Result<Value, Failure>),
+    Mutated(HandlerId, String, Value, Result<Value, Failure>),
+    Replayed(Result<Value, Failure>),
+    Restored(Option<String>),
+    Connectivity(bool),
+}
+
+pub struct CacheAgent {
+    link: AgentLink<Self>,
+    store: Store,
+    queue: MutationQueue,
+    online: bool,
+    replaying: bool,
+    handlers: HashSet<HandlerId>,
+    subscriptions: HashMap<HandlerId, HashSet<String>>,
+    _connectivity: ConnectivityListener,
+}
+
+impl CacheAgent {
+    fn post<F>(&self, body: Value, on_done: F)
+    where
+        F: FnOnce(Result<Value, Failure>) -> Msg + 'static,
+    {
+        let callback: Callback<Msg> = self.link.callback(|msg| msg);
+        spawn_local(async move {
+            callback.emit(on_done(post_graphql(body).await));
+        });
+    }
+
+    fn respond(&self, id: HandlerId, key: &str, state: FetchState<Value>) {
+        self.link.respond(
+            id,
+            Response::Query {
+                key: key.to_string(),
+                state,
+            },
+        );
+    }
+
+    /// Pushes fresh data to every subscriber after the store has changed.
+    fn broadcast(&self) {
+        for (id, keys) in &self.subscriptions {
+            for key in keys {
+                if let Some(data) = self.store.read_query(key) {
+                    self.respond(*id, key, FetchState::Success(data));
+                }
+            }
+        }
+    }
+
+    fn broadcast_status(&self) {
+        for id in &self.handlers {
+            self.link.respond(
+                *id,
+                Response::Status {
+                    online: self.online,
+                    pending: self.queue.len(),
+                },
+            );
+        }
+    }
+
+    fn enqueue(&mut self, body: Value) -> Value {
+        let mutation = self.queue.enqueue(body);
+        if let Some(entity) = mutation.optimistic_entity() {
+            self.store.write_entities(&entity);
+            self.broadcast();
+        }
+        self.persist();
+        self.broadcast_status();
+        json!({ "queued": true, "tempId": mutation.temp_id.map(|temp_id| temp_id.id) })
+    }
+
+    /// Sends the oldest queued mutation, if there is one and we are online.
+    /// The rest follow one at a time, in order, from `Msg::Replayed`.
+    fn replay_next(&mut self) {
+        if !self.online || self.replaying {
+            return;
+        }
+        if let Some(mutation) = self.queue.front() {
+            self.replaying = true;
+            self.post(mutation.outgoing_body(), Msg::Replayed);
+        }
+    }
+
+    /// Saves the cache for the next start, while someone is signed in.
+    fn persist(&self) {
+        if get_token().is_err() {
+            return;
+        }
+        let snapshot = Snapshot {
+            entities: self.store.entities().clone(),
+            queries: self.store.queries().clone(),
+            queue: self.queue.clone(),
+        };
+        let json = serde_json::to_string(&snapshot).unwrap_or_default();
+        spawn_local(async move {
+            if let Err(error) = save_snapshot(json).await {
+                ConsoleService::new().log(&format!("Error: {}", &error));
+            }
+        });
+    }
+}
+
+impl Agent for CacheAgent {
+    type Reach = Context;
+    type Message = Msg;
+    type Input = Request;
+    type Output = Response;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        let connectivity = ConnectivityListener::new(link.callback(Msg::Connectivity));
+        let restored = link.callback(Msg::Restored);
+        spawn_local(async move {
+            match load_snapshot().await {
+                Ok(snapshot) => restored.emit(snapshot),
+                Err(error) => {
+                    ConsoleService::new().log(&format!("Error: {}", &error));
+                    restored.emit(None)
+                }
+            }
+        });
+        Self {
+            link,
+            store: Store::new(),
+            queue: MutationQueue::new(),
+            online: is_online(),
+            replaying: false,
+            handlers: HashSet::new(),
+            subscriptions: HashMap::new(),
+            _connectivity: connectivity,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        match msg {
+            Msg::Fetched(id, key, result) => match result {
+                Ok(data) => {
+                    self.store.write_query(&key, &data);
+                    self.persist();
+                    self.broadcast();
+                }
+                Err(failure) => self.respond(id, &key, FetchState::Failed(failure.into())),
+            },
+            Msg::Mutated(id, key, body, result) => match result {
+                Ok(data) => {
+                    self.store.write_entities(&data);
+                    self.persist();
+                    self.respond(id, &key, FetchState::Success(data));
+                    self.broadcast();
+                }
+                Err(Failure::Network(_)) => {
+                    self.online = false;
+                    let queued = self.enqueue(body);
+                    self.respond(id, &key, FetchState::Success(queued));
+                }
+                Err(failure) => self.respond(id, &key, FetchState::Failed(failure.into())),
+            },
+            Msg::Replayed(result) => {
+                self.replaying = false;
+                match result {
+                    Err(Failure::Network(_)) => {
+                        self.online = false;
+                        self.broadcast_status();
+                        return;
+                    }
+                    Ok(data) => {
+                        if let Some(mutation) = self.queue.pop_front() {
+                            if let Some(temp_id) = mutation.temp_id {
+                                if let Some(real_id) = created_id(&data, &temp_id.typename) {
+                                    self.queue.reconcile(temp_id.id, real_id);
+                                }
+                                self.store.evict(&temp_id.typename, temp_id.id);
+                            }
+                        }
+                        self.store.write_entities(&data);
+                        self.broadcast();
+                    }
+                    Err(Failure::Rejected(message)) => {
+                        if let Some(mutation) = self.queue.pop_front() {
+                            if let Some(temp_id) = &mutation.temp_id {
+                                self.store.evict(&temp_id.typename, temp_id.id);
+                            }
+                            for id in &self.handlers {
+                                self.link.respond(
+                                    *id,
+                                    Response::Conflict {
+                                        operation: mutation.operation().to_string(),
+                                        message: message.clone(),
+                                    },
+                                );
+                            }
+                        }
+                        self.broadcast();
+                    }
+                }
+                self.persist();
+                self.broadcast_status();
+                self.replay_next();
+            }
+            Msg::Restored(snapshot) => {
+                let snapshot = snapshot
+                    .and_then(|json| serde_json::from_str::<Snapshot>(&json).ok())
+                    .unwrap_or_default();
+                if self.store.entities().is_empty() {
+                    self.store.restore(snapshot.entities, snapshot.queries);
+                    self.broadcast();
+                }
+                self.queue.restore(snapshot.queue);
+                self.broadcast_status();
+                self.replay_next();
+            }
+            Msg::Connectivity(online) => {
+                self.online = online;
+                self.broadcast_status();
+                self.replay_next();
+            }
+        }
+    }
+
+    fn connected(&mut self, id: HandlerId) {
+        self.handlers.insert(id);
+        self.link.respond(
+            id,
+            Response::Status {
+                online: self.online,
+                pending: self.queue.len(),
+            },
+        );
+    }
+
+    fn handle_input(&mut self, msg: Self::Input, id: HandlerId) {
+        match msg {
+            Request::Query { body, policy } => {
+                let key = query_key(&body);
+                self.subscriptions
+                    .entry(id)
+                    .or_insert_with(HashSet::new)
+                    .insert(key.clone());
+                let cached = self.store.read_query(&key);
+                let fetched_key = key.clone();
+                let fetched = move |result| Msg::Fetched(id, fetched_key, result);
+                match (policy, cached, self.online) {
+                    (FetchPolicy::CacheFirst, Some(data), _) | (_, Some(data), false) => {
+                        self.respond(id, &key, FetchState::Success(data))
+                    }
+                    (FetchPolicy::CacheAndNetwork, Some(data), true) => {
+                        self.respond(id, &key, FetchState::Success(data));
+                        self.post(body, fetched);
+                    }
+                    _ => {
+                        self.respond(id, &key, FetchState::Fetching);
+                        self.post(body, fetched);
+                    }
+                }
+            }
+            Request::Mutate { body } => {
+                let key = query_key(&body);
+                if self.online && self.queue.is_empty() {
+                    self.respond(id, &key, FetchState::Fetching);
+                    let sent = body.clone();
+                    self.post(body, move |result| Msg::Mutated(id, key, sent, result));
+                } else {
+                    let queued = self.enqueue(body);
+                    self.respond(id, &key, FetchState::Success(queued));
+                }
+            }
+            Request::Clear => {
+                self.store = Store::new();
+                self.queue = MutationQueue::new();
+                self.broadcast_status();
+            }
+        }
+    }
+
+    fn disconnected(&mut self, id: HandlerId) {
+        self.handlers.remove(&id);
+        self.subscriptions.remove(&id);
+    }
+}
+
+/// A component's handle on one cached query: sends it on creation and
+/// tells its responses apart from those of other queries.
+pub struct CachedQuery {
+    cache: Box<dyn Bridge<CacheAgent>>,
+    body: Value,
+    key: String,
+}
+
+impl CachedQuery {
+    /// Shows cached data right away and refreshes it from the network.
+    pub fn new(body: Value, callback: Callback<Response>) -> Self {
+        let mut cache = CacheAgent::bridge(callback);
+        let key = query_key(&body);
+        cache.send(Request::Query {
+            body: body.clone(),
+            policy: FetchPolicy::CacheAndNetwork,
+        });
+        Self { cache, body, key }
+    }
+
+    /// Asks the network again, e.g. after a failure.
+    pub fn refetch(&mut self) {
+        self.cache.send(Request::Query {
+            body: self.body.clone(),
+            policy: FetchPolicy::NetworkOnly,
+        });
+    }
+
+    /// The state carried by `response` when it is about this query, with
+    /// `data[field]` decoded.
+    pub fn state<T: DeserializeOwned>(&self, response: Response, field: &str) -> Option<FetchState<T>> {
+        match response {
+            Response::Query { key, state } if key == self.key => Some(match state {
+                FetchState::Fetching => FetchState::Fetching,
+                FetchState::Success(data) => match decode(&data, field) {
+                    Ok(value) => FetchState::Success(value),
+                    Err(error) => FetchState::Failed(FetchError::from(error.as_str())),
+                },
+                FetchState::Failed(error) => FetchState::Failed(error),
+            }),
+            _ => None,
+        }
+    }
+}
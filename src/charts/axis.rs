@@ -0,0 +1,59 @@
+use yew::prelude::{html, Html};
+
+use super::{short_number, shown_labels, ticks, Plot, Point};
+
+/// Gridlines with their values, and the baseline.
+pub fn y_axis(plot: &Plot, top: f64) -> Html {
+    html! {
+        <g class="chart-axis">
+            { for ticks(top).into_iter().map(|tick| {
+                let y = plot.y(tick, top);
+                html! {
+                    <g>
+                        <line x1=plot.left x2={plot.left + plot.width} y1=y y2=y
+                              stroke="#e9ecef" />
+                        <text x={plot.left - 6.0} y={y + 4.0} text-anchor="end"
+                              font-size="11" fill="#6c757d">
+                            { short_number(tick) }
+                        </text>
+                    </g>
+                }
+            }) }
+            <line x1=plot.left x2={plot.left + plot.width} y1={plot.bottom()} y2={plot.bottom()}
+                  stroke="#adb5bd" />
+        </g>
+    }
+}
+
+/// Labels under the x axis, `x` giving each one's centre.
+pub fn x_labels(plot: &Plot, points: &[Point], x: impl Fn(usize) -> f64) -> Html {
+    html! {
+        <g class="chart-labels">
+            { for shown_labels(points.len()).into_iter().map(|index| html! {
+                <text x={x(index)} y={plot.bottom() + 18.0} text-anchor="middle"
+                      font-size="11" fill="#6c757d">
+                    { &points[index].label }
+                </text>
+            }) }
+        </g>
+    }
+}
+
+/// The hover box over a point, kept inside the drawing.
+pub fn tooltip(plot: &Plot, x: f64, y: f64, point: &Point) -> Html {
+    let text = format!("{}: {:.2}", point.label, point.value);
+    let width = 7.0 * text.len() as f64 + 12.0;
+    let left = (x - width / 2.0)
+        .max(plot.left)
+        .min(plot.left + plot.width - width);
+    let top = (y - 34.0).max(0.0);
+    html! {
+        <g class="chart-tooltip" pointer-events="none">
+            <rect x=left y=top width=width height=24 rx=4 fill="#343a40" opacity="0.9" />
+            <text x={left + width / 2.0} y={top + 16.0} text-anchor="middle"
+                  font-size="12" fill="#fff">
+                { text }
+            </text>
+        </g>
+    }
+}
@@ -0,0 +1,89 @@
+use yew::prelude::{html, Component, ComponentLink, Properties, ShouldRender};
+use yew::virtual_dom::VNode;
+
+use super::axis::{tooltip, x_labels, y_axis};
+use super::{nice_max, Plot, Point, HEIGHT, WIDTH};
+
+/// Share of each slot a bar fills.
+const BAR_WIDTH: f64 = 0.7;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub points: Vec<Point>,
+    #[prop_or("#007bff")]
+    pub color: &'static str,
+}
+
+pub struct Model {
+    link: ComponentLink<Self>,
+    props: Props,
+    hovered: Option<usize>,
+}
+
+pub enum Msg {
+    Hover(Option<usize>),
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link,
+            props,
+            hovered: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Hover(hovered) => {
+                let changed = self.hovered != hovered;
+                self.hovered = hovered;
+                changed
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        self.hovered = None;
+        true
+    }
+
+    fn view(&self) -> VNode {
+        let plot = Plot::default();
+        let points = &self.props.points;
+        let band = plot.band(points.len());
+        let centre = |index: usize| plot.left + band * (index as f64 + 0.5);
+        let top = nice_max(points.iter().map(|point| point.value).fold(0.0, f64::max));
+        html! {
+            <svg class="chart chart-bar" viewBox=format!("0 0 {} {}", WIDTH, HEIGHT)
+                 width="100%" preserveAspectRatio="xMidYMid meet" role="img">
+                { y_axis(&plot, top) }
+                { x_labels(&plot, points, centre) }
+                { for points.iter().enumerate().map(|(index, point)| {
+                    let y = plot.y(point.value, top);
+                    html! {
+                        <rect x={centre(index) - band * BAR_WIDTH / 2.0} y=y
+                              width={band * BAR_WIDTH} height={plot.bottom() - y}
+                              fill=self.props.color
+                              opacity={if self.hovered == Some(index) { "1" } else { "0.8" }}
+                              onmouseover=self.link.callback(move |_| Msg::Hover(Some(index)))
+                              onmouseout=self.link.callback(|_| Msg::Hover(None))>
+                            <title>{ format!("{}: {:.2}", point.label, point.value) }</title>
+                        </rect>
+                    }
+                }) }
+                { self.hovered
+                    .and_then(|index| points.get(index).map(|point| (index, point)))
+                    .map(|(index, point)| tooltip(&plot, centre(index), plot.y(point.value, top), point))
+                    .unwrap_or_default() }
+            </svg>
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use yew::prelude::{html, Component, ComponentLink, Properties, ShouldRender};
+use yew::virtual_dom::VNode;
+
+use super::{angles, arc_path, Slice};
+
+const SIZE: f64 = 200.0;
+const OUTER: f64 = 90.0;
+const INNER: f64 = 55.0;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub slices: Vec<Slice>,
+}
+
+/// A ring split by value, with the hovered slice's share in the middle
+/// and the total otherwise.
+pub struct Model {
+    link: ComponentLink<Self>,
+    props: Props,
+    hovered: Option<usize>,
+}
+
+pub enum Msg {
+    Hover(Option<usize>),
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link,
+            props,
+            hovered: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Hover(hovered) => {
+                let changed = self.hovered != hovered;
+                self.hovered = hovered;
+                changed
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        self.hovered = None;
+        true
+    }
+
+    fn view(&self) -> VNode {
+        let slices = &self.props.slices;
+        let values: Vec<f64> = slices.iter().map(|slice| slice.value).collect();
+        let total: f64 = values.iter().map(|value| value.max(0.0)).sum();
+        let centre = SIZE / 2.0;
+        let (caption, figure) = match self.hovered.and_then(|index| slices.get(index)) {
+            Some(slice) if total > 0.0 => (
+                slice.label.clone(),
+                format!("{:.0}%", slice.value.max(0.0) / total * 100.0),
+            ),
+            _ => ("Total".to_string(), format!("{}", total)),
+        };
+        html! {
+            <svg class="chart chart-donut" viewBox=format!("0 0 {} {}", SIZE, SIZE)
+                 width="100%" preserveAspectRatio="xMidYMid meet" role="img">
+                <circle cx=centre cy=centre r={(OUTER + INNER) / 2.0} fill="none"
+                        stroke="#e9ecef" stroke-width={OUTER - INNER} />
+                { for slices.iter().zip(angles(&values)).enumerate()
+                    .filter(|(_, (_, (start, end)))| end > start)
+                    .map(|(index, (slice, (start, end)))| {
+                        let outer = if self.hovered == Some(index) { OUTER + 4.0 } else { OUTER };
+                        html! {
+                            <path d=arc_path(centre, centre, outer, INNER, start, end) fill=slice.color
+                                  onmouseover=self.link.callback(move |_| Msg::Hover(Some(index)))
+                                  onmouseout=self.link.callback(|_| Msg::Hover(None))>
+                                <title>{ format!("{}: {}", slice.label, slice.value) }</title>
+                            </path>
+                        }
+                    }) }
+                <text x=centre y={centre - 4.0} text-anchor="middle" font-size="22" font-weight="bold">
+                    { figure }
+                </text>
+                <text x=centre y={centre + 16.0} text-anchor="middle" font-size="12" fill="#6c757d">
+                    { caption }
+                </text>
+            </svg>
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use yew::prelude::{html, Component, ComponentLink, Properties, ShouldRender};
+use yew::virtual_dom::VNode;
+
+use super::axis::{tooltip, x_labels, y_axis};
+use super::{nice_max, Plot, Point, HEIGHT, WIDTH};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub points: Vec<Point>,
+    #[prop_or("#007bff")]
+    pub color: &'static str,
+}
+
+pub struct Model {
+    link: ComponentLink<Self>,
+    props: Props,
+    hovered: Option<usize>,
+}
+
+pub enum Msg {
+    Hover(Option<usize>),
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link,
+            props,
+            hovered: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Hover(hovered) => {
+                let changed = self.hovered != hovered;
+                self.hovered = hovered;
+                changed
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        self.hovered = None;
+        true
+    }
+
+    fn view(&self) -> VNode {
+        let plot = Plot::default();
+        let points = &self.props.points;
+        let count = points.len();
+        let top = nice_max(points.iter().map(|point| point.value).fold(0.0, f64::max));
+        let coordinates: Vec<(f64, f64)> = points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| (plot.x(index, count), plot.y(point.value, top)))
+            .collect();
+        let line = coordinates
+            .iter()
+            .map(|(x, y)| format!("{:.2},{:.2}", x, y))
+            .collect::<Vec<_>>()
+            .join(" ");
+        html! {
+            <svg class="chart chart-line" viewBox=format!("0 0 {} {}", WIDTH, HEIGHT)
+                 width="100%" preserveAspectRatio="xMidYMid meet" role="img">
+                { y_axis(&plot, top) }
+                { x_labels(&plot, points, |index| plot.x(index, count)) }
+                <polyline points=line fill="none" stroke=self.props.color stroke-width="2" />
+                { for coordinates.iter().enumerate().map(|(index, (x, y))| html! {
+                    <circle cx=x cy=y r={if self.hovered == Some(index) { 5 } else { 3 }}
+                            fill=self.props.color
+                            onmouseover=self.link.callback(move |_| Msg::Hover(Some(index)))
+                            onmouseout=self.link.callback(|_| Msg::Hover(None))>
+                        <title>{ format!("{}: {:.2}", points[index].label, points[index].value) }</title>
+                    </circle>
+                }) }
+                { self.hovered
+                    .and_then(|index| Some((coordinates.get(index)?, points.get(index)?)))
+                    .map(|((x, y), point)| tooltip(&plot, *x, *y, point))
+                    .unwrap_or_default() }
+            </svg>
+        }
+    }
+}
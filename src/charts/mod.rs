@@ -0,0 +1,175 @@
+//! Line, bar and donut charts drawn as plain SVG. The drawings scale with
+//! their container through `viewBox`; the geometry lives here so it can be
+//! checked without a browser.
+
+use std::f64::consts::PI;
+
+mod axis;
+pub mod bar;
+pub mod donut;
+pub mod line;
+
+pub use bar::Model as BarChart;
+pub use donut::Model as DonutChart;
+pub use line::Model as LineChart;
+
+/// Size of the drawing in SVG units, before it is scaled to fit.
+pub const WIDTH: f64 = 600.0;
+pub const HEIGHT: f64 = 260.0;
+const MARGIN_LEFT: f64 = 56.0;
+const MARGIN_RIGHT: f64 = 12.0;
+const MARGIN_TOP: f64 = 12.0;
+const MARGIN_BOTTOM: f64 = 28.0;
+/// Most labels shown under the x axis; the rest are skipped evenly.
+const MAX_X_LABELS: usize = 8;
+const Y_TICKS: usize = 4;
+
+/// One value of a line or bar chart.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Point {
+    pub label: String,
+    pub value: f64,
+}
+
+/// One part of a donut chart.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slice {
+    pub label: String,
+    pub value: f64,
+    pub color: &'static str,
+}
+
+/// The area inside the axes, in SVG units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plot {
+    pub left: f64,
+    pub top: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Default for Plot {
+    fn default() -> Self {
+        Self {
+            left: MARGIN_LEFT,
+            top: MARGIN_TOP,
+            width: WIDTH - MARGIN_LEFT - MARGIN_RIGHT,
+            height: HEIGHT - MARGIN_TOP - MARGIN_BOTTOM,
+        }
+    }
+}
+
+impl Plot {
+    pub fn bottom(&self) -> f64 {
+        self.top + self.height
+    }
+
+    /// Height of `value` on an axis that goes from zero to `max`.
+    pub fn y(&self, value: f64, max: f64) -> f64 {
+        self.bottom() - value.max(0.0) / max * self.height
+    }
+
+    /// Centre of the `index`th of `count` points spread over the width.
+    pub fn x(&self, index: usize, count: usize) -> f64 {
+        if count <= 1 {
+            return self.left + self.width / 2.0;
+        }
+        self.left + index as f64 * self.width / (count - 1) as f64
+    }
+
+    /// Width of each of `count` bars' slots.
+    pub fn band(&self, count: usize) -> f64 {
+        self.width / count.max(1) as f64
+    }
+}
+
+/// The top of the y axis: `max` rounded up to 1, 2 or 5 times a power of
+/// ten, so the ticks land on round numbers.
+pub fn nice_max(max: f64) -> f64 {
+    if !max.is_finite() || max <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(max.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|step| step * magnitude)
+        .find(|top| *top >= max)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// Values of the y axis gridlines, from zero up to `top`.
+pub fn ticks(top: f64) -> Vec<f64> {
+    (0..=Y_TICKS)
+        .map(|tick| top * tick as f64 / Y_TICKS as f64)
+        .collect()
+}
+
+/// Indexes of the x labels to show among `count`, evenly spaced.
+pub fn shown_labels(count: usize) -> Vec<usize> {
+    let step = (count + MAX_X_LABELS - 1) / MAX_X_LABELS;
+    (0..count).step_by(step.max(1)).collect()
+}
+
+/// Start and end angles, in radians clockwise from twelve o'clock, of each
+/// value's share of the circle. Negative values count as zero.
+pub fn angles(values: &[f64]) -> Vec<(f64, f64)> {
+    let total: f64 = values.iter().map(|value| value.max(0.0)).sum();
+    let mut start = 0.0;
+    values
+        .iter()
+        .map(|value| {
+            let sweep = if total > 0.0 {
+                value.max(0.0) / total * 2.0 * PI
+            } else {
+                0.0
+            };
+            let angle = (start, start + sweep);
+            start += sweep;
+            angle
+        })
+        .collect()
+}
+
+/// SVG path of a ring segment centred on `(cx, cy)` between `inner` and
+/// `outer` radii. A whole ring is drawn just short of closing, as an arc
+/// cannot start and end on the same point.
+pub fn arc_path(cx: f64, cy: f64, outer: f64, inner: f64, start: f64, end: f64) -> String {
+    let end = end.min(start + 2.0 * PI - 1e-4);
+    let large = if end - start > PI { 1 } else { 0 };
+    let point = |radius: f64, angle: f64| (cx + radius * angle.sin(), cy - radius * angle.cos());
+    let (x1, y1) = point(outer, start);
+    let (x2, y2) = point(outer, end);
+    let (x3, y3) = point(inner, end);
+    let (x4, y4) = point(inner, start);
+    format!(
+        "M{:.2},{:.2} A{r},{r} 0 {l} 1 {:.2},{:.2} L{:.2},{:.2} A{ri},{ri} 0 {l} 0 {:.2},{:.2} Z",
+        x1,
+        y1,
+        x2,
+        y2,
+        x3,
+        y3,
+        x4,
+        y4,
+        r = outer,
+        ri = inner,
+        l = large
+    )
+}
+
+/// Short form of an axis value: 1200 reads 1.2k.
+pub fn short_number(value: f64) -> String {
+    let abs = value.abs();
+    if abs >= 1_000_000.0 {
+        format!("{}M", trim(value / 1_000_000.0))
+    } else if abs >= 1_000.0 {
+        format!("{}k", trim(value / 1_000.0))
+    } else {
+        trim(value)
+    }
+}
+
+fn trim(value: f64) -> String {
+    let text = format!("{:.1}", value);
+    text.trim_end_matches(".0").to_string()
+}
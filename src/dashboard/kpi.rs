@@ -0,0 +1,227 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ops::AddAssign;
+
+use crate::models::{FullProduct, FullSale, Product, Sale, SaleState};
+use crate::pricing::Money;
+
+/// Stock at or below which a product shows up as low on stock.
+pub const LOW_STOCK_THRESHOLD: f64 = 5.0;
+
+/// Most buckets a chart draws, about three months of days.
+pub const MAX_BUCKETS: usize = 92;
+
+/// What the `dashboard` query answers with, when it is a JSON object in a
+/// string. Missing keys read as zero; a string that is not JSON at all falls
+/// back to `from_lists`.
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DashboardSummary {
+    pub today_sales: f64,
+    pub month_sales: f64,
+    pub sales_count: i64,
+    pub products_count: i64,
+}
+
+impl DashboardSummary {
+    pub fn parse(dashboard: &str) -> Result<Self, String> {
+        serde_json::from_str(dashboard)
+            .map_err(|error| format!("Unexpected dashboard data: {}", error))
+    }
+
+    /// The same figures worked out from the listed sales and products.
+    /// Cancelled sales are left out.
+    pub fn from_lists(sales: &[FullSale], products: &[FullProduct], today: NaiveDate) -> Self {
+        let counted: Vec<&Sale> = sales
+            .iter()
+            .map(|full_sale| &full_sale.sale)
+            .filter(|sale| sale.state != SaleState::Cancelled)
+            .collect();
+        let total = |in_period: &dyn Fn(NaiveDate) -> bool| {
+            counted
+                .iter()
+                .filter(|sale| in_period(sale.sale_date))
+                .map(|sale| Money::from_f64(sale.total))
+                .sum::<Money>()
+                .to_f64()
+        };
+        Self {
+            today_sales: total(&|date| date == today),
+            month_sales: total(&|date| Period::Month.start(date) == Period::Month.start(today)),
+            sales_count: counted.len() as i64,
+            products_count: products.len() as i64,
+        }
+    }
+}
+
+/// How many sales are in each state, every state listed even when empty.
+pub fn count_by_state(sales: &[FullSale]) -> Vec<(SaleState, usize)> {
+    SaleState::ALL
+        .iter()
+        .map(|state| {
+            let count = sales
+                .iter()
+                .filter(|full_sale| full_sale.sale.state == *state)
+                .count();
+            (*state, count)
+        })
+        .collect()
+}
+
+/// Products with `threshold` or less in stock, emptiest first.
+pub fn low_stock(products: &[FullProduct], threshold: f64, limit: usize) -> Vec<&Product> {
+    let mut low: Vec<&Product> = products
+        .iter()
+        .map(|full_product| &full_product.product)
+        .filter(|product| product.stock <= threshold)
+        .collect();
+    low.sort_by(|a, b| {
+        a.stock
+            .partial_cmp(&b.stock)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    low.truncate(limit);
+    low
+}
+
+/// The latest sales by date, newest first; ties go to the higher id.
+pub fn recent_sales(sales: &[FullSale], limit: usize) -> Vec<&FullSale> {
+    let mut recent: Vec<&FullSale> = sales.iter().collect();
+    recent.sort_by(|a, b| {
+        b.sale
+            .sale_date
+            .cmp(&a.sale.sale_date)
+            .then_with(|| b.sale.id.cmp(&a.sale.id))
+    });
+    recent.truncate(limit);
+    recent
+}
+
+/// How sales are grouped along the time axis of the charts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+}
+
+impl Period {
+    pub const ALL: [Period; 3] = [Period::Day, Period::Week, Period::Month];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Period::Day => "Day",
+            Period::Week => "Week",
+            Period::Month => "Month",
+        }
+    }
+
+    /// The first day of the bucket `date` falls in; weeks start on Monday.
+    pub fn start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => date,
+            Period::Week => date - Duration::days(i64::from(date.weekday().num_days_from_monday())),
+            Period::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// The first day of the bucket after the one starting on `start`.
+    pub fn next(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => start + Duration::days(1),
+            Period::Week => start + Duration::weeks(1),
+            Period::Month => {
+                if start.month() == 12 {
+                    NaiveDate::from_ymd(start.year() + 1, 1, 1)
+                } else {
+                    NaiveDate::from_ymd(start.year(), start.month() + 1, 1)
+                }
+            }
+        }
+    }
+
+    /// How many buckets `from` to `to` spans, counting no further than one
+    /// past `MAX_BUCKETS`.
+    fn buckets(self, from: NaiveDate, to: NaiveDate) -> usize {
+        let mut count = 0;
+        let mut start = self.start(from);
+        while start <= to && count <= MAX_BUCKETS {
+            count += 1;
+            start = self.next(start);
+        }
+        count
+    }
+
+    /// Axis label of the bucket starting on `start`.
+    pub fn format(self, start: NaiveDate) -> String {
+        match self {
+            Period::Day | Period::Week => start.format("%d %b").to_string(),
+            Period::Month => start.format("%b %Y").to_string(),
+        }
+    }
+}
+
+/// The period and first day a chart of `from` to `to` is drawn with, so it
+/// has at most `MAX_BUCKETS` buckets: `period` or the next longer one that
+/// fits, and when not even months do, only the last `MAX_BUCKETS` months.
+pub fn chart_range(period: Period, from: NaiveDate, to: NaiveDate) -> (Period, NaiveDate) {
+    let period = Period::ALL
+        .iter()
+        .copied()
+        .skip_while(|longer| *longer != period)
+        .find(|longer| longer.buckets(from, to) <= MAX_BUCKETS)
+        .unwrap_or(Period::Month);
+    if period.buckets(from, to) <= MAX_BUCKETS {
+        return (period, from);
+    }
+    let months = to.year() * 12 + to.month0() as i32 - (MAX_BUCKETS as i32 - 1);
+    let from = NaiveDate::from_ymd(months.div_euclid(12), months.rem_euclid(12) as u32 + 1, 1);
+    (period, from)
+}
+
+/// `value` of the sales dated `from` to `to`, both included, summed per
+/// `period` in one pass, oldest first, with empty buckets as zero so gaps
+/// show.
+pub fn per_period<'a, T>(
+    sales: impl IntoIterator<Item = &'a FullSale>,
+    period: Period,
+    from: NaiveDate,
+    to: NaiveDate,
+    value: impl Fn(&FullSale) -> T,
+) -> Vec<(NaiveDate, T)>
+where
+    T: Default + AddAssign,
+{
+    let mut buckets = BTreeMap::new();
+    let mut start = period.start(from);
+    while start <= to {
+        buckets.insert(start, T::default());
+        start = period.next(start);
+    }
+    for full_sale in sales {
+        let date = full_sale.sale.sale_date;
+        if date < from || date > to {
+            continue;
+        }
+        if let Some(total) = buckets.get_mut(&period.start(date)) {
+            *total += value(full_sale);
+        }
+    }
+    buckets.into_iter().collect()
+}
+
+/// Totals of the sales dated `from` to `to`, both included, per `period`,
+/// with empty buckets as zero so gaps show. Cancelled sales are left out.
+pub fn sales_over_time(
+    sales: &[FullSale],
+    period: Period,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<(NaiveDate, f64)> {
+    let kept = sales
+        .iter()
+        .filter(|full_sale| full_sale.sale.state != SaleState::Cancelled);
+    per_period(kept, period, from, to, |full_sale| full_sale.sale.total)
+}
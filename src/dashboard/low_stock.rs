@@ -0,0 +1,68 @@
+use yew::prelude::{
+    html, Callback, Component, ComponentLink, MouseEvent, Properties, ShouldRender,
+};
+use yew::virtual_dom::VNode;
+
+use super::kpi::{low_stock, LOW_STOCK_THRESHOLD};
+use super::widget::card;
+use crate::fetching::FetchState;
+use crate::models::ListProduct;
+
+const SHOWN: usize = 10;
+
+#[derive(Properties, Clone)]
+pub struct Props {
+    pub products: FetchState<ListProduct>,
+    pub retry: Callback<MouseEvent>,
+    #[prop_or(LOW_STOCK_THRESHOLD)]
+    pub threshold: f64,
+}
+
+/// Products running out, emptiest first.
+pub struct Model {
+    props: Props,
+}
+
+impl Component for Model {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> VNode {
+        let threshold = self.props.threshold;
+        card(
+            "Low stock",
+            "fa-boxes",
+            &self.props.products,
+            self.props.retry.clone(),
+            |list: &ListProduct| {
+                let products = low_stock(&list.data, threshold, SHOWN);
+                if products.is_empty() {
+                    return html! { <p class="text-muted mb-0">{ "Every product is stocked." }</p> };
+                }
+                html! {
+                    <ul class="list-group list-group-flush low-stock">
+                        { for products.into_iter().map(|product| html! {
+                            <li class="list-group-item d-flex justify-content-between">
+                                <span>{ &product.name }</span>
+                                <span class="badge badge-warning">{ product.stock }</span>
+                            </li>
+                        }) }
+                    </ul>
+                }
+            },
+        )
+    }
+}
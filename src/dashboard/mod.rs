@@ -0,0 +1,126 @@
+use yew::prelude::{html, Component, ComponentLink, MouseEvent, ShouldRender};
+use yew::virtual_dom::VNode;
+
+use crate::cache::{query_body, CachedQuery, Response};
+use crate::fetching::FetchState;
+use crate::graphql;
+use crate::models::{ListProduct, ListSale};
+use crate::navbar::Model as Navbar;
+
+pub mod kpi;
+mod low_stock;
+mod recent_sales;
+mod sale_states;
+mod sales_chart;
+mod totals;
+mod widget;
+
+use kpi::DashboardSummary;
+
+/// How many sales the widgets fetch to count and list from.
+const SALES_LIMIT: i64 = 1000;
+const PRODUCTS_LIMIT: i64 = 1000;
+
+/// Fetches the sales and products once and hands them to every widget.
+pub struct Model {
+    link: ComponentLink<Self>,
+    sales_query: CachedQuery,
+    products_query: CachedQuery,
+    dashboard_query: CachedQuery,
+    sales: FetchState<ListSale>,
+    products: FetchState<ListProduct>,
+    /// The `dashboard` query's figures, when the backend sends them in the
+    /// shape `DashboardSummary` reads.
+    summary: Option<DashboardSummary>,
+}
+
+pub enum Msg {
+    Cache(Response),
+    Retry,
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let sales = query_body::<graphql::ListSale>(graphql::list_sale::Variables {
+            search: None,
+            limit: SALES_LIMIT,
+        });
+        let products = query_body::<graphql::ListProduct>(graphql::list_product::Variables {
+            search: String::new(),
+            limit: PRODUCTS_LIMIT,
+            rank: 0.0,
+        });
+        let dashboard = query_body::<graphql::Dashboard>(graphql::dashboard::Variables);
+        Self {
+            sales_query: CachedQuery::new(sales, link.callback(Msg::Cache)),
+            products_query: CachedQuery::new(products, link.callback(Msg::Cache)),
+            dashboard_query: CachedQuery::new(dashboard, link.callback(Msg::Cache)),
+            link,
+            sales: FetchState::Fetching,
+            products: FetchState::Fetching,
+            summary: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Cache(response) => {
+                if let Some(state) = self.sales_query.state(response.clone(), "listSale") {
+                    self.sales = state;
+                    true
+                } else if let Some(state) =
+                    self.products_query.state(response.clone(), "listProduct")
+                {
+                    self.products = state;
+                    true
+                } else if let Some(state) =
+                    self.dashboard_query.state::<String>(response, "dashboard")
+                {
+                    match state {
+                        FetchState::Success(dashboard) => {
+                            self.summary = DashboardSummary::parse(&dashboard).ok();
+                            true
+                        }
+                        _ => false,
+                    }
+                } else {
+                    false
+                }
+            }
+            Msg::Retry => {
+                if let FetchState::Failed(_) = self.sales {
+                    self.sales_query.refetch();
+                }
+                if let FetchState::Failed(_) = self.products {
+                    self.products_query.refetch();
+                }
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> VNode {
+        let retry = self.link.callback(|_: MouseEvent| Msg::Retry);
+        html! {
+            <div>
+                <Navbar />
+                <h1 class="my-4"> { "Dashboard" } </h1>
+                <div class="row">
+                    <totals::Model sales=self.sales.clone() products=self.products.clone()
+                                   summary=self.summary.clone() retry=retry.clone() />
+                    <sale_states::Model sales=self.sales.clone() retry=retry.clone() />
+                    <sales_chart::Model sales=self.sales.clone() retry=retry.clone() />
+                    <recent_sales::Model sales=self.sales.clone() retry=retry.clone() />
+                    <low_stock::Model products=self.products.clone() retry=retry />
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use yew::prelude::{
+    html, Callback, Component, ComponentLink, MouseEvent, Properties, ShouldRender,
+};
+use yew::virtual_dom::VNode;
+
+use super::kpi::recent_sales;
+use super::widget::card;
+use super::SALES_LIMIT;
+use crate::fetching::FetchState;
+use crate::models::{format_money, ListSale};
+use crate::views::truncated;
+
+const SHOWN: usize = 5;
+
+#[derive(Properties, Clone)]
+pub struct Props {
+    pub sales: FetchState<ListSale>,
+    pub retry: Callback<MouseEvent>,
+}
+
+/// The latest sales with their bill number, state and total.
+pub struct Model {
+    props: Props,
+}
+
+impl Component for Model {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> VNode {
+        card(
+            "Recent sales",
+            "fa-receipt",
+            &self.props.sales,
+            self.props.retry.clone(),
+            |list: &ListSale| {
+                let sales = recent_sales(&list.data, SHOWN);
+                if sales.is_empty() {
+                    return html! { <p class="text-muted mb-0">{ "No sales yet." }</p> };
+                }
+                html! {
+                    <>
+                        { truncated(list.data.len(), SALES_LIMIT) }
+                        <table class="table table-sm mb-0 recent-sales">
+                            <tbody>
+                                { for sales.into_iter().map(|full_sale| {
+                                    let sale = &full_sale.sale;
+                                    html! {
+                                        <tr>
+                                            <td>{ sale.sale_date.format("%Y-%m-%d").to_string() }</td>
+                                            <td>{ sale.bill_number.clone().unwrap_or_default() }</td>
+                                            <td><span class=sale.state.badge()>{ sale.state.label() }</span></td>
+                                            <td class="text-right">{ format_money(sale.total) }</td>
+                                        </tr>
+                                    }
+                                }) }
+                            </tbody>
+                        </table>
+                    </>
+                }
+            },
+        )
+    }
+}
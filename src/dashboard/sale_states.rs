@@ -0,0 +1,81 @@
+use yew::prelude::{
+    html, Callback, Component, ComponentLink, MouseEvent, Properties, ShouldRender,
+};
+use yew::virtual_dom::VNode;
+
+use super::kpi::count_by_state;
+use super::widget::card;
+use super::SALES_LIMIT;
+use crate::charts::{DonutChart, Slice};
+use crate::fetching::FetchState;
+use crate::models::ListSale;
+use crate::views::truncated;
+
+#[derive(Properties, Clone)]
+pub struct Props {
+    pub sales: FetchState<ListSale>,
+    pub retry: Callback<MouseEvent>,
+}
+
+/// How many of the listed sales are in each `SaleState`.
+pub struct Model {
+    props: Props,
+}
+
+impl Component for Model {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> VNode {
+        card(
+            "Sales by state",
+            "fa-tasks",
+            &self.props.sales,
+            self.props.retry.clone(),
+            |list: &ListSale| {
+                let counts = count_by_state(&list.data);
+                let slices: Vec<Slice> = counts
+                    .iter()
+                    .map(|(state, count)| Slice {
+                        label: state.label().to_string(),
+                        value: *count as f64,
+                        color: state.color(),
+                    })
+                    .collect();
+                html! {
+                    <>
+                        { truncated(list.data.len(), SALES_LIMIT) }
+                        <div class="row align-items-center">
+                            <div class="col-sm-5">
+                                <DonutChart slices=slices />
+                            </div>
+                            <div class="col-sm-7">
+                                <ul class="list-group list-group-flush sale-states">
+                                    { for counts.into_iter().map(|(state, count)| html! {
+                                        <li class="list-group-item d-flex justify-content-between">
+                                            <span class=state.badge()>{ state.label() }</span>
+                                            <strong>{ count }</strong>
+                                        </li>
+                                    }) }
+                                </ul>
+                            </div>
+                        </div>
+                    </>
+                }
+            },
+        )
+    }
+}
@@ -0,0 +1,170 @@
+use chrono::{Duration, NaiveDate};
+use yew::prelude::{
+    html, Callback, ChangeData, Component, ComponentLink, Html, MouseEvent, Properties,
+    ShouldRender,
+};
+use yew::virtual_dom::VNode;
+
+use super::kpi::{chart_range, sales_over_time, Period};
+use super::widget::wide_card;
+use super::SALES_LIMIT;
+use crate::charts::{BarChart, LineChart, Point};
+use crate::dates::{parse_date, today, DATE_FORMAT};
+use crate::fetching::FetchState;
+use crate::models::ListSale;
+use crate::views::truncated;
+
+/// Days shown until the user picks another range.
+const DEFAULT_DAYS: i64 = 30;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Kind {
+    Line,
+    Bar,
+}
+
+#[derive(Properties, Clone)]
+pub struct Props {
+    pub sales: FetchState<ListSale>,
+    pub retry: Callback<MouseEvent>,
+}
+
+/// Sales totals over a date range, by day, week or month.
+pub struct Model {
+    link: ComponentLink<Self>,
+    props: Props,
+    period: Period,
+    kind: Kind,
+    from: NaiveDate,
+    to: NaiveDate,
+}
+
+pub enum Msg {
+    Period(Period),
+    Kind(Kind),
+    From(ChangeData),
+    To(ChangeData),
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let to = today();
+        Self {
+            link,
+            props,
+            period: Period::Day,
+            kind: Kind::Line,
+            from: to - Duration::days(DEFAULT_DAYS - 1),
+            to,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Period(period) => {
+                self.period = period;
+                true
+            }
+            Msg::Kind(kind) => {
+                self.kind = kind;
+                true
+            }
+            Msg::From(data) => match parse_date(data) {
+                Some(from) if from <= self.to => {
+                    self.from = from;
+                    true
+                }
+                _ => true,
+            },
+            Msg::To(data) => match parse_date(data) {
+                Some(to) if to >= self.from => {
+                    self.to = to;
+                    true
+                }
+                _ => true,
+            },
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> VNode {
+        wide_card(
+            "Sales over time",
+            "fa-chart-line",
+            &self.props.sales,
+            self.props.retry.clone(),
+            |list: &ListSale| {
+                let (period, from) = chart_range(self.period, self.from, self.to);
+                let points: Vec<Point> = sales_over_time(&list.data, period, from, self.to)
+                    .into_iter()
+                    .map(|(start, total)| Point {
+                        label: period.format(start),
+                        value: total,
+                    })
+                    .collect();
+                html! {
+                    <div class="sales-chart">
+                        { self.controls(period) }
+                        { truncated(list.data.len(), SALES_LIMIT) }
+                        { match self.kind {
+                            Kind::Line => html! { <LineChart points=points /> },
+                            Kind::Bar => html! { <BarChart points=points /> },
+                        } }
+                    </div>
+                }
+            },
+        )
+    }
+}
+
+impl Model {
+    /// The range and period pickers, with `period` the one the chart is
+    /// drawn with.
+    fn controls(&self, shown: Period) -> Html {
+        let button = |active: bool| {
+            if active {
+                "btn btn-sm btn-secondary"
+            } else {
+                "btn btn-sm btn-outline-secondary"
+            }
+        };
+        html! {
+            <div class="form-inline mb-3">
+                <input type="date" class="form-control form-control-sm mr-2 chart-from"
+                       value=self.from.format(DATE_FORMAT).to_string()
+                       onchange=self.link.callback(Msg::From) />
+                <input type="date" class="form-control form-control-sm mr-3 chart-to"
+                       value=self.to.format(DATE_FORMAT).to_string()
+                       onchange=self.link.callback(Msg::To) />
+                <div class="btn-group mr-3 chart-period">
+                    { for Period::ALL.iter().map(|period| {
+                        let period = *period;
+                        html! {
+                            <button type="button" class=button(shown == period)
+                                    onclick=self.link.callback(move |_| Msg::Period(period))>
+                                { period.label() }
+                            </button>
+                        }
+                    }) }
+                </div>
+                <div class="btn-group chart-kind">
+                    <button type="button" class=button(self.kind == Kind::Line)
+                            onclick=self.link.callback(|_| Msg::Kind(Kind::Line))>
+                        <i class="fas fa-chart-line"></i>
+                    </button>
+                    <button type="button" class=button(self.kind == Kind::Bar)
+                            onclick=self.link.callback(|_| Msg::Kind(Kind::Bar))>
+                        <i class="fas fa-chart-bar"></i>
+                    </button>
+                </div>
+            </div>
+        }
+    }
+}
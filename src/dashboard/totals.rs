@@ -0,0 +1,90 @@
+use yew::prelude::{
+    html, Callback, Component, ComponentLink, MouseEvent, Properties, ShouldRender,
+};
+use yew::virtual_dom::VNode;
+
+use super::kpi::DashboardSummary;
+use super::widget::card;
+use crate::dates::today;
+use crate::fetching::FetchState;
+use crate::models::{format_money, ListProduct, ListSale};
+
+#[derive(Properties, Clone)]
+pub struct Props {
+    pub sales: FetchState<ListSale>,
+    pub products: FetchState<ListProduct>,
+    pub summary: Option<DashboardSummary>,
+    pub retry: Callback<MouseEvent>,
+}
+
+/// Today's and this month's sales totals, from the `dashboard` query when
+/// it can be read and from the listed sales and products otherwise.
+pub struct Model {
+    props: Props,
+}
+
+impl Model {
+    fn summary(&self) -> FetchState<DashboardSummary> {
+        if let Some(summary) = &self.props.summary {
+            return FetchState::Success(summary.clone());
+        }
+        match (&self.props.sales, &self.props.products) {
+            (FetchState::Failed(error), _) | (_, FetchState::Failed(error)) => {
+                FetchState::Failed(error.clone())
+            }
+            (FetchState::Success(sales), FetchState::Success(products)) => FetchState::Success(
+                DashboardSummary::from_lists(&sales.data, &products.data, today()),
+            ),
+            _ => FetchState::Fetching,
+        }
+    }
+}
+
+impl Component for Model {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> VNode {
+        card(
+            "Sales",
+            "fa-cash-register",
+            &self.summary(),
+            self.props.retry.clone(),
+            |summary| {
+                html! {
+                    <div class="row text-center">
+                        <div class="col">
+                            <h3 class="sales-today">{ format_money(summary.today_sales) }</h3>
+                            <small class="text-muted">{ "Today" }</small>
+                        </div>
+                        <div class="col">
+                            <h3 class="sales-month">{ format_money(summary.month_sales) }</h3>
+                            <small class="text-muted">{ "This month" }</small>
+                        </div>
+                        <div class="col">
+                            <h3>{ summary.sales_count }</h3>
+                            <small class="text-muted">{ "Sales" }</small>
+                        </div>
+                        <div class="col">
+                            <h3>{ summary.products_count }</h3>
+                            <small class="text-muted">{ "Products" }</small>
+                        </div>
+                    </div>
+                }
+            },
+        )
+    }
+}
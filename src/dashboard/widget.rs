@@ -0,0 +1,55 @@
+use yew::prelude::{html, Callback, Html, MouseEvent};
+
+use crate::fetching::FetchState;
+use crate::views::{failed, loading};
+
+/// A dashboard card with its own loading and error display, so one failing
+/// query does not blank the whole page.
+pub fn card<T>(
+    title: &str,
+    icon: &str,
+    state: &FetchState<T>,
+    retry: Callback<MouseEvent>,
+    view: impl Fn(&T) -> Html,
+) -> Html {
+    column_card("col-md-6 mb-4", title, icon, state, retry, view)
+}
+
+/// A `card` as wide as the page.
+pub fn wide_card<T>(
+    title: &str,
+    icon: &str,
+    state: &FetchState<T>,
+    retry: Callback<MouseEvent>,
+    view: impl Fn(&T) -> Html,
+) -> Html {
+    column_card("col-12 mb-4", title, icon, state, retry, view)
+}
+
+fn column_card<T>(
+    column: &str,
+    title: &str,
+    icon: &str,
+    state: &FetchState<T>,
+    retry: Callback<MouseEvent>,
+    view: impl Fn(&T) -> Html,
+) -> Html {
+    let body = match state {
+        FetchState::Fetching => loading(),
+        FetchState::Success(data) => view(data),
+        FetchState::Failed(error) => failed(error, retry),
+    };
+    html! {
+        <div class=column>
+            <div class="card h-100">
+                <div class="card-header">
+                    <i class=format!("fas {}", icon)></i>
+                    { format!(" {}", title) }
+                </div>
+                <div class="card-body">
+                    { body }
+                </div>
+            </div>
+        </div>
+    }
+}
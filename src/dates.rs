@@ -0,0 +1,23 @@
+use chrono::NaiveDate;
+use yew::prelude::ChangeData;
+
+/// How `<input type="date">` reads and writes dates.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The browser's local date.
+pub fn today() -> NaiveDate {
+    let now = js_sys::Date::new_0();
+    NaiveDate::from_ymd(
+        now.get_full_year() as i32,
+        now.get_month() + 1,
+        now.get_date(),
+    )
+}
+
+/// The date picked in a date input's `onchange`.
+pub fn parse_date(data: ChangeData) -> Option<NaiveDate> {
+    match data {
+        ChangeData::Value(value) => NaiveDate::parse_from_str(&value, DATE_FORMAT).ok(),
+        _ => None,
+    }
+}
@@ -0,0 +1,201 @@
+use super::{Cell, Table};
+use crate::dates::DATE_FORMAT;
+
+/// How numbers are written. Only the decimal separator changes: digit
+/// grouping would keep spreadsheets from reading the values as numbers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NumberLocale {
+    pub decimal: char,
+}
+
+impl NumberLocale {
+    pub const POINT: NumberLocale = NumberLocale { decimal: '.' };
+    pub const COMMA: NumberLocale = NumberLocale { decimal: ',' };
+
+    /// The convention of a BCP 47 language tag such as `es-CO` or `en-US`.
+    pub fn for_language(tag: &str) -> Self {
+        let language = tag
+            .split(|c| c == '-' || c == '_')
+            .next()
+            .unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "de" | "es" | "fr" | "it" | "pt" | "nl" | "ru" | "pl" | "tr" | "sv" | "da" | "nb"
+            | "fi" | "cs" | "ro" | "id" => NumberLocale::COMMA,
+            _ => NumberLocale::POINT,
+        }
+    }
+
+    /// Reads a number written this way; the point is always accepted too.
+    pub fn parse(self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        let text = if self.decimal == '.' {
+            text.to_string()
+        } else {
+            text.replace(self.decimal, ".")
+        };
+        text.parse::<f64>().ok().filter(|value| value.is_finite())
+    }
+
+    pub fn format(self, value: f64) -> String {
+        let text = value.to_string();
+        if self.decimal == '.' {
+            text
+        } else {
+            text.replace('.', &self.decimal.to_string())
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub locale: NumberLocale,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self::for_locale(NumberLocale::POINT)
+    }
+}
+
+impl CsvOptions {
+    /// Semicolons where the comma is the decimal separator, as
+    /// spreadsheets in those locales expect.
+    pub fn for_locale(locale: NumberLocale) -> Self {
+        Self {
+            delimiter: if locale.decimal == ',' { ';' } else { ',' },
+            locale,
+        }
+    }
+}
+
+/// `table` as RFC 4180 CSV: CRLF line breaks, and fields holding the
+/// delimiter, a quote or a line break quoted with inner quotes doubled.
+pub fn write(table: &Table, options: CsvOptions) -> String {
+    let mut csv = String::new();
+    let headers: Vec<Cell> = table
+        .headers
+        .iter()
+        .map(|header| Cell::from(header.as_str()))
+        .collect();
+    for row in std::iter::once(&headers).chain(table.rows.iter()) {
+        let fields: Vec<String> = row
+            .iter()
+            .map(|cell| escape(&field(cell, options.locale), options.delimiter))
+            .collect();
+        csv.push_str(&fields.join(&options.delimiter.to_string()));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Leading characters that make a spreadsheet read a text cell as a
+/// formula; such cells are written behind a `'` so they stay text.
+const FORMULA_PREFIXES: &[char] = &['=', '+', '-', '@'];
+
+fn field(cell: &Cell, locale: NumberLocale) -> String {
+    match cell {
+        Cell::Empty => String::new(),
+        Cell::Text(text) if text.starts_with(FORMULA_PREFIXES) => format!("'{}", text),
+        Cell::Text(text) => text.clone(),
+        Cell::Integer(value) => value.to_string(),
+        Cell::Number(value) => locale.format(*value),
+        Cell::Date(date) => date.format(DATE_FORMAT).to_string(),
+    }
+}
+
+fn escape(field: &str, delimiter: char) -> String {
+    if field.contains(|c| c == delimiter || c == '"' || c == '\r' || c == '\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Delimiters `detect_delimiter` chooses from.
+const DELIMITERS: [char; 3] = [',', ';', '\t'];
+
+/// The delimiter the first line of `text` is most split by, outside quotes.
+pub fn detect_delimiter(text: &str) -> char {
+    let mut counts = [0usize; 3];
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '\r' | '\n' if !quoted => break,
+            _ if !quoted => {
+                if let Some(index) = DELIMITERS.iter().position(|delimiter| *delimiter == c) {
+                    counts[index] += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    let (index, count) = counts
+        .iter()
+        .enumerate()
+        .max_by_key(|(index, count)| (**count, std::cmp::Reverse(*index)))
+        .unwrap_or((0, &0));
+    if *count == 0 {
+        DELIMITERS[0]
+    } else {
+        DELIMITERS[index]
+    }
+}
+
+/// The records of RFC 4180 `text`. Line breaks may be CRLF, LF or CR, and
+/// a byte order mark is skipped. Quotes inside an unquoted field are kept
+/// as they are; a quoted field left open is an error.
+pub fn parse(text: &str, delimiter: char) -> Result<Vec<Vec<String>>, String> {
+    let text = text.trim_start_matches('\u{feff}');
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    // Whether the current field started with a quote.
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() && !quoted => {
+                quoted = true;
+                in_quotes = true;
+            }
+            '\r' | '\n' => {
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+                quoted = false;
+            }
+            _ if c == delimiter => {
+                record.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(format!(
+            "A quoted field on record {} is never closed",
+            records.len() + 1
+        ));
+    }
+    if !field.is_empty() || quoted || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
@@ -0,0 +1,123 @@
+use yew::prelude::{html, ChangeData, Component, ComponentLink, Properties, ShouldRender};
+use yew::services::ConsoleService;
+use yew::virtual_dom::VNode;
+
+use super::csv::{self, CsvOptions, NumberLocale};
+use super::{download, xlsx, Table};
+use crate::dates::{today, DATE_FORMAT};
+
+const DELIMITERS: [(char, &str); 3] = [(',', "Comma"), (';', "Semicolon"), ('\t', "Tab")];
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub table: Table,
+    /// File name without the date and extension, e.g. `sales`.
+    pub name: String,
+}
+
+/// Buttons that download `table` as CSV or XLSX.
+pub struct Model {
+    link: ComponentLink<Self>,
+    props: Props,
+    options: CsvOptions,
+}
+
+pub enum Msg {
+    Delimiter(ChangeData),
+    Csv,
+    Xlsx,
+}
+
+/// The number convention of the browser's language.
+fn browser_locale() -> NumberLocale {
+    web_sys::window()
+        .and_then(|window| window.navigator().language())
+        .map(|language| NumberLocale::for_language(&language))
+        .unwrap_or(NumberLocale::POINT)
+}
+
+impl Model {
+    fn filename(&self, extension: &str) -> String {
+        format!(
+            "{}-{}.{}",
+            self.props.name,
+            today().format(DATE_FORMAT),
+            extension
+        )
+    }
+
+    fn save(&self, extension: &str, mime: &str, bytes: &[u8]) {
+        if let Err(error) = download(&self.filename(extension), mime, bytes) {
+            ConsoleService::new().log(&format!("Error: {:?}", error));
+        }
+    }
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link,
+            props,
+            options: CsvOptions::for_locale(browser_locale()),
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Delimiter(ChangeData::Select(select)) => {
+                if let Some(delimiter) = select.value().chars().next() {
+                    self.options.delimiter = delimiter;
+                }
+                true
+            }
+            Msg::Delimiter(_) => false,
+            Msg::Csv => {
+                let csv = csv::write(&self.props.table, self.options);
+                self.save("csv", "text/csv;charset=utf-8", csv.as_bytes());
+                false
+            }
+            Msg::Xlsx => {
+                let bytes = xlsx::write(&self.props.table, &self.props.name);
+                self.save("xlsx", xlsx::MIME, &bytes);
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> VNode {
+        html! {
+            <div class="form-inline export-menu">
+                <select class="custom-select custom-select-sm mr-2 export-delimiter"
+                        onchange=self.link.callback(Msg::Delimiter)>
+                    { for DELIMITERS.iter().map(|(delimiter, label)| html! {
+                        <option value=delimiter.to_string()
+                                selected={*delimiter == self.options.delimiter}>
+                            { label }
+                        </option>
+                    }) }
+                </select>
+                <div class="btn-group">
+                    <button type="button" class="btn btn-sm btn-outline-primary export-csv"
+                            onclick=self.link.callback(|_| Msg::Csv)>
+                        <i class="fas fa-file-csv"></i>{ " CSV" }
+                    </button>
+                    <button type="button" class="btn btn-sm btn-outline-primary export-xlsx"
+                            onclick=self.link.callback(|_| Msg::Xlsx)>
+                        <i class="fas fa-file-excel"></i>{ " XLSX" }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Tables of sales, products and price lists written out as CSV or XLSX
+//! files. Everything but the download itself is plain Rust.
+
+use chrono::NaiveDate;
+use js_sys::{Array, Uint8Array};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::prelude::JsValue;
+use wasm_bindgen::JsCast;
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+pub mod csv;
+mod menu;
+pub mod tables;
+pub mod xlsx;
+pub mod zip;
+
+pub use menu::Model as ExportMenu;
+
+/// How long a download's object URL is kept after the click.
+const REVOKE_DELAY_MS: i32 = 1000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cell {
+    Empty,
+    Text(String),
+    Integer(i64),
+    Number(f64),
+    Date(NaiveDate),
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::Text(text.to_string())
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell::Text(text)
+    }
+}
+
+impl From<Option<String>> for Cell {
+    fn from(text: Option<String>) -> Self {
+        text.map(Cell::Text).unwrap_or(Cell::Empty)
+    }
+}
+
+impl From<i64> for Cell {
+    fn from(value: i64) -> Self {
+        Cell::Integer(value)
+    }
+}
+
+impl From<Option<i64>> for Cell {
+    fn from(value: Option<i64>) -> Self {
+        value.map(Cell::Integer).unwrap_or(Cell::Empty)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Cell::Number(value)
+    }
+}
+
+impl From<NaiveDate> for Cell {
+    fn from(date: NaiveDate) -> Self {
+        Cell::Date(date)
+    }
+}
+
+/// A header row and the rows under it, ready to be written in any format.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+/// Hands `bytes` to the browser as a file download named `filename`.
+pub fn download(filename: &str, mime: &str, bytes: &[u8]) -> Result<(), JsValue> {
+    let parts = Array::new();
+    parts.push(&Uint8Array::from(bytes));
+    let mut options = BlobPropertyBag::new();
+    options.type_(mime);
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window"))?;
+    let document = window
+        .document()
+        .ok_or_else(|| JsValue::from_str("No document"))?;
+    let body = document
+        .body()
+        .ok_or_else(|| JsValue::from_str("No document body"))?;
+    let anchor: HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    // Some browsers ignore clicks on detached anchors, and start the
+    // download only after the click returns, so the URL outlives it.
+    body.append_child(&anchor)?;
+    anchor.click();
+    anchor.remove();
+    let revoke = Closure::once_into_js(move || {
+        let _ = Url::revoke_object_url(&url);
+    });
+    window.set_timeout_with_callback_and_timeout_and_arguments_0(
+        revoke.unchecked_ref(),
+        REVOKE_DELAY_MS,
+    )?;
+    Ok(())
+}
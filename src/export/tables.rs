@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use super::{Cell, Table};
+use crate::models::{FullProduct, FullSale, Price};
+
+fn headers(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+pub fn sales(sales: &[FullSale]) -> Table {
+    Table {
+        headers: headers(&["Id", "Date", "Bill number", "State", "Lines", "Total"]),
+        rows: sales
+            .iter()
+            .map(|full_sale| {
+                let sale = &full_sale.sale;
+                vec![
+                    Cell::from(sale.id),
+                    Cell::from(sale.sale_date),
+                    Cell::from(sale.bill_number.clone()),
+                    Cell::from(sale.state.label()),
+                    Cell::from(full_sale.sale_products.len() as i64),
+                    Cell::from(sale.total),
+                ]
+            })
+            .collect(),
+    }
+}
+
+/// Products with a column per price list, holding the product's price in
+/// it, if it has one.
+pub fn products(products: &[FullProduct]) -> Table {
+    let price_lists: BTreeMap<i64, String> = products
+        .iter()
+        .flat_map(|full_product| &full_product.price_products)
+        .map(|full_price| (full_price.price.id, full_price.price.name.clone()))
+        .collect();
+    let mut table = Table {
+        headers: headers(&["Id", "Name", "Stock", "Cost", "Description"]),
+        rows: vec![],
+    };
+    table.headers.extend(price_lists.values().cloned());
+    table.rows = products
+        .iter()
+        .map(|full_product| {
+            let product = &full_product.product;
+            let mut row = vec![
+                Cell::from(product.id),
+                Cell::from(product.name.clone()),
+                Cell::from(product.stock),
+                Cell::from(product.cost),
+                Cell::from(product.description.clone()),
+            ];
+            row.extend(price_lists.keys().map(|price_id| {
+                let amount = full_product
+                    .price_products
+                    .iter()
+                    .find(|full_price| full_price.price.id == *price_id)
+                    .and_then(|full_price| full_price.price_product.amount);
+                Cell::from(amount)
+            }));
+            row
+        })
+        .collect();
+    table
+}
+
+pub fn prices(prices: &[Price]) -> Table {
+    Table {
+        headers: headers(&["Id", "Name"]),
+        rows: prices
+            .iter()
+            .map(|price| vec![Cell::from(price.id), Cell::from(price.name.clone())])
+            .collect(),
+    }
+}
@@ -0,0 +1,139 @@
+//! A single-sheet Office Open XML workbook. Strings are written inline, so
+//! the package needs no shared string table.
+
+use chrono::NaiveDate;
+
+use super::zip::ZipWriter;
+use super::{Cell, Table};
+
+pub const MIME: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/></Types>"#;
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+/// Style 0 is the default, style 1 shows a date serial as a date and
+/// style 2 is bold, for the header row.
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="3"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="14" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/></cellXfs></styleSheet>"#;
+
+const DATE_STYLE: u8 = 1;
+const HEADER_STYLE: u8 = 2;
+
+/// `table` as the bytes of an `.xlsx` file, on a sheet named `sheet`.
+pub fn write(table: &Table, sheet: &str) -> Vec<u8> {
+    let mut zip = ZipWriter::new();
+    zip.add("[Content_Types].xml", CONTENT_TYPES.as_bytes());
+    zip.add("_rels/.rels", ROOT_RELS.as_bytes());
+    zip.add("xl/workbook.xml", workbook_xml(sheet).as_bytes());
+    zip.add("xl/_rels/workbook.xml.rels", WORKBOOK_RELS.as_bytes());
+    zip.add("xl/styles.xml", STYLES.as_bytes());
+    zip.add("xl/worksheets/sheet1.xml", sheet_xml(table).as_bytes());
+    zip.finish()
+}
+
+fn workbook_xml(sheet: &str) -> String {
+    // Sheet names are at most 31 characters and some are reserved.
+    let name: String = sheet
+        .chars()
+        .filter(|c| !"[]:*?/\\".contains(*c))
+        .take(31)
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+        escape(&name)
+    )
+}
+
+/// The worksheet part, one `<row>` per table row after the header.
+pub fn sheet_xml(table: &Table) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+    );
+    let headers: Vec<Cell> = table
+        .headers
+        .iter()
+        .map(|header| Cell::from(header.as_str()))
+        .collect();
+    for (index, row) in std::iter::once(&headers)
+        .chain(table.rows.iter())
+        .enumerate()
+    {
+        let number = index + 1;
+        xml.push_str(&format!(r#"<row r="{}">"#, number));
+        for (column, cell) in row.iter().enumerate() {
+            let reference = format!("{}{}", column_name(column), number);
+            let style = if index == 0 { HEADER_STYLE } else { 0 };
+            xml.push_str(&cell_xml(&reference, cell, style));
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+fn cell_xml(reference: &str, cell: &Cell, style: u8) -> String {
+    let style = if style == 0 {
+        String::new()
+    } else {
+        format!(r#" s="{}""#, style)
+    };
+    match cell {
+        Cell::Empty => String::new(),
+        // Spreadsheets have no NaN or infinity and refuse the whole file.
+        Cell::Number(value) if !value.is_finite() => String::new(),
+        Cell::Text(text) => format!(
+            r#"<c r="{}"{} t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+            reference,
+            style,
+            escape(text)
+        ),
+        Cell::Integer(value) => format!(r#"<c r="{}"{}><v>{}</v></c>"#, reference, style, value),
+        Cell::Number(value) => format!(r#"<c r="{}"{}><v>{}</v></c>"#, reference, style, value),
+        Cell::Date(date) => format!(
+            r#"<c r="{}" s="{}"><v>{}</v></c>"#,
+            reference,
+            DATE_STYLE,
+            serial(*date)
+        ),
+    }
+}
+
+/// Spreadsheet column letters: 0 is A, 25 is Z, 26 is AA.
+pub fn column_name(index: usize) -> String {
+    let mut name = vec![];
+    let mut index = index + 1;
+    while index > 0 {
+        let rest = (index - 1) % 26;
+        name.push((b'A' + rest as u8) as char);
+        index = (index - 1) / 26;
+    }
+    name.iter().rev().collect()
+}
+
+/// Days since 1899-12-30, the way spreadsheets store dates.
+pub fn serial(date: NaiveDate) -> i64 {
+    (date - NaiveDate::from_ymd(1899, 12, 30)).num_days()
+}
+
+fn escape(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_control() || *c == '\t' || *c == '\n' || *c == '\r')
+        .fold(String::new(), |mut escaped, c| {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                _ => escaped.push(c),
+            }
+            escaped
+        })
+}
@@ -0,0 +1,103 @@
+//! Just enough of the ZIP format to package an XLSX file: entries are
+//! stored uncompressed, which every reader accepts.
+
+/// DOS date of 1980-01-01, the earliest a ZIP entry can carry.
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+struct Entry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+#[derive(Default)]
+pub struct ZipWriter {
+    bytes: Vec<u8>,
+    entries: Vec<Entry>,
+}
+
+impl ZipWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, name: &str, data: &[u8]) {
+        let entry = Entry {
+            name: name.to_string(),
+            crc: crc32(data),
+            size: data.len() as u32,
+            offset: self.bytes.len() as u32,
+        };
+        self.u32(0x0403_4b50);
+        self.u16(20); // version needed: 2.0
+        self.u16(0); // flags
+        self.u16(0); // stored
+        self.u16(0); // time
+        self.u16(DOS_DATE);
+        self.u32(entry.crc);
+        self.u32(entry.size);
+        self.u32(entry.size);
+        self.u16(entry.name.len() as u16);
+        self.u16(0); // extra field length
+        self.bytes.extend_from_slice(entry.name.as_bytes());
+        self.bytes.extend_from_slice(data);
+        self.entries.push(entry);
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        let directory = self.bytes.len() as u32;
+        let entries = std::mem::take(&mut self.entries);
+        for entry in &entries {
+            self.u32(0x0201_4b50);
+            self.u16(20); // version made by
+            self.u16(20); // version needed
+            self.u16(0);
+            self.u16(0);
+            self.u16(0);
+            self.u16(DOS_DATE);
+            self.u32(entry.crc);
+            self.u32(entry.size);
+            self.u32(entry.size);
+            self.u16(entry.name.len() as u16);
+            self.u16(0); // extra field length
+            self.u16(0); // comment length
+            self.u16(0); // disk number
+            self.u16(0); // internal attributes
+            self.u32(0); // external attributes
+            self.u32(entry.offset);
+            self.bytes.extend_from_slice(entry.name.as_bytes());
+        }
+        let size = self.bytes.len() as u32 - directory;
+        self.u32(0x0605_4b50);
+        self.u16(0);
+        self.u16(0);
+        self.u16(entries.len() as u16);
+        self.u16(entries.len() as u16);
+        self.u32(size);
+        self.u32(directory);
+        self.u16(0); // comment length
+        self.bytes
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// CRC-32 as ZIP uses it (IEEE polynomial, reflected).
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
@@ -4,16 +4,19 @@ use std::future::Future;
 use wasm_bindgen::prelude::JsValue;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::spawn_local;
-use wasm_bindgen_futures::JsFuture;
-use web_sys::{
-    Headers, HtmlDocument, Request, RequestCredentials, RequestInit, RequestMode, Response,
-};
+use web_sys::{Headers, HtmlDocument};
 use yew::prelude::{Component, ComponentLink};
 use yew::services::ConsoleService;
 
+use crate::offline::clear_snapshot;
 use crate::routing::{AppRoute, Redirecter};
+use crate::transport::{current_transport, TransportRequest};
+use crate::CurrentUser;
 
 const TOKEN_KEY: &str = "mystore.key";
+const USER_KEY: &str = "mystore.user";
+/// Correlates a request across the client, the server and the backend logs.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
 
 pub struct FetchResponse<T> {
     pub headers: Headers,
@@ -70,37 +73,43 @@ where
     T: Serialize,
     R: Serialize + for<'b> Deserialize<'b>,
 {
-    let mut opts = RequestInit::new();
-    opts.method(method);
-    opts.mode(RequestMode::Cors);
-    opts.credentials(RequestCredentials::SameOrigin);
+    let transport = current_transport();
+    let request_id = new_request_id();
+    let mut request = TransportRequest {
+        url: url.to_string(),
+        method: method.to_string(),
+        headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+        body: None,
+    };
+    // The default API origin does not allow the header in its CORS
+    // preflight; the id is still logged here either way.
+    if transport.is_same_origin() {
+        request
+            .headers
+            .push((REQUEST_ID_HEADER.to_string(), request_id.clone()));
+    }
     if let Some(maybe_data) = data {
         if let Ok(data_str) = serde_json::to_string(&maybe_data) {
-            opts.body(Some(&JsValue::from_str(&data_str)));
+            request.body = Some(data_str);
         }
     }
-
-    let base_url = "http://localhost:8088";
-
-    let request = Request::new_with_str_and_init(&format!("{}{}", base_url, url), &opts)?;
-    request.headers().set("Content-Type", "application/json")?;
     if let Ok(token) = get_token() {
-        request.headers().set("x-csrf-token", &token)?;
+        request.headers.push(("x-csrf-token".to_string(), token));
     }
 
-    let window =
-        web_sys::window().ok_or_else(|| JsValue::from_str("Could not get a window object"))?;
-    let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
-    let resp: Response = resp_value.dyn_into()?;
-
-    let headers = resp.headers();
+    let FetchResponse {
+        headers,
+        data: json,
+    } = transport.fetch(request).await.map_err(|error| {
+        ConsoleService::new().log(&format!("Request {} failed: {}", request_id, error));
+        error
+    })?;
     if let Err(_) = validate_token(&headers) {
         let mut redirecter = Redirecter::new();
         redirecter.redirect(AppRoute::Login);
         ConsoleService::new().log("Redirecting!")
     }
 
-    let json = JsFuture::from(resp.json()?).await?;
     match json.into_serde::<R>() {
         Ok(value) => JsValue::from_serde(&value)
             .map(|data| FetchResponse { headers, data })
@@ -113,6 +122,26 @@ where
     }
 }
 
+/// A random version 4 UUID. Request ids only need to be unique enough to
+/// tell log lines apart, so `Math.random` will do.
+pub(crate) fn new_request_id() -> String {
+    let mut bytes = [0u8; 16];
+    for byte in bytes.iter_mut() {
+        *byte = (js_sys::Math::random() * 256.0) as u8;
+    }
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
 pub fn save_token(headers: Headers) -> Result<bool, FetchError> {
     let token = headers
         .get("x-csrf-token")?
@@ -120,12 +149,20 @@ pub fn save_token(headers: Headers) -> Result<bool, FetchError> {
     store_token(token)
 }
 
+/// Ends the session on this device: the token, the saved user and the
+/// offline snapshot of their data all go.
 pub fn delete_token() -> Result<bool, FetchError> {
+    spawn_local(async {
+        if let Err(error) = clear_snapshot().await {
+            ConsoleService::new().log(&format!("Error: {}", &error));
+        }
+    });
     let window =
         web_sys::window().ok_or_else(|| JsValue::from_str("Could not get a window object"))?;
     let maybe_storage = window.local_storage()?;
     if let Some(storage) = maybe_storage {
         storage.remove_item(TOKEN_KEY)?;
+        storage.remove_item(USER_KEY)?;
         Ok(true)
     } else {
         Err(FetchError {
@@ -134,6 +171,28 @@ pub fn delete_token() -> Result<bool, FetchError> {
     }
 }
 
+/// Keeps the user `/login` answered with, for pages that show who is
+/// signed in. `delete_token` forgets it.
+pub fn save_current_user(data: &JsValue) -> Result<bool, FetchError> {
+    let user: CurrentUser = data
+        .into_serde()
+        .map_err(|error| JsValue::from_str(&error.to_string()))?;
+    let json =
+        serde_json::to_string(&user).map_err(|error| JsValue::from_str(&error.to_string()))?;
+    let window =
+        web_sys::window().ok_or_else(|| JsValue::from_str("Could not get a window object"))?;
+    if let Some(storage) = window.local_storage()? {
+        storage.set_item(USER_KEY, &json)?;
+    }
+    Ok(true)
+}
+
+pub fn current_user() -> Option<CurrentUser> {
+    let storage = web_sys::window()?.local_storage().ok()??;
+    let json = storage.get_item(USER_KEY).ok()??;
+    serde_json::from_str(&json).ok()
+}
+
 pub fn set_cookie(headers: Headers) -> Result<bool, FetchError> {
     let cookie = headers
         .get("set-cookie")?
@@ -142,7 +201,7 @@ pub fn set_cookie(headers: Headers) -> Result<bool, FetchError> {
     Ok(true)
 }
 
-fn get_token() -> Result<String, FetchError> {
+pub(crate) fn get_token() -> Result<String, FetchError> {
     let window =
         web_sys::window().ok_or_else(|| JsValue::from_str("Could not get a window object"))?;
     let maybe_storage = window.local_storage()?;
@@ -172,7 +231,7 @@ fn validate_token(headers: &Headers) -> Result<bool, FetchError> {
     Ok(true)
 }
 
-fn store_token(token: String) -> Result<bool, FetchError> {
+pub(crate) fn store_token(token: String) -> Result<bool, FetchError> {
     let window =
         web_sys::window().ok_or_else(|| JsValue::from_str("Could not get a window object"))?;
     let maybe_storage = window.local_storage()?;
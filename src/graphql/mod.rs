@@ -1,9 +1,6 @@
 use chrono::NaiveDate;
 use graphql_client::GraphQLQuery;
 use serde::{Deserialize, Serialize};
-use wasm_bindgen::prelude::JsValue;
-
-use crate::fetching::{send_request, FetchError, FetchResponse};
 
 #[derive(GraphQLQuery, Serialize, Deserialize)]
 #[graphql(
@@ -21,10 +18,66 @@ pub struct Dashboard;
 )]
 pub struct ListSale;
 
-pub async fn fetch_graphql<T, R>(request_body: T) -> Result<FetchResponse<JsValue>, FetchError>
-where
-    T: Serialize,
-    R: Serialize + for<'b> Deserialize<'b>,
-{
-    send_request::<T, R>("/graphql", Some(&request_body), "POST").await
-}
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/graphql/schema.graphql",
+    query_path = "src/graphql/show_sale.graphql",
+    response_derives = "Debug"
+)]
+pub struct ShowSale;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/graphql/schema.graphql",
+    query_path = "src/graphql/list_product.graphql",
+    response_derives = "Debug"
+)]
+pub struct ListProduct;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/graphql/schema.graphql",
+    query_path = "src/graphql/list_price.graphql",
+    response_derives = "Debug"
+)]
+pub struct ListPrice;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/graphql/schema.graphql",
+    query_path = "src/graphql/create_product.graphql",
+    response_derives = "Debug"
+)]
+pub struct CreateProduct;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/graphql/schema.graphql",
+    query_path = "src/graphql/update_product.graphql",
+    response_derives = "Debug"
+)]
+pub struct UpdateProduct;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/graphql/schema.graphql",
+    query_path = "src/graphql/create_sale.graphql",
+    response_derives = "Debug"
+)]
+pub struct CreateSale;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/graphql/schema.graphql",
+    query_path = "src/graphql/approve_sale.graphql",
+    response_derives = "Debug"
+)]
+pub struct ApproveSale;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/graphql/schema.graphql",
+    query_path = "src/graphql/pay_sale.graphql",
+    response_derives = "Debug"
+)]
+pub struct PaySale;
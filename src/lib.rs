@@ -4,32 +4,58 @@
 extern crate validator_derive;
 extern crate validator;
 
-mod fetching;
-mod login;
-mod routing;
-mod register;
+pub mod fetching;
+pub mod login;
+pub mod routing;
+pub mod register;
 mod index;
-mod dashboard;
+pub mod dashboard;
+pub mod dates;
+pub mod export;
+pub mod navbar;
+pub mod prices;
+pub mod products;
+pub mod reports;
+pub mod sales;
+pub mod cache;
+pub mod charts;
+pub mod models;
+pub mod offline;
+pub mod pos;
+pub mod pricing;
+mod offline_status;
 mod graphql;
+pub mod transport;
+mod views;
 
 use login::Model as Login;
 use register::Model as Register;
 use index::Model as Index;
 use dashboard::Model as Dashboard;
+use reports::Model as Reports;
+use sales::Model as Sales;
+use sales::detail::Model as SaleDetail;
+use products::Model as Products;
+use products::import::Model as ProductImport;
+use prices::Model as Prices;
+use pos::Model as Pos;
+use offline_status::Model as OfflineStatus;
 use routing::AppRoute;
 use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use transport::{set_transport, Transport};
 use wasm_bindgen::prelude::*;
 use yew::prelude::*;
 use yew::virtual_dom::VNode;
 use yew_router::switch::Permissive;
 use yew_router::{prelude::Router, route::Route};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct CurrentUser {
-    id: i32,
-    email: String,
-    company: String,
-    created_at: String
+    pub id: i32,
+    pub email: String,
+    pub company: String,
+    pub created_at: String
 }
 
 pub struct Model {}
@@ -53,6 +79,7 @@ impl Component for Model {
     fn view(&self) -> VNode {
         html! {
             <div class="container">
+                <OfflineStatus />
                 <Router <AppRoute>
                   render = Router::render(|switch: AppRoute| {
                       match switch {
@@ -60,6 +87,13 @@ impl Component for Model {
                         AppRoute::Register => html! { <Register /> },
                         AppRoute::Index => html!{ <Index /> },
                         AppRoute::Dashboard => html!{ <Dashboard /> },
+                        AppRoute::Reports => html!{ <Reports /> },
+                        AppRoute::Sale(id) => html!{ <SaleDetail id=id /> },
+                        AppRoute::Sales => html!{ <Sales /> },
+                        AppRoute::ProductImport => html!{ <ProductImport /> },
+                        AppRoute::Products => html!{ <Products /> },
+                        AppRoute::Prices => html!{ <Prices /> },
+                        AppRoute::Pos => html!{ <Pos /> },
                         AppRoute::PageNotFound(Permissive(None)) => html!{"Page not found"},
                         AppRoute::PageNotFound(Permissive(Some(missed_route))) => html!{format!("Page '{}' not found", missed_route)}
                       }
@@ -77,3 +111,8 @@ impl Component for Model {
 pub fn run_app() {
     yew::start_app::<Model>();
 }
+
+pub fn run_app_with_transport(transport: Rc<dyn Transport>) {
+    set_transport(transport);
+    yew::start_app::<Model>();
+}
@@ -7,7 +7,8 @@ use yew::virtual_dom::VNode;
 use yew_router::prelude::RouterAnchor;
 
 use crate::fetching::{
-    save_token, send_future, send_request, FetchError, FetchResponse, FetchState, set_cookie,
+    save_current_user, save_token, send_future, send_request, FetchError, FetchResponse,
+    FetchState, set_cookie,
 };
 use crate::routing::{AppRoute, Redirecter};
 use crate::CurrentUser;
@@ -84,6 +85,9 @@ impl Component for Model {
                         if let Err(_) = set_cookie(response.headers) {
                             ConsoleService::new().log("Error saving cookie!");
                         }
+                        if save_current_user(&response.data).is_err() {
+                            ConsoleService::new().log("Error saving user!");
+                        }
                         let mut redirecter = Redirecter::new();
                         redirecter.redirect(AppRoute::Dashboard);
                         ConsoleService::new().log("Success")
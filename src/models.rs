@@ -0,0 +1,169 @@
+//! Plain Rust mirrors of the GraphQL types, shared by the screens and the
+//! code that computes over sales and products. They decode the `data` the
+//! cache agent hands out, ignoring `__typename`.
+
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SaleState {
+    Draft,
+    Approved,
+    PartiallyPayed,
+    Payed,
+    Cancelled,
+}
+
+impl SaleState {
+    pub const ALL: [SaleState; 5] = [
+        SaleState::Draft,
+        SaleState::Approved,
+        SaleState::PartiallyPayed,
+        SaleState::Payed,
+        SaleState::Cancelled,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SaleState::Draft => "Draft",
+            SaleState::Approved => "Approved",
+            SaleState::PartiallyPayed => "Partially paid",
+            SaleState::Payed => "Paid",
+            SaleState::Cancelled => "Cancelled",
+        }
+    }
+
+    /// Bootstrap badge class for the state.
+    pub fn badge(self) -> &'static str {
+        match self {
+            SaleState::Draft => "badge badge-secondary",
+            SaleState::Approved => "badge badge-info",
+            SaleState::PartiallyPayed => "badge badge-warning",
+            SaleState::Payed => "badge badge-success",
+            SaleState::Cancelled => "badge badge-danger",
+        }
+    }
+
+    /// Chart colour matching `badge`.
+    pub fn color(self) -> &'static str {
+        match self {
+            SaleState::Draft => "#6c757d",
+            SaleState::Approved => "#17a2b8",
+            SaleState::PartiallyPayed => "#ffc107",
+            SaleState::Payed => "#28a745",
+            SaleState::Cancelled => "#dc3545",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Sale {
+    pub id: i64,
+    pub user_id: i64,
+    pub sale_date: NaiveDate,
+    pub total: f64,
+    pub bill_number: Option<String>,
+    pub state: SaleState,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SaleProduct {
+    pub id: i64,
+    pub product_id: i64,
+    pub sale_id: i64,
+    pub amount: f64,
+    pub discount: i64,
+    pub tax: i64,
+    pub price: i64,
+    pub total: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Product {
+    pub id: i64,
+    pub name: String,
+    pub stock: f64,
+    pub cost: Option<i64>,
+    pub description: Option<String>,
+    pub user_id: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Price {
+    pub id: i64,
+    pub name: String,
+    pub user_id: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceProduct {
+    pub id: i64,
+    pub price_id: i64,
+    pub product_id: i64,
+    pub user_id: i64,
+    pub amount: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FullSaleProduct {
+    pub sale_product: SaleProduct,
+    pub product: Product,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FullSale {
+    pub sale: Sale,
+    pub sale_products: Vec<FullSaleProduct>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FullPriceProduct {
+    pub price_product: PriceProduct,
+    pub price: Price,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FullProduct {
+    pub product: Product,
+    pub price_products: Vec<FullPriceProduct>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ListSale {
+    pub data: Vec<FullSale>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ListProduct {
+    pub data: Vec<FullProduct>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ListPrice {
+    pub data: Vec<Price>,
+}
+
+/// Decodes `data[field]` of a GraphQL response, e.g. `listSale`.
+pub fn decode<T: DeserializeOwned>(data: &Value, field: &str) -> Result<T, String> {
+    match data.get(field) {
+        Some(value) => serde_json::from_value(value.clone()).map_err(|error| error.to_string()),
+        None => Err(format!("The response has no {}", field)),
+    }
+}
+
+/// Two decimals, as amounts are shown throughout the app.
+pub fn format_money(amount: f64) -> String {
+    format!("{:.2}", amount)
+}
@@ -1,20 +1,20 @@
-use graphql_client::{GraphQLQuery, QueryBody};
 use wasm_bindgen::prelude::JsValue;
+use yew::agent::Dispatched;
 use yew::prelude::{html, Component, ComponentLink, ShouldRender};
 use yew::services::ConsoleService;
 use yew::virtual_dom::VNode;
+use yew_router::prelude::RouterAnchor;
 
+use crate::cache::{CacheAgent, Request};
 use crate::fetching::{delete_token, send_future, send_request, FetchState};
-use crate::graphql;
 use crate::routing::{AppRoute, Redirecter};
 
+/// The signed-in pages' navigation, with the logout button.
 pub struct Model {
     link: ComponentLink<Self>,
 }
 
 pub enum Msg {
-    OnLoad,
-    Loaded(FetchState<JsValue>),
     Logout,
     LoggedOut(FetchState<JsValue>),
 }
@@ -24,26 +24,11 @@ impl Component for Model {
     type Properties = ();
 
     fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
-        link.send_message(Msg::OnLoad);
         Self { link }
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
-            Msg::OnLoad => {
-                let future = async move {
-                    match graphql::fetch_graphql::<QueryBody<graphql::dashboard::Variables>, String>(
-                        graphql::Dashboard::build_query(graphql::dashboard::Variables),
-                    )
-                    .await
-                    {
-                        Ok(response) => Msg::Loaded(FetchState::Success(response.data)),
-                        Err(error) => Msg::Loaded(FetchState::Failed(error)),
-                    }
-                };
-                send_future(self.link.clone(), future);
-                true
-            }
             Msg::Logout => {
                 let future = async move {
                     match send_request::<Option<String>, String>("/logout", None, "DELETE").await {
@@ -54,6 +39,7 @@ impl Component for Model {
                 if let Err(error) = delete_token() {
                     ConsoleService::new().log(&format!("Error: {}", &error));
                 }
+                CacheAgent::dispatcher().send(Request::Clear);
                 send_future(self.link.clone(), future);
                 true
             }
@@ -71,16 +57,6 @@ impl Component for Model {
                 };
                 true
             }
-            Msg::Loaded(fetch_state) => {
-                match fetch_state {
-                    FetchState::Success(_) => ConsoleService::new().log("Success"),
-                    FetchState::Failed(error) => {
-                        ConsoleService::new().log(&format!("Error: {}", &error.to_string()))
-                    }
-                    FetchState::Fetching => ConsoleService::new().log("Fetching"),
-                };
-                true
-            }
         }
     }
 
@@ -90,18 +66,35 @@ impl Component for Model {
 
     fn view(&self) -> VNode {
         html! {
-            <div>
-                <nav class="navbar navbar-expand-lg navbar-light bg-light">
-                    <a class="navbar-brand" href="#">
-                        <i class="fas fa-store"></i>
-                        {"My Store"}
-                    </a>
+            <nav class="navbar navbar-expand-lg navbar-light bg-light d-print-none">
+                <a class="navbar-brand" href="#">
+                    <i class="fas fa-store"></i>
+                    {"My Store"}
+                </a>
+                <div class="navbar-nav mr-auto">
+                    <RouterAnchor<AppRoute> route=AppRoute::Dashboard classes="nav-link">
+                        {"Dashboard"}
+                    </RouterAnchor<AppRoute>>
+                    <RouterAnchor<AppRoute> route=AppRoute::Pos classes="nav-link">
+                        {"Point of sale"}
+                    </RouterAnchor<AppRoute>>
+                    <RouterAnchor<AppRoute> route=AppRoute::Sales classes="nav-link">
+                        {"Sales"}
+                    </RouterAnchor<AppRoute>>
+                    <RouterAnchor<AppRoute> route=AppRoute::Products classes="nav-link">
+                        {"Products"}
+                    </RouterAnchor<AppRoute>>
+                    <RouterAnchor<AppRoute> route=AppRoute::Prices classes="nav-link">
+                        {"Price lists"}
+                    </RouterAnchor<AppRoute>>
+                    <RouterAnchor<AppRoute> route=AppRoute::Reports classes="nav-link">
+                        {"Reports"}
+                    </RouterAnchor<AppRoute>>
+                </div>
 
-                    <button onclick=self.link.callback(|_| Msg::Logout)
-                            class="btn btn-info my-4">{ "Logout" }</button>
-                </nav>
-                <h1> { "Dashboard" } </h1>
-            </div>
+                <button onclick=self.link.callback(|_| Msg::Logout)
+                        class="btn btn-info my-4">{ "Logout" }</button>
+            </nav>
         }
     }
 }
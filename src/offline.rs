@@ -0,0 +1,298 @@
+use js_sys::Promise;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::prelude::JsValue;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbRequest, IdbTransactionMode};
+use yew::Callback;
+
+use crate::fetching::FetchError;
+
+const DB_NAME: &str = "mystore";
+const DB_VERSION: u32 = 1;
+const STORE_NAME: &str = "state";
+const SNAPSHOT_KEY: &str = "snapshot";
+
+/// Mutations that create an entity, with the `__typename` they return. These
+/// get a temporary negative id while they wait in the queue.
+const CREATING_MUTATIONS: [(&str, &str); 3] = [
+    ("CreateSale", "Sale"),
+    ("CreateProduct", "Product"),
+    ("CreatePrice", "Price"),
+];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TempId {
+    pub typename: String,
+    pub id: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct QueuedMutation {
+    pub body: Value,
+    pub temp_id: Option<TempId>,
+}
+
+impl QueuedMutation {
+    pub fn operation(&self) -> &str {
+        self.body["operationName"].as_str().unwrap_or_default()
+    }
+
+    /// The body sent on replay. The temporary id is dropped from the form so
+    /// the server assigns the real one.
+    pub fn outgoing_body(&self) -> Value {
+        let mut body = self.body.clone();
+        if self.temp_id.is_some() {
+            body["variables"]["form"]["id"] = Value::Null;
+        }
+        body
+    }
+
+    /// The entity to show in the cache until the mutation reaches the server.
+    pub fn optimistic_entity(&self) -> Option<Value> {
+        let temp_id = self.temp_id.as_ref()?;
+        let mut entity = self.body["variables"]["form"].clone();
+        entity["__typename"] = json!(temp_id.typename);
+        entity["id"] = json!(temp_id.id);
+        Some(entity)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct MutationQueue {
+    items: VecDeque<QueuedMutation>,
+    next_temp_id: i64,
+}
+
+impl MutationQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn enqueue(&mut self, mut body: Value) -> QueuedMutation {
+        let operation = body["operationName"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let temp_id = match CREATING_MUTATIONS
+            .iter()
+            .find(|(name, _)| *name == operation)
+        {
+            Some((_, typename)) => {
+                self.next_temp_id -= 1;
+                body["variables"]["form"]["id"] = json!(self.next_temp_id);
+                Some(TempId {
+                    typename: typename.to_string(),
+                    id: self.next_temp_id,
+                })
+            }
+            None => None,
+        };
+        let mutation = QueuedMutation { body, temp_id };
+        self.items.push_back(mutation.clone());
+        mutation
+    }
+
+    pub fn front(&self) -> Option<&QueuedMutation> {
+        self.items.front()
+    }
+
+    pub fn pop_front(&mut self) -> Option<QueuedMutation> {
+        self.items.pop_front()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Rewrites every use of `temp_id` in the mutations still queued, once
+    /// the server has assigned `real_id` to the entity.
+    pub fn reconcile(&mut self, temp_id: i64, real_id: i64) {
+        for item in self.items.iter_mut() {
+            replace_id(&mut item.body["variables"], temp_id, real_id);
+        }
+    }
+
+    /// Puts the mutations restored from a previous session in front of the
+    /// ones queued since. Both sessions count temporary ids down from -1, so
+    /// the restored ones are moved below those already handed out.
+    pub fn restore(&mut self, mut previous: MutationQueue) {
+        let offset = self.next_temp_id;
+        if offset != 0 {
+            let mut temp_ids: Vec<i64> = previous
+                .items
+                .iter()
+                .filter_map(|item| item.temp_id.as_ref().map(|temp_id| temp_id.id))
+                .collect();
+            // Most negative first, so no id is moved onto one still to move.
+            temp_ids.sort_unstable();
+            for temp_id in temp_ids {
+                for item in previous.items.iter_mut() {
+                    replace_id(&mut item.body["variables"], temp_id, temp_id + offset);
+                    if let Some(own) = item.temp_id.as_mut().filter(|own| own.id == temp_id) {
+                        own.id = temp_id + offset;
+                    }
+                }
+            }
+        }
+        previous.items.extend(self.items.drain(..));
+        self.items = previous.items;
+        self.next_temp_id = offset + previous.next_temp_id;
+    }
+}
+
+fn replace_id(value: &mut Value, from: i64, to: i64) {
+    match value {
+        Value::Number(number) if number.as_i64() == Some(from) => *value = json!(to),
+        Value::Array(items) => items.iter_mut().for_each(|item| replace_id(item, from, to)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|field| replace_id(field, from, to)),
+        _ => {}
+    }
+}
+
+/// Finds the id the server gave to the first `typename` entity in `data`.
+pub fn created_id(data: &Value, typename: &str) -> Option<i64> {
+    match data {
+        Value::Array(items) => items.iter().find_map(|item| created_id(item, typename)),
+        Value::Object(fields) => {
+            if fields.get("__typename").and_then(Value::as_str) == Some(typename) {
+                if let Some(id) = fields.get("id").and_then(Value::as_i64) {
+                    return Some(id);
+                }
+            }
+            fields
+                .values()
+                .find_map(|field| created_id(field, typename))
+        }
+        _ => None,
+    }
+}
+
+pub fn is_online() -> bool {
+    web_sys::window()
+        .map(|window| window.navigator().on_line())
+        .unwrap_or(true)
+}
+
+/// Emits `true`/`false` on the window `online`/`offline` events for as long
+/// as it is alive.
+pub struct ConnectivityListener {
+    online: Closure<dyn Fn()>,
+    offline: Closure<dyn Fn()>,
+}
+
+impl ConnectivityListener {
+    pub fn new(callback: Callback<bool>) -> Self {
+        let on_online = callback.clone();
+        let online = Closure::wrap(Box::new(move || on_online.emit(true)) as Box<dyn Fn()>);
+        let offline = Closure::wrap(Box::new(move || callback.emit(false)) as Box<dyn Fn()>);
+        if let Some(window) = web_sys::window() {
+            let _ =
+                window.add_event_listener_with_callback("online", online.as_ref().unchecked_ref());
+            let _ = window
+                .add_event_listener_with_callback("offline", offline.as_ref().unchecked_ref());
+        }
+        Self { online, offline }
+    }
+}
+
+impl Drop for ConnectivityListener {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            let _ = window.remove_event_listener_with_callback(
+                "online",
+                self.online.as_ref().unchecked_ref(),
+            );
+            let _ = window.remove_event_listener_with_callback(
+                "offline",
+                self.offline.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
+
+pub async fn save_snapshot(snapshot: String) -> Result<(), FetchError> {
+    let db = open_db().await?;
+    let transaction =
+        db.transaction_with_str_and_mode(STORE_NAME, IdbTransactionMode::Readwrite)?;
+    let request = transaction.object_store(STORE_NAME)?.put_with_key(
+        &JsValue::from_str(&snapshot),
+        &JsValue::from_str(SNAPSHOT_KEY),
+    )?;
+    request_future(&request).await?;
+    Ok(())
+}
+
+pub async fn load_snapshot() -> Result<Option<String>, FetchError> {
+    let db = open_db().await?;
+    let transaction = db.transaction_with_str(STORE_NAME)?;
+    let request = transaction
+        .object_store(STORE_NAME)?
+        .get(&JsValue::from_str(SNAPSHOT_KEY))?;
+    Ok(request_future(&request).await?.as_string())
+}
+
+/// Deletes the snapshot, so the next start has nothing to restore.
+pub async fn clear_snapshot() -> Result<(), FetchError> {
+    let db = open_db().await?;
+    let transaction =
+        db.transaction_with_str_and_mode(STORE_NAME, IdbTransactionMode::Readwrite)?;
+    let request = transaction
+        .object_store(STORE_NAME)?
+        .delete(&JsValue::from_str(SNAPSHOT_KEY))?;
+    request_future(&request).await?;
+    Ok(())
+}
+
+async fn open_db() -> Result<IdbDatabase, FetchError> {
+    let window =
+        web_sys::window().ok_or_else(|| JsValue::from_str("Could not get a window object"))?;
+    let factory = window
+        .indexed_db()?
+        .ok_or_else(|| JsValue::from_str("IndexedDB is not available"))?;
+    let open_request = factory.open_with_u32(DB_NAME, DB_VERSION)?;
+
+    let upgrading = open_request.clone();
+    let on_upgrade = Closure::once_into_js(move || {
+        if let Ok(result) = upgrading.result() {
+            let db: IdbDatabase = result.unchecked_into();
+            let _ = db.create_object_store(STORE_NAME);
+        }
+    });
+    open_request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+    Ok(request_future(&open_request).await?.unchecked_into())
+}
+
+/// Resolves once an IndexedDB request succeeds, with its result.
+async fn request_future(request: &IdbRequest) -> Result<JsValue, FetchError> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let succeeded = request.clone();
+        let on_success = Closure::once_into_js(move || {
+            let result = succeeded.result().unwrap_or(JsValue::UNDEFINED);
+            let _ = resolve.call1(&JsValue::NULL, &result);
+        });
+        let failed = request.clone();
+        let on_error = Closure::once_into_js(move || {
+            let error = failed
+                .error()
+                .ok()
+                .flatten()
+                .map(JsValue::from)
+                .unwrap_or_else(|| JsValue::from_str("IndexedDB request failed"));
+            let _ = reject.call1(&JsValue::NULL, &error);
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    Ok(JsFuture::from(promise).await?)
+}
@@ -0,0 +1,90 @@
+use yew::agent::{Bridge, Bridged};
+use yew::prelude::{html, Component, ComponentLink, ShouldRender};
+use yew::virtual_dom::VNode;
+
+use crate::cache::{CacheAgent, Response};
+
+pub struct Model {
+    link: ComponentLink<Self>,
+    online: bool,
+    pending: usize,
+    conflicts: Vec<(String, String)>,
+    _cache: Box<dyn Bridge<CacheAgent>>,
+}
+
+pub enum Msg {
+    Cache(Response),
+    Dismiss(usize),
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let cache = CacheAgent::bridge(link.callback(Msg::Cache));
+        Self {
+            link,
+            online: true,
+            pending: 0,
+            conflicts: vec![],
+            _cache: cache,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Cache(Response::Status { online, pending }) => {
+                self.online = online;
+                self.pending = pending;
+                true
+            }
+            Msg::Cache(Response::Conflict { operation, message }) => {
+                self.conflicts.push((operation, message));
+                true
+            }
+            Msg::Cache(Response::Query { .. }) => false,
+            Msg::Dismiss(index) => {
+                self.conflicts.remove(index);
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> VNode {
+        let status = if !self.online {
+            html! {
+                <div class="alert alert-warning">
+                    <i class="fas fa-wifi"></i>
+                    { format!(" Offline: {} pending change(s) will be sent on reconnect", self.pending) }
+                </div>
+            }
+        } else if self.pending > 0 {
+            html! {
+                <div class="alert alert-info">
+                    { format!("Sending {} pending change(s)...", self.pending) }
+                </div>
+            }
+        } else {
+            html! {}
+        };
+        html! {
+            <div>
+                { status }
+                { for self.conflicts.iter().enumerate().map(|(index, (operation, message))| html! {
+                    <div class="alert alert-danger">
+                        { format!("{} could not be saved: {}", operation, message) }
+                        <button type="button" class="close"
+                                onclick=self.link.callback(move |_| Msg::Dismiss(index))>
+                            { "×" }
+                        </button>
+                    </div>
+                }) }
+            </div>
+        }
+    }
+}
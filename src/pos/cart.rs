@@ -0,0 +1,212 @@
+use chrono::NaiveDate;
+
+use crate::models::{
+    FullProduct, FullSale, FullSaleProduct, Product, Sale, SaleProduct, SaleState,
+};
+use crate::pricing::{line_totals, Discount, LineInput, LineTotals, Money, Quantity, Rules};
+
+/// A product being sold, at the price it was rung up at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CartLine {
+    pub product: Product,
+    pub price: i64,
+    pub amount: f64,
+    pub discount: i64,
+    pub tax: i64,
+}
+
+impl CartLine {
+    pub fn totals(&self) -> LineTotals {
+        let input = LineInput {
+            price: Money::from_units(self.price),
+            quantity: Quantity::from_f64(self.amount),
+            discount: Discount::Percent(self.discount),
+            tax_rate: self.tax,
+        };
+        line_totals(&input, Rules::default())
+    }
+
+    pub fn sale_product(&self) -> SaleProduct {
+        SaleProduct {
+            id: 0,
+            product_id: self.product.id,
+            sale_id: 0,
+            amount: self.amount,
+            discount: self.discount,
+            tax: self.tax,
+            price: self.price,
+            total: self.totals().total.to_f64(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cart {
+    pub lines: Vec<CartLine>,
+}
+
+impl Cart {
+    /// Rings up `amount` of `product`. Scanning a product already in the
+    /// cart adds to its line; returns the index of that line.
+    pub fn add(&mut self, product: &Product, price: i64, amount: f64) -> usize {
+        if let Some(index) = self
+            .lines
+            .iter()
+            .position(|line| line.product.id == product.id && line.price == price)
+        {
+            self.lines[index].amount += amount;
+            return index;
+        }
+        self.lines.push(CartLine {
+            product: product.clone(),
+            price,
+            amount,
+            discount: 0,
+            tax: 0,
+        });
+        self.lines.len() - 1
+    }
+
+    /// Sets how many of a line are sold; nothing or less removes the line.
+    pub fn set_amount(&mut self, index: usize, amount: f64) {
+        if amount <= 0.0 || !amount.is_finite() {
+            self.remove(index);
+        } else if let Some(line) = self.lines.get_mut(index) {
+            line.amount = amount;
+        }
+    }
+
+    /// Sets a line's discount, as a percentage between 0 and 100.
+    pub fn set_discount(&mut self, index: usize, discount: i64) {
+        if let Some(line) = self.lines.get_mut(index) {
+            line.discount = discount.max(0).min(100);
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.lines.len() {
+            self.lines.remove(index);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn items(&self) -> f64 {
+        self.lines.iter().map(|line| line.amount).sum()
+    }
+
+    pub fn total(&self) -> Money {
+        self.lines.iter().map(|line| line.totals().total).sum()
+    }
+
+    /// The cart as a sale, for a receipt the server has not numbered yet.
+    pub fn sale(&self, id: i64, sale_date: NaiveDate) -> FullSale {
+        FullSale {
+            sale: Sale {
+                id,
+                user_id: 0,
+                sale_date,
+                total: self.total().to_f64(),
+                bill_number: None,
+                state: SaleState::Payed,
+            },
+            sale_products: self
+                .lines
+                .iter()
+                .map(|line| FullSaleProduct {
+                    sale_product: SaleProduct {
+                        sale_id: id,
+                        ..line.sale_product()
+                    },
+                    product: line.product.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The product's price in the price list `price_id`, or its cost when the
+/// list has no price for it.
+pub fn unit_price(product: &FullProduct, price_id: Option<i64>) -> i64 {
+    product
+        .price_products
+        .iter()
+        .filter(|price| Some(price.price.id) == price_id)
+        .find_map(|price| price.price_product.amount)
+        .or(product.product.cost)
+        .unwrap_or(0)
+}
+
+/// Splits the quantity off a `3*code` entry; a bare code is one item.
+pub fn split_quantity(entry: &str) -> (f64, &str) {
+    if let Some(star) = entry.find('*') {
+        if let Ok(amount) = entry[..star].trim().parse::<f64>() {
+            if amount > 0.0 && amount.is_finite() {
+                return (amount, entry[star + 1..].trim());
+            }
+        }
+    }
+    (1.0, entry.trim())
+}
+
+/// The product a scanner or the cashier typed the exact code of: its id or,
+/// ignoring case, its name.
+pub fn find_code<'a>(products: &'a [FullProduct], code: &str) -> Option<&'a FullProduct> {
+    let code = code.trim();
+    if code.is_empty() {
+        return None;
+    }
+    let id = code.parse::<i64>().ok();
+    products
+        .iter()
+        .find(|product| Some(product.product.id) == id)
+        .or_else(|| {
+            products
+                .iter()
+                .find(|product| product.product.name.eq_ignore_ascii_case(code))
+        })
+}
+
+/// Up to `limit` products whose name or description contains `query`,
+/// ignoring case; names starting with it first.
+pub fn search<'a>(products: &'a [FullProduct], query: &str, limit: usize) -> Vec<&'a FullProduct> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return vec![];
+    }
+    let mut found: Vec<(bool, &FullProduct)> = products
+        .iter()
+        .filter_map(|product| {
+            let name = product.product.name.to_lowercase();
+            let description = product
+                .product
+                .description
+                .as_deref()
+                .unwrap_or_default()
+                .to_lowercase();
+            if name.contains(&query) || description.contains(&query) {
+                Some((!name.starts_with(&query), product))
+            } else {
+                None
+            }
+        })
+        .collect();
+    found.sort_by_key(|(later, _)| *later);
+    found
+        .into_iter()
+        .take(limit)
+        .map(|(_, product)| product)
+        .collect()
+}
+
+/// What to hand back when `tendered` is paid for `total`, or `None` when it
+/// is not enough.
+pub fn change_due(total: Money, tendered: Money) -> Option<Money> {
+    if tendered < total {
+        None
+    } else {
+        Some(tendered - total)
+    }
+}
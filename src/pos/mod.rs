@@ -0,0 +1,735 @@
+use chrono::NaiveDate;
+use serde_json::Value;
+use web_sys::HtmlElement;
+use yew::agent::{Bridge, Bridged};
+use yew::prelude::{
+    html, ChangeData, Component, ComponentLink, Html, InputData, KeyboardEvent, NodeRef,
+    ShouldRender,
+};
+use yew::virtual_dom::VNode;
+
+use crate::cache::{query_body, query_key, CacheAgent, CachedQuery, Request, Response};
+use crate::dates::today;
+use crate::export::csv::NumberLocale;
+use crate::fetching::{current_user, FetchState};
+use crate::graphql;
+use crate::models::{
+    decode, FullProduct, FullSale, ListPrice, ListProduct, SaleState,
+};
+use crate::navbar::Model as Navbar;
+use crate::pricing::Money;
+use crate::sales::invoice::{print, Layout, Model as Invoice};
+use crate::views::{failed, loading};
+
+pub mod cart;
+
+use cart::{change_due, find_code, search, split_quantity, Cart};
+
+const PRODUCTS_LIMIT: i64 = 10_000;
+const RESULTS_LIMIT: usize = 8;
+
+fn create_body(cart: &Cart, sale_date: NaiveDate) -> Value {
+    use graphql::create_sale::{
+        FormProduct, FormSale, FormSaleProduct, FormSaleProducts, FullFormSaleProduct, SaleState,
+        Variables,
+    };
+    query_body::<graphql::CreateSale>(Variables {
+        form: FormSale {
+            id: None,
+            sale_date: Some(sale_date),
+            user_id: None,
+            total: Some(cart.total().to_f64()),
+            bill_number: None,
+            state: Some(SaleState::DRAFT),
+        },
+        form_sale_products: FormSaleProducts {
+            data: cart
+                .lines
+                .iter()
+                .map(|line| {
+                    let item = line.sale_product();
+                    FullFormSaleProduct {
+                        sale_product: FormSaleProduct {
+                            id: None,
+                            product_id: Some(item.product_id),
+                            sale_id: None,
+                            amount: Some(item.amount),
+                            discount: Some(item.discount),
+                            tax: Some(item.tax),
+                            price: Some(item.price),
+                            total: Some(item.total),
+                        },
+                        product: FormProduct {
+                            id: Some(line.product.id),
+                            name: Some(line.product.name.clone()),
+                            stock: Some(line.product.stock),
+                            cost: line.product.cost,
+                            description: line.product.description.clone(),
+                            user_id: None,
+                        },
+                    }
+                })
+                .collect(),
+        },
+    })
+}
+
+/// The mutations a checkout sends, in order.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Step {
+    Create,
+    Approve,
+    Pay,
+    Done,
+}
+
+impl Step {
+    fn label(self) -> &'static str {
+        match self {
+            Step::Create => "Creating the sale",
+            Step::Approve => "Approving the sale",
+            Step::Pay => "Recording the payment",
+            Step::Done => "Paid",
+        }
+    }
+}
+
+/// A paid-for cart on its way to the server.
+struct Checkout {
+    step: Step,
+    body: Value,
+    key: String,
+    /// The cart until the server answers with the sale it created.
+    sale: FullSale,
+    change: Money,
+    /// Whether any step waits in the offline queue.
+    queued: bool,
+    error: Option<String>,
+}
+
+enum Stage {
+    Cart,
+    Tender,
+    Checkout(Checkout),
+}
+
+/// A till: products are rung up from the keyboard or a barcode scanner, the
+/// cash tendered is counted and the sale is created, approved and paid in
+/// one go.
+pub struct Model {
+    link: ComponentLink<Self>,
+    prices_query: CachedQuery,
+    products_query: CachedQuery,
+    prices: FetchState<ListPrice>,
+    products: FetchState<ListProduct>,
+    cache: Box<dyn Bridge<CacheAgent>>,
+    price_id: Option<i64>,
+    query: String,
+    highlight: usize,
+    cart: Cart,
+    tendered: String,
+    stage: Stage,
+    notice: Option<String>,
+    /// The input the keyboard belongs to in the current stage.
+    focus: NodeRef,
+    refocus: bool,
+    print: bool,
+}
+
+pub enum Msg {
+    Cache(Response),
+    Mutated(Response),
+    Retry,
+    PriceList(ChangeData),
+    Query(InputData),
+    SearchKey(KeyboardEvent),
+    Add(i64),
+    Amount(usize, ChangeData),
+    Discount(usize, ChangeData),
+    Remove(usize),
+    Tender,
+    Tendered(InputData),
+    Exact,
+    TenderKey(KeyboardEvent),
+    Back,
+    Pay,
+    Resend,
+    Print,
+    NewSale,
+}
+
+impl Model {
+    fn product_list(&self) -> &[FullProduct] {
+        match &self.products {
+            FetchState::Success(list) => &list.data,
+            _ => &[],
+        }
+    }
+
+    fn results(&self) -> Vec<&FullProduct> {
+        let (_, text) = split_quantity(&self.query);
+        search(self.product_list(), text, RESULTS_LIMIT)
+    }
+
+    fn add(&mut self, product_id: i64, amount: f64) {
+        let found = self
+            .product_list()
+            .iter()
+            .find(|product| product.product.id == product_id)
+            .map(|product| {
+                (
+                    product.product.clone(),
+                    cart::unit_price(product, self.price_id),
+                )
+            });
+        if let Some((product, price)) = found {
+            self.cart.add(&product, price, amount);
+            self.query.clear();
+            self.highlight = 0;
+            self.notice = None;
+            self.refocus = true;
+        }
+    }
+
+    /// Enter in the search box: adds the scanned or chosen product, or
+    /// moves on to the tender once the box is empty.
+    fn scan(&mut self) {
+        if self.query.trim().is_empty() {
+            if !self.cart.is_empty() {
+                self.tender();
+            }
+            return;
+        }
+        let (amount, code) = split_quantity(&self.query);
+        let product_id = find_code(self.product_list(), code)
+            .or_else(|| self.results().get(self.highlight).copied())
+            .map(|product| product.product.id);
+        match product_id {
+            Some(product_id) => self.add(product_id, amount),
+            None => self.notice = Some(format!("No product matches \"{}\"", code)),
+        }
+    }
+
+    fn tender(&mut self) {
+        self.stage = Stage::Tender;
+        self.tendered.clear();
+        self.refocus = true;
+    }
+
+    fn tendered_change(&self) -> Option<Money> {
+        let tendered = NumberLocale::COMMA.parse(&self.tendered)?;
+        change_due(self.cart.total(), Money::from_f64(tendered))
+    }
+
+    /// Sends `checkout`'s current step, or finishes it once paid.
+    fn send(&mut self) {
+        let checkout = match &mut self.stage {
+            Stage::Checkout(checkout) => checkout,
+            _ => return,
+        };
+        let sale_id = checkout.sale.sale.id;
+        checkout.error = None;
+        checkout.body = match checkout.step {
+            Step::Create => checkout.body.clone(),
+            Step::Approve => {
+                query_body::<graphql::ApproveSale>(graphql::approve_sale::Variables { sale_id })
+            }
+            Step::Pay => query_body::<graphql::PaySale>(graphql::pay_sale::Variables { sale_id }),
+            Step::Done => {
+                checkout.sale.sale.state = SaleState::Payed;
+                self.print = true;
+                self.refocus = true;
+                return;
+            }
+        };
+        checkout.key = query_key(&checkout.body);
+        let body = checkout.body.clone();
+        self.cache.send(Request::Mutate { body });
+    }
+
+    fn mutated(&mut self, data: Value) {
+        let checkout = match &mut self.stage {
+            Stage::Checkout(checkout) => checkout,
+            _ => return,
+        };
+        let queued = data["queued"].as_bool() == Some(true);
+        checkout.queued |= queued;
+        checkout.step = match checkout.step {
+            Step::Create if queued => match data["tempId"].as_i64() {
+                Some(temp_id) => {
+                    checkout.sale.sale.id = temp_id;
+                    Step::Approve
+                }
+                None => {
+                    checkout.error = Some("The sale could not be queued".to_string());
+                    return;
+                }
+            },
+            Step::Create => match decode::<FullSale>(&data, "createSale") {
+                Ok(sale) => {
+                    checkout.sale = sale;
+                    Step::Approve
+                }
+                Err(error) => {
+                    checkout.error = Some(error);
+                    return;
+                }
+            },
+            Step::Approve if !queued && data["approveSale"] != Value::Bool(true) => {
+                checkout.error = Some("The sale could not be approved".to_string());
+                return;
+            }
+            Step::Approve => Step::Pay,
+            Step::Pay if !queued && data["paySale"] != Value::Bool(true) => {
+                checkout.error = Some("The payment could not be recorded".to_string());
+                return;
+            }
+            Step::Pay | Step::Done => Step::Done,
+        };
+        self.send();
+    }
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let prices = query_body::<graphql::ListPrice>(graphql::list_price::Variables);
+        let products = query_body::<graphql::ListProduct>(graphql::list_product::Variables {
+            search: String::new(),
+            limit: PRODUCTS_LIMIT,
+            rank: 0.0,
+        });
+        Self {
+            prices_query: CachedQuery::new(prices, link.callback(Msg::Cache)),
+            products_query: CachedQuery::new(products, link.callback(Msg::Cache)),
+            cache: CacheAgent::bridge(link.callback(Msg::Mutated)),
+            link,
+            prices: FetchState::Fetching,
+            products: FetchState::Fetching,
+            price_id: None,
+            query: String::new(),
+            highlight: 0,
+            cart: Cart::default(),
+            tendered: String::new(),
+            stage: Stage::Cart,
+            notice: None,
+            focus: NodeRef::default(),
+            refocus: true,
+            print: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Cache(response) => {
+                if let Some(state) = self
+                    .prices_query
+                    .state::<ListPrice>(response.clone(), "ListPrice")
+                {
+                    if let (None, FetchState::Success(list)) = (self.price_id, &state) {
+                        self.price_id = list.data.first().map(|price| price.id);
+                    }
+                    self.prices = state;
+                    true
+                } else if let Some(state) = self.products_query.state(response, "listProduct") {
+                    self.products = state;
+                    true
+                } else {
+                    false
+                }
+            }
+            Msg::Mutated(Response::Query { key, state }) => {
+                if !matches!(&self.stage, Stage::Checkout(checkout) if checkout.key == key) {
+                    return false;
+                }
+                match state {
+                    FetchState::Fetching => return false,
+                    FetchState::Success(data) => self.mutated(data),
+                    FetchState::Failed(error) => {
+                        if let Stage::Checkout(checkout) = &mut self.stage {
+                            checkout.error = Some(error.to_string());
+                        }
+                    }
+                }
+                true
+            }
+            Msg::Mutated(_) => false,
+            Msg::Retry => {
+                if let FetchState::Failed(_) = self.prices {
+                    self.prices_query.refetch();
+                }
+                if let FetchState::Failed(_) = self.products {
+                    self.products_query.refetch();
+                }
+                false
+            }
+            Msg::PriceList(ChangeData::Select(select)) => {
+                self.price_id = select.value().parse().ok();
+                self.refocus = true;
+                true
+            }
+            Msg::PriceList(_) => false,
+            Msg::Query(input) => {
+                self.query = input.value;
+                self.highlight = 0;
+                self.notice = None;
+                true
+            }
+            Msg::SearchKey(event) => {
+                match event.key().as_str() {
+                    "ArrowDown" => {
+                        event.prevent_default();
+                        let last = self.results().len().saturating_sub(1);
+                        self.highlight = (self.highlight + 1).min(last);
+                    }
+                    "ArrowUp" => {
+                        event.prevent_default();
+                        self.highlight = self.highlight.saturating_sub(1);
+                    }
+                    "Enter" => {
+                        event.prevent_default();
+                        self.scan();
+                    }
+                    "Escape" => {
+                        self.query.clear();
+                        self.highlight = 0;
+                        self.notice = None;
+                    }
+                    _ => return false,
+                }
+                true
+            }
+            Msg::Add(product_id) => {
+                self.add(product_id, 1.0);
+                true
+            }
+            Msg::Amount(index, ChangeData::Value(value)) => {
+                if let Some(amount) = NumberLocale::COMMA.parse(&value) {
+                    self.cart.set_amount(index, amount);
+                }
+                true
+            }
+            Msg::Amount(_, _) => false,
+            Msg::Discount(index, ChangeData::Value(value)) => {
+                if let Ok(discount) = value.trim().parse() {
+                    self.cart.set_discount(index, discount);
+                }
+                true
+            }
+            Msg::Discount(_, _) => false,
+            Msg::Remove(index) => {
+                self.cart.remove(index);
+                self.refocus = true;
+                true
+            }
+            Msg::Tender => {
+                if self.cart.is_empty() {
+                    return false;
+                }
+                self.tender();
+                true
+            }
+            Msg::Tendered(input) => {
+                self.tendered = input.value;
+                true
+            }
+            Msg::Exact => {
+                self.tendered = self.cart.total().to_string();
+                self.refocus = true;
+                true
+            }
+            Msg::TenderKey(event) => match event.key().as_str() {
+                "Enter" => {
+                    event.prevent_default();
+                    self.update(Msg::Pay)
+                }
+                "Escape" => self.update(Msg::Back),
+                _ => false,
+            },
+            Msg::Back => {
+                self.stage = Stage::Cart;
+                self.refocus = true;
+                true
+            }
+            Msg::Pay => {
+                let change = match (&self.stage, self.tendered_change()) {
+                    (Stage::Tender, Some(change)) => change,
+                    _ => return false,
+                };
+                let sale_date = today();
+                let body = create_body(&self.cart, sale_date);
+                self.stage = Stage::Checkout(Checkout {
+                    step: Step::Create,
+                    body,
+                    key: String::new(),
+                    sale: self.cart.sale(0, sale_date),
+                    change,
+                    queued: false,
+                    error: None,
+                });
+                self.send();
+                true
+            }
+            Msg::Resend => {
+                self.send();
+                true
+            }
+            Msg::Print => {
+                print();
+                false
+            }
+            Msg::NewSale => {
+                self.cart = Cart::default();
+                self.query.clear();
+                self.highlight = 0;
+                self.notice = None;
+                self.stage = Stage::Cart;
+                self.refocus = true;
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn rendered(&mut self, _first_render: bool) {
+        if self.refocus {
+            if let Some(element) = self.focus.cast::<HtmlElement>() {
+                let _ = element.focus();
+                self.refocus = false;
+            }
+        }
+        if self.print {
+            self.print = false;
+            print();
+        }
+    }
+
+    fn view(&self) -> VNode {
+        let body = match (&self.prices, &self.products) {
+            (FetchState::Failed(error), _) | (_, FetchState::Failed(error)) => {
+                failed(error, self.link.callback(|_| Msg::Retry))
+            }
+            (FetchState::Success(prices), FetchState::Success(_)) => match &self.stage {
+                Stage::Cart => html! {
+                    <div class="row">
+                        <div class="col-lg-5 mb-4">{ self.search_view(prices) }</div>
+                        <div class="col-lg-7">{ self.cart_view() }</div>
+                    </div>
+                },
+                Stage::Tender => self.tender_view(),
+                Stage::Checkout(checkout) => self.checkout_view(checkout),
+            },
+            _ => loading(),
+        };
+        html! {
+            <div>
+                <Navbar />
+                <h1 class="my-4 d-print-none"> { "Point of sale" } </h1>
+                { body }
+            </div>
+        }
+    }
+}
+
+impl Model {
+    fn search_view(&self, prices: &ListPrice) -> Html {
+        let results = self.results();
+        html! {
+            <>
+                <select class="custom-select mb-2 pos-price-list"
+                        onchange=self.link.callback(Msg::PriceList)>
+                    { for prices.data.iter().map(|price| html! {
+                        <option value=price.id.to_string() selected={Some(price.id) == self.price_id}>
+                            { &price.name }
+                        </option>
+                    }) }
+                </select>
+                <input type="search" class="form-control form-control-lg pos-search"
+                       placeholder="Scan or search a product"
+                       autocomplete="off"
+                       value=&self.query
+                       ref=self.focus.clone()
+                       oninput=self.link.callback(Msg::Query)
+                       onkeydown=self.link.callback(Msg::SearchKey) />
+                <small class="form-text text-muted">
+                    { "Enter adds · ↑↓ choose · 3*code rings up three · Enter on an empty box pays" }
+                </small>
+                { match &self.notice {
+                    Some(notice) => html! { <div class="alert alert-warning mt-2 pos-notice">{ notice }</div> },
+                    None => html! {},
+                } }
+                <div class="list-group mt-2 pos-results">
+                    { for results.iter().enumerate().map(|(index, product)| {
+                        let id = product.product.id;
+                        let class = if index == self.highlight {
+                            "list-group-item list-group-item-action active"
+                        } else {
+                            "list-group-item list-group-item-action"
+                        };
+                        html! {
+                            <button type="button" class=class
+                                    onclick=self.link.callback(move |_| Msg::Add(id))>
+                                { &product.product.name }
+                                <span class="float-right">
+                                    { Money::from_units(cart::unit_price(product, self.price_id)).to_string() }
+                                </span>
+                            </button>
+                        }
+                    }) }
+                </div>
+            </>
+        }
+    }
+
+    fn cart_view(&self) -> Html {
+        html! {
+            <>
+                <table class="table table-sm pos-cart">
+                    <thead>
+                        <tr>
+                            <th>{ "Product" }</th>
+                            <th class="text-right">{ "Price" }</th>
+                            <th>{ "Amount" }</th>
+                            <th>{ "Discount %" }</th>
+                            <th class="text-right">{ "Total" }</th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for self.cart.lines.iter().enumerate().map(|(index, line)| html! {
+                            <tr>
+                                <td>{ &line.product.name }</td>
+                                <td class="text-right">{ Money::from_units(line.price).to_string() }</td>
+                                <td>
+                                    <input type="number" step="any" min="0"
+                                           class="form-control form-control-sm pos-amount"
+                                           value=line.amount.to_string()
+                                           onchange=self.link.callback(move |data| Msg::Amount(index, data)) />
+                                </td>
+                                <td>
+                                    <input type="number" step="1" min="0" max="100"
+                                           class="form-control form-control-sm pos-discount"
+                                           value=line.discount.to_string()
+                                           onchange=self.link.callback(move |data| Msg::Discount(index, data)) />
+                                </td>
+                                <td class="text-right">{ line.totals().total.to_string() }</td>
+                                <td>
+                                    <button type="button" class="btn btn-sm btn-outline-danger pos-remove"
+                                            title="Remove"
+                                            onclick=self.link.callback(move |_| Msg::Remove(index))>
+                                        <i class="fas fa-times"></i>
+                                    </button>
+                                </td>
+                            </tr>
+                        }) }
+                    </tbody>
+                </table>
+                <div class="d-flex justify-content-between align-items-center">
+                    <span class="h3 pos-total">{ format!("Total {}", self.cart.total()) }</span>
+                    <button type="button" class="btn btn-lg btn-success pos-tender"
+                            disabled={self.cart.is_empty()}
+                            onclick=self.link.callback(|_| Msg::Tender)>
+                        { "Pay" }
+                    </button>
+                </div>
+            </>
+        }
+    }
+
+    fn tender_view(&self) -> Html {
+        let change = self.tendered_change();
+        html! {
+            <div class="col-lg-6 mx-auto pos-tender-screen">
+                <p class="h3">{ format!("Total {}", self.cart.total()) }</p>
+                <p class="text-muted">{ format!("{} items", self.cart.items()) }</p>
+                <div class="input-group input-group-lg mb-3">
+                    <input type="text" inputmode="decimal" class="form-control pos-tendered"
+                           placeholder="Cash tendered"
+                           value=&self.tendered
+                           ref=self.focus.clone()
+                           oninput=self.link.callback(Msg::Tendered)
+                           onkeydown=self.link.callback(Msg::TenderKey) />
+                    <div class="input-group-append">
+                        <button type="button" class="btn btn-outline-secondary pos-exact"
+                                onclick=self.link.callback(|_| Msg::Exact)>
+                            { "Exact" }
+                        </button>
+                    </div>
+                </div>
+                <p class="h3 pos-change">
+                    { match change {
+                        Some(change) => format!("Change {}", change),
+                        None => "Not enough tendered".to_string(),
+                    } }
+                </p>
+                <div class="d-flex justify-content-between">
+                    <button type="button" class="btn btn-lg btn-outline-secondary pos-back"
+                            onclick=self.link.callback(|_| Msg::Back)>
+                        { "Back" }
+                    </button>
+                    <button type="button" class="btn btn-lg btn-success pos-pay"
+                            disabled={change.is_none()}
+                            onclick=self.link.callback(|_| Msg::Pay)>
+                        { "Confirm" }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+
+    fn checkout_view(&self, checkout: &Checkout) -> Html {
+        if checkout.step != Step::Done {
+            return html! {
+                <div class="col-lg-6 mx-auto pos-checkout">
+                    <p class="h4">{ checkout.step.label() }</p>
+                    { match &checkout.error {
+                        Some(error) => html! {
+                            <div class="alert alert-danger">
+                                <p>{ error }</p>
+                                <button type="button" class="btn btn-danger pos-resend"
+                                        onclick=self.link.callback(|_| Msg::Resend)>
+                                    { "Try again" }
+                                </button>
+                            </div>
+                        },
+                        None => loading(),
+                    } }
+                </div>
+            };
+        }
+        let company = current_user().map(|user| user.company);
+        html! {
+            <div class="row">
+                <div class="col-lg-6 d-print-none pos-done">
+                    <p class="h3 pos-change">{ format!("Change {}", checkout.change) }</p>
+                    { if checkout.queued {
+                        html! {
+                            <div class="alert alert-warning">
+                                { "Offline: the sale is queued and will be sent once the connection is back." }
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    } }
+                    <button type="button" class="btn btn-lg btn-primary mr-2 pos-new-sale"
+                            ref=self.focus.clone()
+                            onclick=self.link.callback(|_| Msg::NewSale)>
+                        { "New sale" }
+                    </button>
+                    <button type="button" class="btn btn-lg btn-outline-secondary print"
+                            onclick=self.link.callback(|_| Msg::Print)>
+                        <i class="fas fa-print"></i>{ " Print again" }
+                    </button>
+                </div>
+                <div class="col-lg-6 print-area">
+                    <Invoice sale=checkout.sale.clone() company=company layout=Layout::Receipt80 />
+                </div>
+            </div>
+        }
+    }
+}
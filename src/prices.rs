@@ -0,0 +1,82 @@
+use yew::prelude::{html, Component, ComponentLink, ShouldRender};
+use yew::virtual_dom::VNode;
+
+use crate::cache::{query_body, CachedQuery, Response};
+use crate::export::{tables, ExportMenu};
+use crate::fetching::FetchState;
+use crate::graphql;
+use crate::models::ListPrice;
+use crate::navbar::Model as Navbar;
+use crate::views::{failed, loading};
+
+/// The price lists.
+pub struct Model {
+    link: ComponentLink<Self>,
+    query: CachedQuery,
+    state: FetchState<ListPrice>,
+}
+
+pub enum Msg {
+    Cache(Response),
+    Retry,
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let body = query_body::<graphql::ListPrice>(graphql::list_price::Variables);
+        Self {
+            query: CachedQuery::new(body, link.callback(Msg::Cache)),
+            link,
+            state: FetchState::Fetching,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Cache(response) => match self.query.state(response, "ListPrice") {
+                Some(state) => {
+                    self.state = state;
+                    true
+                }
+                None => false,
+            },
+            Msg::Retry => {
+                self.query.refetch();
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> VNode {
+        let body = match &self.state {
+            FetchState::Fetching => loading(),
+            FetchState::Failed(error) => failed(error, self.link.callback(|_| Msg::Retry)),
+            FetchState::Success(list) => html! {
+                <>
+                    <div class="d-flex justify-content-end mb-2">
+                        <ExportMenu table=tables::prices(&list.data) name="price-lists" />
+                    </div>
+                    <ul class="list-group prices">
+                        { for list.data.iter().map(|price| html! {
+                            <li class="list-group-item">{ &price.name }</li>
+                        }) }
+                    </ul>
+                </>
+            },
+        };
+        html! {
+            <div>
+                <Navbar />
+                <h1 class="my-4"> { "Price lists" } </h1>
+                { body }
+            </div>
+        }
+    }
+}
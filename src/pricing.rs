@@ -0,0 +1,406 @@
+//! How sale lines add up. Amounts are fixed-point, so totals come out the
+//! same on every screen and never pick up binary floating point noise:
+//! money is counted in cents and quantities in thousandths.
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+use crate::models::{FullSale, SaleProduct};
+
+const CENTS_PER_UNIT: i64 = 100;
+const MILLIS_PER_UNIT: i64 = 1000;
+
+/// How a result that falls between two cents is settled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// To the nearest, halves away from zero: 0.125 becomes 0.13.
+    HalfUp,
+    /// To the nearest, halves to the even neighbour: 0.125 becomes 0.12.
+    HalfEven,
+    /// Towards zero.
+    Down,
+    /// Away from zero.
+    Up,
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Rounding::HalfUp
+    }
+}
+
+impl Rounding {
+    /// `numerator / denominator`, rounded to a whole number, or `None` when
+    /// `denominator` is zero or the result does not fit in an `i64`.
+    pub fn divide(self, numerator: i128, denominator: i128) -> Option<i64> {
+        let (numerator, denominator) = if denominator < 0 {
+            (numerator.checked_neg()?, denominator.checked_neg()?)
+        } else {
+            (numerator, denominator)
+        };
+        let quotient = numerator.checked_div(denominator)?;
+        let remainder = (numerator % denominator).abs();
+        if remainder == 0 {
+            return i64::try_from(quotient).ok();
+        }
+        // Compared without doubling the remainder, which could overflow.
+        let rest = denominator - remainder;
+        let away = quotient + numerator.signum();
+        let rounded = match self {
+            Rounding::Down => quotient,
+            Rounding::Up => away,
+            Rounding::HalfUp if remainder >= rest => away,
+            Rounding::HalfEven if remainder > rest => away,
+            Rounding::HalfEven if remainder == rest && quotient % 2 != 0 => away,
+            Rounding::HalfUp | Rounding::HalfEven => quotient,
+        };
+        i64::try_from(rounded).ok()
+    }
+}
+
+/// An amount of money, in cents.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub fn from_cents(cents: i64) -> Self {
+        Money(cents)
+    }
+
+    /// Whole units, as the API's integer prices are.
+    pub fn from_units(units: i64) -> Self {
+        Money(units * CENTS_PER_UNIT)
+    }
+
+    /// The API's floating point totals, to the nearest cent.
+    pub fn from_f64(value: f64) -> Self {
+        Money((value * CENTS_PER_UNIT as f64).round() as i64)
+    }
+
+    pub fn cents(self) -> i64 {
+        self.0
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / CENTS_PER_UNIT as f64
+    }
+
+    pub fn abs(self) -> Self {
+        Money(self.0.abs())
+    }
+
+    /// `percent`% of this amount, or `None` when that is more than `Money`
+    /// holds.
+    pub fn percent(self, percent: i64, rounding: Rounding) -> Option<Self> {
+        rounding
+            .divide(self.0 as i128 * percent as i128, 100)
+            .map(Money)
+    }
+
+    /// This amount shared out `parts` ways, or `None` for zero parts.
+    pub fn divide(self, parts: i64, rounding: Rounding) -> Option<Self> {
+        rounding.divide(self.0 as i128, parts as i128).map(Money)
+    }
+
+    /// `numerator / denominator` cents for the line arithmetic, whose
+    /// denominators are never zero, held at the end of the range `Money`
+    /// holds when the quotient is beyond it.
+    fn settle(numerator: i128, denominator: i128, rounding: Rounding) -> Self {
+        match rounding.divide(numerator, denominator) {
+            Some(cents) => Money(cents),
+            None if (numerator < 0) == (denominator < 0) => Money(i64::MAX),
+            None => Money(i64::MIN),
+        }
+    }
+}
+
+impl Display for Money {
+    /// Two decimals, as `format_money` writes them.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let cents = (self.0 as i128).abs();
+        let units = cents / CENTS_PER_UNIT as i128;
+        let cents = cents % CENTS_PER_UNIT as i128;
+        f.pad(&format!("{}{}.{:02}", sign, units, cents))
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        Money(self.0 + other.0)
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        Money(self.0 - other.0)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        self.0 += other.0;
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, other: Money) {
+        self.0 -= other.0;
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.copied().sum()
+    }
+}
+
+/// How many of a product are sold, in thousandths, so weights and lengths
+/// can be sold too.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Quantity(i64);
+
+impl Quantity {
+    pub fn from_millis(millis: i64) -> Self {
+        Quantity(millis)
+    }
+
+    pub fn from_units(units: i64) -> Self {
+        Quantity(units * MILLIS_PER_UNIT)
+    }
+
+    /// The API's floating point amounts, to the nearest thousandth.
+    pub fn from_f64(value: f64) -> Self {
+        Quantity((value * MILLIS_PER_UNIT as f64).round() as i64)
+    }
+
+    pub fn millis(self) -> i64 {
+        self.0
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / MILLIS_PER_UNIT as f64
+    }
+}
+
+/// What comes off a line before tax.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Discount {
+    /// A percentage of the line, from 0 to 100.
+    Percent(i64),
+    /// A fixed amount off the whole line, never more than the line.
+    Amount(Money),
+}
+
+impl Default for Discount {
+    fn default() -> Self {
+        Discount::Percent(0)
+    }
+}
+
+/// Whether prices already include their tax.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaxMode {
+    /// Tax is added on top of the price, as the API's sales are priced.
+    Exclusive,
+    /// The price is what the customer pays; the tax is worked out of it.
+    Inclusive,
+}
+
+impl Default for TaxMode {
+    fn default() -> Self {
+        TaxMode::Exclusive
+    }
+}
+
+/// The rules a sale is priced by. Rounding happens once per step on each
+/// line, and a sale's totals are the sums of its rounded lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rules {
+    pub tax_mode: TaxMode,
+    pub rounding: Rounding,
+}
+
+/// One line of a sale: `quantity` of a product at `price` each.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LineInput {
+    pub price: Money,
+    pub quantity: Quantity,
+    pub discount: Discount,
+    /// Tax rate, as a percentage.
+    pub tax_rate: i64,
+}
+
+impl LineInput {
+    /// A line as the API stores it: whole-unit prices and percentage
+    /// discounts and taxes.
+    pub fn of(line: &SaleProduct) -> Self {
+        Self {
+            price: Money::from_units(line.price),
+            quantity: Quantity::from_f64(line.amount),
+            discount: Discount::Percent(line.discount),
+            tax_rate: line.tax,
+        }
+    }
+}
+
+/// What one line adds up to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LineTotals {
+    /// Price times quantity, before the discount.
+    pub gross: Money,
+    pub discount: Money,
+    /// What is taxed: the discounted line without its tax.
+    pub net: Money,
+    pub tax: Money,
+    /// What the customer pays.
+    pub total: Money,
+}
+
+impl LineTotals {
+    /// A line as the API stores it, priced by the default rules.
+    pub fn of(line: &SaleProduct) -> Self {
+        line_totals(&LineInput::of(line), Rules::default())
+    }
+}
+
+impl AddAssign for LineTotals {
+    fn add_assign(&mut self, other: LineTotals) {
+        self.gross += other.gross;
+        self.discount += other.discount;
+        self.net += other.net;
+        self.tax += other.tax;
+        self.total += other.total;
+    }
+}
+
+impl Sum for LineTotals {
+    fn sum<I: Iterator<Item = LineTotals>>(iter: I) -> LineTotals {
+        iter.fold(LineTotals::default(), |mut sum, line| {
+            sum += line;
+            sum
+        })
+    }
+}
+
+pub fn line_totals(line: &LineInput, rules: Rules) -> LineTotals {
+    let rounding = rules.rounding;
+    let gross = Money::settle(
+        line.price.0 as i128 * line.quantity.0 as i128,
+        MILLIS_PER_UNIT as i128,
+        rounding,
+    );
+    let discount = match line.discount {
+        Discount::Percent(percent) => Money::settle(
+            gross.0 as i128 * percent.max(0).min(100) as i128,
+            100,
+            rounding,
+        ),
+        Discount::Amount(amount) => {
+            // Off the line's size, in the line's direction, so a refund
+            // line is discounted like a sale line.
+            let amount = amount.abs().min(gross.abs());
+            if gross < Money::ZERO {
+                -amount
+            } else {
+                amount
+            }
+        }
+    };
+    let discounted = gross - discount;
+    let tax_rate = line.tax_rate.max(0);
+    let (net, tax) = match rules.tax_mode {
+        TaxMode::Exclusive => {
+            let tax = Money::settle(discounted.0 as i128 * tax_rate as i128, 100, rounding);
+            (discounted, tax)
+        }
+        TaxMode::Inclusive => {
+            let net = Money::settle(discounted.0 as i128 * 100, 100 + tax_rate as i128, rounding);
+            (net, discounted - net)
+        }
+    };
+    LineTotals {
+        gross,
+        discount,
+        net,
+        tax,
+        total: net + tax,
+    }
+}
+
+/// The base and tax of the lines taxed at one rate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TaxLine {
+    pub rate: i64,
+    pub base: Money,
+    pub tax: Money,
+}
+
+/// What a sale adds up to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SaleTotals {
+    pub lines: Vec<LineTotals>,
+    /// The sums of `lines`.
+    pub totals: LineTotals,
+    /// The tax per rate, lowest rate first.
+    pub taxes: Vec<TaxLine>,
+}
+
+impl SaleTotals {
+    /// A sale as the API stores it, priced by the default rules.
+    pub fn of(sale: &FullSale) -> Self {
+        let lines: Vec<LineInput> = sale
+            .sale_products
+            .iter()
+            .map(|line| LineInput::of(&line.sale_product))
+            .collect();
+        sale_totals(&lines, Rules::default())
+    }
+}
+
+pub fn sale_totals(lines: &[LineInput], rules: Rules) -> SaleTotals {
+    let mut taxes: BTreeMap<i64, TaxLine> = BTreeMap::new();
+    let lines: Vec<LineTotals> = lines
+        .iter()
+        .map(|line| {
+            let totals = line_totals(line, rules);
+            let rate = line.tax_rate.max(0);
+            let row = taxes.entry(rate).or_insert(TaxLine {
+                rate,
+                base: Money::ZERO,
+                tax: Money::ZERO,
+            });
+            row.base += totals.net;
+            row.tax += totals.tax;
+            totals
+        })
+        .collect();
+    SaleTotals {
+        totals: lines.iter().copied().sum(),
+        lines,
+        taxes: taxes.into_iter().map(|(_, row)| row).collect(),
+    }
+}
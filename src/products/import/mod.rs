@@ -0,0 +1,633 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, VecDeque};
+use web_sys::HtmlElement;
+use yew::agent::{Bridge, Bridged};
+use yew::prelude::{html, ChangeData, Component, ComponentLink, Html, NodeRef, ShouldRender};
+use yew::services::reader::{FileData, ReaderService, ReaderTask};
+use yew::virtual_dom::VNode;
+use yew_router::prelude::RouterAnchor;
+
+use crate::cache::{query_body, query_key, CacheAgent, CachedQuery, Request, Response};
+use crate::dates::{today, DATE_FORMAT};
+use crate::export::csv::{self, CsvOptions, NumberLocale};
+use crate::export::download;
+use crate::fetching::FetchState;
+use crate::graphql;
+use crate::models::{ListPrice, ListProduct, Price};
+use crate::navbar::Model as Navbar;
+use crate::routing::AppRoute;
+use crate::views::{failed, loading};
+
+pub mod plan;
+
+use plan::{check_mapping, error_report, guess_mapping, Action, Field, ImportRow, OnDuplicate};
+
+const PRODUCTS_LIMIT: i64 = 10_000;
+
+/// The `createProduct` or `updateProduct` variables of `row`, in the
+/// types generated for `$module`.
+macro_rules! product_variables {
+    ($module:ident, $row:expr, $id:expr) => {{
+        use graphql::$module::{
+            FormPriceProduct, FormPriceProductsToUpdate, FormProduct, PriceProductToUpdate,
+            Variables,
+        };
+        let row: &ImportRow = $row;
+        let id: Option<i64> = $id;
+        Variables {
+            form: FormProduct {
+                id,
+                name: Some(row.form.name.clone()),
+                stock: row.form.stock,
+                cost: row.form.cost,
+                description: row.form.description.clone(),
+                user_id: None,
+            },
+            form_price_products: FormPriceProductsToUpdate {
+                data: row
+                    .prices
+                    .iter()
+                    .map(|price| PriceProductToUpdate {
+                        price_product: FormPriceProduct {
+                            id: price.id,
+                            price_id: price.price_id,
+                            product_id: id,
+                            user_id: None,
+                            amount: Some(price.amount),
+                        },
+                        to_delete: false,
+                    })
+                    .collect(),
+            },
+        }
+    }};
+}
+
+fn mutation_body(row: &ImportRow) -> Option<Value> {
+    match row.action {
+        Action::Create => Some(query_body::<graphql::CreateProduct>(product_variables!(
+            create_product,
+            row,
+            None
+        ))),
+        Action::Update(id) => Some(query_body::<graphql::UpdateProduct>(product_variables!(
+            update_product,
+            row,
+            Some(id)
+        ))),
+        Action::Skip => None,
+    }
+}
+
+fn field_value(field: Field) -> String {
+    match field {
+        Field::Ignore => "ignore".to_string(),
+        Field::Name => "name".to_string(),
+        Field::Stock => "stock".to_string(),
+        Field::Cost => "cost".to_string(),
+        Field::Description => "description".to_string(),
+        Field::Price(price_id) => format!("price:{}", price_id),
+    }
+}
+
+fn parse_field(value: &str) -> Field {
+    match value {
+        "name" => Field::Name,
+        "stock" => Field::Stock,
+        "cost" => Field::Cost,
+        "description" => Field::Description,
+        _ => value
+            .strip_prefix("price:")
+            .and_then(|price_id| price_id.parse().ok())
+            .map(Field::Price)
+            .unwrap_or(Field::Ignore),
+    }
+}
+
+/// The file being imported.
+struct Loaded {
+    name: String,
+    records: Vec<Vec<String>>,
+    locale: NumberLocale,
+}
+
+/// Mutations sent one at a time, so progress can be shown and each
+/// failure tied to its record.
+struct Progress {
+    queue: VecDeque<(usize, Value)>,
+    current: Option<(usize, String)>,
+    total: usize,
+    done: usize,
+    queued: usize,
+    failures: BTreeMap<usize, String>,
+}
+
+impl Progress {
+    fn finished(&self) -> bool {
+        self.current.is_none() && self.queue.is_empty()
+    }
+
+    fn percent(&self) -> usize {
+        if self.total == 0 {
+            100
+        } else {
+            self.done * 100 / self.total
+        }
+    }
+}
+
+/// Reads products from a CSV file, previews them against the existing
+/// ones and creates or updates them.
+pub struct Model {
+    link: ComponentLink<Self>,
+    prices_query: CachedQuery,
+    products_query: CachedQuery,
+    prices: FetchState<ListPrice>,
+    products: FetchState<ListProduct>,
+    cache: Box<dyn Bridge<CacheAgent>>,
+    reader: ReaderService,
+    reading: Option<ReaderTask>,
+    file: Option<Loaded>,
+    file_error: Option<String>,
+    mapping: Vec<Field>,
+    on_duplicate: OnDuplicate,
+    progress: Option<Progress>,
+    progress_bar: NodeRef,
+}
+
+pub enum Msg {
+    Cache(Response),
+    Mutated(Response),
+    Retry,
+    File(ChangeData),
+    Read(FileData),
+    Map(usize, ChangeData),
+    OnDuplicate(ChangeData),
+    Import,
+    Report,
+}
+
+impl Model {
+    fn price_lists(&self) -> &[Price] {
+        match &self.prices {
+            FetchState::Success(list) => &list.data,
+            _ => &[],
+        }
+    }
+
+    fn rows(&self) -> Vec<ImportRow> {
+        match (&self.file, &self.products) {
+            (Some(file), FetchState::Success(products)) => plan::plan(
+                &file.records,
+                &self.mapping,
+                file.locale,
+                &products.data,
+                self.on_duplicate,
+            ),
+            _ => vec![],
+        }
+    }
+
+    fn send_next(&mut self) {
+        let progress = match &mut self.progress {
+            Some(progress) => progress,
+            None => return,
+        };
+        match progress.queue.pop_front() {
+            Some((record, body)) => {
+                progress.current = Some((record, query_key(&body)));
+                self.cache.send(Request::Mutate { body });
+            }
+            None => {
+                progress.current = None;
+                self.products_query.refetch();
+            }
+        }
+    }
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let prices = query_body::<graphql::ListPrice>(graphql::list_price::Variables);
+        let products = query_body::<graphql::ListProduct>(graphql::list_product::Variables {
+            search: String::new(),
+            limit: PRODUCTS_LIMIT,
+            rank: 0.0,
+        });
+        Self {
+            prices_query: CachedQuery::new(prices, link.callback(Msg::Cache)),
+            products_query: CachedQuery::new(products, link.callback(Msg::Cache)),
+            cache: CacheAgent::bridge(link.callback(Msg::Mutated)),
+            link,
+            prices: FetchState::Fetching,
+            products: FetchState::Fetching,
+            reader: ReaderService::new(),
+            reading: None,
+            file: None,
+            file_error: None,
+            mapping: vec![],
+            on_duplicate: OnDuplicate::Update,
+            progress: None,
+            progress_bar: NodeRef::default(),
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Cache(response) => {
+                if let Some(state) = self.prices_query.state(response.clone(), "ListPrice") {
+                    self.prices = state;
+                    true
+                } else if let Some(state) = self.products_query.state(response, "listProduct") {
+                    self.products = state;
+                    true
+                } else {
+                    false
+                }
+            }
+            Msg::Mutated(Response::Query { key, state }) => {
+                let progress = match &mut self.progress {
+                    Some(progress) => progress,
+                    None => return false,
+                };
+                let record = match &progress.current {
+                    Some((record, current)) if *current == key => *record,
+                    _ => return false,
+                };
+                match state {
+                    FetchState::Fetching => return false,
+                    FetchState::Success(data) => {
+                        if data["queued"].as_bool() == Some(true) {
+                            progress.queued += 1;
+                        }
+                    }
+                    FetchState::Failed(error) => {
+                        progress.failures.insert(record, error.to_string());
+                    }
+                }
+                progress.done += 1;
+                self.send_next();
+                true
+            }
+            Msg::Mutated(_) => false,
+            Msg::Retry => {
+                if let FetchState::Failed(_) = self.prices {
+                    self.prices_query.refetch();
+                }
+                if let FetchState::Failed(_) = self.products {
+                    self.products_query.refetch();
+                }
+                false
+            }
+            Msg::File(ChangeData::Files(files)) => {
+                if let Some(file) = files.get(0) {
+                    match self.reader.read_file(file, self.link.callback(Msg::Read)) {
+                        Ok(task) => self.reading = Some(task),
+                        Err(error) => self.file_error = Some(error.to_string()),
+                    }
+                }
+                false
+            }
+            Msg::File(_) => false,
+            Msg::Read(data) => {
+                self.reading = None;
+                self.progress = None;
+                let text = String::from_utf8_lossy(&data.content);
+                let delimiter = csv::detect_delimiter(&text);
+                match csv::parse(&text, delimiter) {
+                    Ok(records) if records.len() > 1 => {
+                        self.mapping = guess_mapping(&records[0], self.price_lists());
+                        // A semicolon-separated file most likely writes
+                        // decimals with commas.
+                        let locale = if delimiter == ';' {
+                            NumberLocale::COMMA
+                        } else {
+                            NumberLocale::POINT
+                        };
+                        self.file = Some(Loaded {
+                            name: data.name,
+                            records,
+                            locale,
+                        });
+                        self.file_error = None;
+                    }
+                    Ok(_) => {
+                        self.file = None;
+                        self.file_error = Some("The file has no products under its header".into());
+                    }
+                    Err(error) => {
+                        self.file = None;
+                        self.file_error = Some(error);
+                    }
+                }
+                true
+            }
+            Msg::Map(column, ChangeData::Select(select)) => {
+                if let Some(field) = self.mapping.get_mut(column) {
+                    *field = parse_field(&select.value());
+                }
+                true
+            }
+            Msg::Map(_, _) => false,
+            Msg::OnDuplicate(ChangeData::Select(select)) => {
+                self.on_duplicate = if select.value() == "skip" {
+                    OnDuplicate::Skip
+                } else {
+                    OnDuplicate::Update
+                };
+                true
+            }
+            Msg::OnDuplicate(_) => false,
+            Msg::Import => {
+                if self
+                    .progress
+                    .as_ref()
+                    .map_or(false, |progress| !progress.finished())
+                {
+                    return false;
+                }
+                let queue: VecDeque<(usize, Value)> = self
+                    .rows()
+                    .iter()
+                    .filter(|row| row.is_imported())
+                    .filter_map(|row| mutation_body(row).map(|body| (row.record, body)))
+                    .collect();
+                self.progress = Some(Progress {
+                    total: queue.len(),
+                    queue,
+                    current: None,
+                    done: 0,
+                    queued: 0,
+                    failures: BTreeMap::new(),
+                });
+                self.send_next();
+                true
+            }
+            Msg::Report => {
+                if let Some(file) = &self.file {
+                    let failures = self
+                        .progress
+                        .as_ref()
+                        .map(|progress| progress.failures.clone())
+                        .unwrap_or_default();
+                    let report = error_report(&file.records, &self.rows(), &failures);
+                    let csv = csv::write(&report, CsvOptions::for_locale(file.locale));
+                    let name = format!(
+                        "{}-errors-{}.csv",
+                        file.name.trim_end_matches(".csv"),
+                        today().format(DATE_FORMAT)
+                    );
+                    if let Err(error) = download(&name, "text/csv;charset=utf-8", csv.as_bytes()) {
+                        self.file_error = Some(format!("{:?}", error));
+                        return true;
+                    }
+                }
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn rendered(&mut self, _first_render: bool) {
+        // Set through the CSSOM: the CSP refuses inline style attributes.
+        if let (Some(progress), Some(bar)) =
+            (&self.progress, self.progress_bar.cast::<HtmlElement>())
+        {
+            let width = format!("{}%", progress.percent());
+            let _ = bar.style().set_property("width", &width);
+        }
+    }
+
+    fn view(&self) -> VNode {
+        let body = match (&self.prices, &self.products) {
+            (FetchState::Failed(error), _) | (_, FetchState::Failed(error)) => {
+                failed(error, self.link.callback(|_| Msg::Retry))
+            }
+            (FetchState::Success(_), FetchState::Success(_)) => self.importer(),
+            _ => loading(),
+        };
+        html! {
+            <div>
+                <Navbar />
+                <h1 class="my-4"> { "Import products" } </h1>
+                <p>
+                    <RouterAnchor<AppRoute> route=AppRoute::Products>
+                        { "Back to products" }
+                    </RouterAnchor<AppRoute>>
+                </p>
+                { body }
+            </div>
+        }
+    }
+}
+
+impl Model {
+    fn importer(&self) -> Html {
+        let rows = self.rows();
+        html! {
+            <>
+                <div class="form-group">
+                    <input type="file" class="form-control-file import-file" accept=".csv,text/csv"
+                           onchange=self.link.callback(Msg::File) />
+                </div>
+                { self.file_error.as_ref().map(|error| html! {
+                    <div class="alert alert-danger import-error">{ error }</div>
+                }).unwrap_or_default() }
+                { match &self.file {
+                    Some(file) => html! {
+                        <>
+                            { self.mapping_form(&file.records[0]) }
+                            { self.actions(&rows) }
+                            { self.preview(&rows) }
+                        </>
+                    },
+                    None => html! {},
+                } }
+            </>
+        }
+    }
+
+    fn mapping_form(&self, headers: &[String]) -> Html {
+        let prices = self.price_lists();
+        let options: Vec<Field> = std::iter::once(Field::Ignore)
+            .chain(Field::PRODUCT.iter().copied())
+            .chain(prices.iter().map(|price| Field::Price(price.id)))
+            .collect();
+        html! {
+            <div class="card mb-3">
+                <div class="card-header">{ "Columns" }</div>
+                <div class="card-body">
+                    <div class="form-row import-mapping">
+                        { for headers.iter().enumerate().map(|(column, header)| {
+                            let current = self.mapping.get(column).copied().unwrap_or(Field::Ignore);
+                            html! {
+                                <div class="col-md-3 mb-2">
+                                    <label class="small">{ header }</label>
+                                    <select class="custom-select custom-select-sm"
+                                            onchange=self.link.callback(move |data| Msg::Map(column, data))>
+                                        { for options.iter().map(|field| html! {
+                                            <option value=field_value(*field) selected={*field == current}>
+                                                { field.label(prices) }
+                                            </option>
+                                        }) }
+                                    </select>
+                                </div>
+                            }
+                        }) }
+                    </div>
+                    <div class="form-inline">
+                        <label class="mr-2">{ "Products that already exist" }</label>
+                        <select class="custom-select custom-select-sm import-duplicates"
+                                onchange=self.link.callback(Msg::OnDuplicate)>
+                            <option value="update" selected={self.on_duplicate == OnDuplicate::Update}>
+                                { "Update them" }
+                            </option>
+                            <option value="skip" selected={self.on_duplicate == OnDuplicate::Skip}>
+                                { "Skip them" }
+                            </option>
+                        </select>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn actions(&self, rows: &[ImportRow]) -> Html {
+        let importable = rows.iter().filter(|row| row.is_imported()).count();
+        let invalid = rows.iter().filter(|row| !row.is_valid()).count();
+        let mapping = check_mapping(&self.mapping);
+        let busy = self
+            .progress
+            .as_ref()
+            .map_or(false, |progress| !progress.finished());
+        let failures = self
+            .progress
+            .as_ref()
+            .map_or(0, |progress| progress.failures.len());
+        html! {
+            <div class="mb-3">
+                { match &mapping {
+                    Err(error) => html! { <div class="alert alert-warning">{ error }</div> },
+                    Ok(()) => html! {
+                        <p class="import-summary">
+                            { format!("{} to import, {} with errors, {} skipped.",
+                                importable, invalid, rows.len() - importable - invalid) }
+                        </p>
+                    },
+                } }
+                <button type="button" class="btn btn-primary mr-2 import-commit"
+                        disabled={mapping.is_err() || busy || importable == 0}
+                        onclick=self.link.callback(|_| Msg::Import)>
+                    { format!("Import {} products", importable) }
+                </button>
+                <button type="button" class="btn btn-outline-secondary import-report"
+                        disabled={invalid + failures == 0}
+                        onclick=self.link.callback(|_| Msg::Report)>
+                    { "Download error report" }
+                </button>
+                { self.progress.as_ref().map(|progress| self.progress_bar(progress)).unwrap_or_default() }
+            </div>
+        }
+    }
+
+    fn progress_bar(&self, progress: &Progress) -> Html {
+        html! {
+            <div class="mt-3 import-progress">
+                <div class="progress mb-2">
+                    <div class="progress-bar" role="progressbar" ref=self.progress_bar.clone()
+                         aria-valuemin="0" aria-valuemax="100"
+                         aria-valuenow=progress.percent().to_string()>
+                        { format!("{} / {}", progress.done, progress.total) }
+                    </div>
+                </div>
+                { if progress.finished() {
+                    html! {
+                        <p class="import-result">
+                            { format!("Imported {} of {} products.",
+                                progress.done - progress.failures.len(), progress.total) }
+                            { if progress.queued > 0 {
+                                format!(" {} will be sent when back online.", progress.queued)
+                            } else {
+                                String::new()
+                            } }
+                        </p>
+                    }
+                } else {
+                    html! {}
+                } }
+                { for progress.failures.iter().map(|(record, error)| html! {
+                    <div class="text-danger small">{ format!("Record {}: {}", record, error) }</div>
+                }) }
+            </div>
+        }
+    }
+
+    fn preview(&self, rows: &[ImportRow]) -> Html {
+        let prices = self.price_lists();
+        let price_columns: Vec<i64> = self
+            .mapping
+            .iter()
+            .filter_map(|field| match field {
+                Field::Price(price_id) => Some(*price_id),
+                _ => None,
+            })
+            .collect();
+        html! {
+            <table class="table table-sm import-preview">
+                <thead>
+                    <tr>
+                        <th>{ "Record" }</th>
+                        <th>{ "Name" }</th>
+                        <th class="text-right">{ "Stock" }</th>
+                        <th class="text-right">{ "Cost" }</th>
+                        <th>{ "Description" }</th>
+                        { for price_columns.iter().map(|price_id| html! {
+                            <th class="text-right">{ Field::Price(*price_id).label(prices) }</th>
+                        }) }
+                        <th>{ "Action" }</th>
+                        <th>{ "Errors" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for rows.iter().map(|row| {
+                        let (action, badge) = match row.action {
+                            _ if !row.is_valid() => ("Invalid", "badge badge-danger"),
+                            Action::Create => ("Create", "badge badge-success"),
+                            Action::Update(_) => ("Update", "badge badge-info"),
+                            Action::Skip => ("Skip", "badge badge-secondary"),
+                        };
+                        html! {
+                            <tr class={if row.is_valid() { "" } else { "table-danger" }}>
+                                <td>{ row.record }</td>
+                                <td>{ &row.form.name }</td>
+                                <td class="text-right">
+                                    { row.form.stock.map(|stock| stock.to_string()).unwrap_or_default() }
+                                </td>
+                                <td class="text-right">
+                                    { row.form.cost.map(|cost| cost.to_string()).unwrap_or_default() }
+                                </td>
+                                <td>{ row.form.description.clone().unwrap_or_default() }</td>
+                                { for price_columns.iter().map(|price_id| html! {
+                                    <td class="text-right">
+                                        { row.prices.iter()
+                                            .find(|price| price.price_id == *price_id)
+                                            .map(|price| price.amount.to_string())
+                                            .unwrap_or_default() }
+                                    </td>
+                                }) }
+                                <td><span class=badge>{ action }</span></td>
+                                <td class="small">{ row.errors.join("; ") }</td>
+                            </tr>
+                        }
+                    }) }
+                </tbody>
+            </table>
+        }
+    }
+}
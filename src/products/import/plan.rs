@@ -0,0 +1,279 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::export::csv::NumberLocale;
+use crate::export::{Cell, Table};
+use crate::models::{FullProduct, Price};
+
+/// What a CSV column is read into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Field {
+    Ignore,
+    Name,
+    Stock,
+    Cost,
+    Description,
+    /// The product's amount on the price list with this id.
+    Price(i64),
+}
+
+impl Field {
+    pub const PRODUCT: [Field; 4] = [Field::Name, Field::Stock, Field::Cost, Field::Description];
+
+    pub fn label(self, prices: &[Price]) -> String {
+        match self {
+            Field::Ignore => "Ignore".to_string(),
+            Field::Name => "Name".to_string(),
+            Field::Stock => "Stock".to_string(),
+            Field::Cost => "Cost".to_string(),
+            Field::Description => "Description".to_string(),
+            Field::Price(price_id) => prices
+                .iter()
+                .find(|price| price.id == price_id)
+                .map(|price| format!("Price: {}", price.name))
+                .unwrap_or_else(|| format!("Price #{}", price_id)),
+        }
+    }
+}
+
+/// A field for each header: the product field or price list it names,
+/// ignoring case and surrounding spaces, or `Field::Ignore`.
+pub fn guess_mapping(headers: &[String], prices: &[Price]) -> Vec<Field> {
+    let mut taken = vec![];
+    headers
+        .iter()
+        .map(|header| {
+            let header = header.trim().to_lowercase();
+            let field = Field::PRODUCT
+                .iter()
+                .copied()
+                .find(|field| field.label(prices).to_lowercase() == header)
+                .or_else(|| {
+                    prices
+                        .iter()
+                        .find(|price| price.name.trim().to_lowercase() == header)
+                        .map(|price| Field::Price(price.id))
+                })
+                .filter(|field| !taken.contains(field))
+                .unwrap_or(Field::Ignore);
+            if field != Field::Ignore {
+                taken.push(field);
+            }
+            field
+        })
+        .collect()
+}
+
+/// Why `mapping` cannot be imported, if it cannot.
+pub fn check_mapping(mapping: &[Field]) -> Result<(), String> {
+    if !mapping.contains(&Field::Name) {
+        return Err("Map a column to the product name".to_string());
+    }
+    let mut seen = vec![];
+    for field in mapping.iter().filter(|field| **field != Field::Ignore) {
+        if seen.contains(field) {
+            return Err("Map each field to one column only".to_string());
+        }
+        seen.push(*field);
+    }
+    Ok(())
+}
+
+/// What to do with a row naming a product that already exists.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OnDuplicate {
+    Update,
+    Skip,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Create,
+    /// Update the existing product with this id.
+    Update(i64),
+    Skip,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProductForm {
+    pub name: String,
+    pub stock: Option<f64>,
+    pub cost: Option<i64>,
+    pub description: Option<String>,
+}
+
+/// One price list amount to write, with the id of the link to update when
+/// the product is already on that list.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PriceAmount {
+    pub id: Option<i64>,
+    pub price_id: i64,
+    pub amount: i64,
+}
+
+/// A data record read through the mapping.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportRow {
+    /// Position of the record in the file, the header being 1.
+    pub record: usize,
+    pub form: ProductForm,
+    pub prices: Vec<PriceAmount>,
+    pub action: Action,
+    pub errors: Vec<String>,
+}
+
+impl ImportRow {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Whether committing sends a mutation for this row.
+    pub fn is_imported(&self) -> bool {
+        self.is_valid() && self.action != Action::Skip
+    }
+}
+
+fn key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+fn whole_amount(text: &str, locale: NumberLocale, what: &str) -> Result<i64, String> {
+    match locale.parse(text) {
+        Some(value) if value.fract() == 0.0 && (0.0..=i32::MAX as f64).contains(&value) => {
+            Ok(value as i64)
+        }
+        Some(_) => Err(format!("{} must be a whole number of at least 0", what)),
+        None => Err(format!("{} \"{}\" is not a number", what, text.trim())),
+    }
+}
+
+/// Reads the data records (all but the header) into rows to import,
+/// validating each and matching names against `existing` products and the
+/// rows above. Blank records are left out.
+pub fn plan(
+    records: &[Vec<String>],
+    mapping: &[Field],
+    locale: NumberLocale,
+    existing: &[FullProduct],
+    on_duplicate: OnDuplicate,
+) -> Vec<ImportRow> {
+    let existing_by_name: HashMap<String, &FullProduct> = existing
+        .iter()
+        .map(|full_product| (key(&full_product.product.name), full_product))
+        .collect();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut rows = vec![];
+    for (index, record) in records.iter().enumerate().skip(1) {
+        if record.iter().all(|value| value.trim().is_empty()) {
+            continue;
+        }
+        let mut row = ImportRow {
+            record: index + 1,
+            form: ProductForm::default(),
+            prices: vec![],
+            action: Action::Create,
+            errors: vec![],
+        };
+        let mut amounts = vec![];
+        for (field, value) in mapping.iter().zip(record) {
+            let text = value.trim();
+            match field {
+                Field::Ignore => {}
+                Field::Name => row.form.name = text.to_string(),
+                Field::Description if !text.is_empty() => {
+                    row.form.description = Some(text.to_string())
+                }
+                Field::Stock if !text.is_empty() => match locale.parse(text) {
+                    Some(stock) if stock >= 0.0 => row.form.stock = Some(stock),
+                    Some(_) => row.errors.push("Stock cannot be negative".to_string()),
+                    None => row
+                        .errors
+                        .push(format!("Stock \"{}\" is not a number", text)),
+                },
+                Field::Cost if !text.is_empty() => match whole_amount(text, locale, "Cost") {
+                    Ok(cost) => row.form.cost = Some(cost),
+                    Err(error) => row.errors.push(error),
+                },
+                Field::Price(price_id) if !text.is_empty() => {
+                    match whole_amount(text, locale, "Price") {
+                        Ok(amount) => amounts.push((*price_id, amount)),
+                        Err(error) => row.errors.push(error),
+                    }
+                }
+                _ => {}
+            }
+        }
+        if record.len() < mapping.len() {
+            row.errors.push(format!(
+                "Has {} columns, {} expected",
+                record.len(),
+                mapping.len()
+            ));
+        }
+        if row.form.name.is_empty() {
+            row.errors.push("Name is required".to_string());
+        } else if let Some(first) = seen.get(&key(&row.form.name)) {
+            row.errors.push(format!("Same name as record {}", first));
+        } else {
+            seen.insert(key(&row.form.name), row.record);
+        }
+
+        let current = existing_by_name.get(&key(&row.form.name));
+        row.action = match (current, on_duplicate) {
+            (None, _) => Action::Create,
+            (Some(full_product), OnDuplicate::Update) => Action::Update(full_product.product.id),
+            (Some(_), OnDuplicate::Skip) => Action::Skip,
+        };
+        row.prices = amounts
+            .into_iter()
+            .map(|(price_id, amount)| PriceAmount {
+                id: current.and_then(|full_product| {
+                    full_product
+                        .price_products
+                        .iter()
+                        .find(|full_price| full_price.price_product.price_id == price_id)
+                        .map(|full_price| full_price.price_product.id)
+                }),
+                price_id,
+                amount,
+            })
+            .collect();
+        rows.push(row);
+    }
+    rows
+}
+
+/// The records that were not imported, as they were in the file, with an
+/// `Errors` column: the validation errors, or the server's answer from
+/// `failures`, keyed by record.
+pub fn error_report(
+    records: &[Vec<String>],
+    rows: &[ImportRow],
+    failures: &BTreeMap<usize, String>,
+) -> Table {
+    let headers = records.first().cloned().unwrap_or_default();
+    let width = headers.len();
+    let mut table = Table {
+        headers: std::iter::once("Record".to_string())
+            .chain(headers)
+            .chain(std::iter::once("Errors".to_string()))
+            .collect(),
+        rows: vec![],
+    };
+    for row in rows {
+        let errors = match failures.get(&row.record) {
+            Some(failure) => vec![failure.clone()],
+            None => row.errors.clone(),
+        };
+        if errors.is_empty() {
+            continue;
+        }
+        let record = &records[row.record - 1];
+        let mut cells = vec![Cell::Integer(row.record as i64)];
+        cells.extend(
+            (0..width).map(|column| Cell::from(record.get(column).cloned().unwrap_or_default())),
+        );
+        cells.push(Cell::from(errors.join("; ")));
+        table.rows.push(cells);
+    }
+    table
+}
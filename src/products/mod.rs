@@ -0,0 +1,131 @@
+use yew::prelude::{html, Callback, Component, ComponentLink, InputData, ShouldRender};
+use yew::virtual_dom::VNode;
+use yew_router::prelude::RouterAnchor;
+
+use crate::cache::{query_body, CachedQuery, Response};
+use crate::export::{tables, ExportMenu};
+use crate::fetching::FetchState;
+use crate::graphql;
+use crate::models::ListProduct;
+use crate::navbar::Model as Navbar;
+use crate::routing::AppRoute;
+use crate::views::{failed, loading};
+
+pub mod import;
+
+const PRODUCTS_LIMIT: i64 = 1000;
+
+fn query(search: &str, callback: Callback<Response>) -> CachedQuery {
+    let body = query_body::<graphql::ListProduct>(graphql::list_product::Variables {
+        search: search.trim().to_string(),
+        limit: PRODUCTS_LIMIT,
+        rank: 0.0,
+    });
+    CachedQuery::new(body, callback)
+}
+
+/// The products, searched by name.
+pub struct Model {
+    link: ComponentLink<Self>,
+    query: CachedQuery,
+    state: FetchState<ListProduct>,
+    search: String,
+}
+
+pub enum Msg {
+    Cache(Response),
+    Retry,
+    Search(String),
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            query: query("", link.callback(Msg::Cache)),
+            link,
+            state: FetchState::Fetching,
+            search: String::new(),
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Cache(response) => match self.query.state(response, "listProduct") {
+                Some(state) => {
+                    self.state = state;
+                    true
+                }
+                None => false,
+            },
+            Msg::Retry => {
+                self.query.refetch();
+                false
+            }
+            Msg::Search(search) => {
+                self.query = query(&search, self.link.callback(Msg::Cache));
+                self.search = search;
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> VNode {
+        let body = match &self.state {
+            FetchState::Fetching => loading(),
+            FetchState::Failed(error) => failed(error, self.link.callback(|_| Msg::Retry)),
+            FetchState::Success(list) => html! {
+                <>
+                    <div class="d-flex justify-content-end mb-2">
+                        <RouterAnchor<AppRoute> route=AppRoute::ProductImport
+                                                classes="btn btn-sm btn-outline-success mr-2">
+                            <i class="fas fa-file-import"></i>{ " Import" }
+                        </RouterAnchor<AppRoute>>
+                        <ExportMenu table=tables::products(&list.data) name="products" />
+                    </div>
+                    <table class="table table-sm products">
+                        <thead>
+                            <tr>
+                                <th>{ "Name" }</th>
+                                <th class="text-right">{ "Stock" }</th>
+                                <th class="text-right">{ "Cost" }</th>
+                                <th>{ "Description" }</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for list.data.iter().map(|full_product| {
+                                let product = &full_product.product;
+                                html! {
+                                    <tr>
+                                        <td>{ &product.name }</td>
+                                        <td class="text-right">{ product.stock }</td>
+                                        <td class="text-right">
+                                            { product.cost.map(|cost| cost.to_string()).unwrap_or_default() }
+                                        </td>
+                                        <td>{ product.description.clone().unwrap_or_default() }</td>
+                                    </tr>
+                                }
+                            }) }
+                        </tbody>
+                    </table>
+                </>
+            },
+        };
+        html! {
+            <div>
+                <Navbar />
+                <h1 class="my-4"> { "Products" } </h1>
+                <input type="search" class="form-control mb-3 product-search"
+                       placeholder="Search products" value=&self.search
+                       oninput=self.link.callback(|input: InputData| Msg::Search(input.value)) />
+                { body }
+            </div>
+        }
+    }
+}
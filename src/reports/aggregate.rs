@@ -0,0 +1,143 @@
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+use crate::dashboard::kpi::{per_period, Period};
+use crate::models::{FullProduct, FullSale, SaleState};
+use crate::pricing::{LineTotals, Money, Rounding};
+
+/// Name of the row gathering lines sold at a price no price list has.
+pub const NO_PRICE_LIST: &str = "No price list";
+
+/// Figures over a set of sales.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub sales: usize,
+    pub revenue: Money,
+    pub average_ticket: Money,
+    pub lines: LineTotals,
+}
+
+/// One product's share of the sales.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProductRow {
+    pub product_id: i64,
+    pub name: String,
+    pub amount: f64,
+    pub revenue: Money,
+}
+
+/// One price list's share of the sales.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PriceListRow {
+    pub price_id: Option<i64>,
+    pub name: String,
+    pub lines: usize,
+    pub revenue: Money,
+}
+
+/// Sales that count towards reports: dated `from` to `to`, both included,
+/// and not cancelled.
+pub fn in_range(sales: &[FullSale], from: NaiveDate, to: NaiveDate) -> Vec<&FullSale> {
+    sales
+        .iter()
+        .filter(|full_sale| {
+            let sale = &full_sale.sale;
+            sale.state != SaleState::Cancelled && sale.sale_date >= from && sale.sale_date <= to
+        })
+        .collect()
+}
+
+pub fn summarize(sales: &[&FullSale]) -> Summary {
+    let mut summary = Summary {
+        sales: sales.len(),
+        ..Summary::default()
+    };
+    for full_sale in sales {
+        summary.revenue += Money::from_f64(full_sale.sale.total);
+        for line in &full_sale.sale_products {
+            summary.lines += LineTotals::of(&line.sale_product);
+        }
+    }
+    summary.average_ticket = summary
+        .revenue
+        .divide(summary.sales as i64, Rounding::default())
+        .unwrap_or(Money::ZERO);
+    summary
+}
+
+/// Revenue per `period` from `from` to `to`, oldest first, with empty
+/// periods as zero so gaps show.
+pub fn by_period(
+    sales: &[&FullSale],
+    period: Period,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<(NaiveDate, Money)> {
+    per_period(sales.iter().copied(), period, from, to, |full_sale| {
+        Money::from_f64(full_sale.sale.total)
+    })
+}
+
+/// Amount sold and revenue per product, highest revenue first.
+pub fn by_product(sales: &[&FullSale]) -> Vec<ProductRow> {
+    let mut rows: BTreeMap<i64, ProductRow> = BTreeMap::new();
+    for line in sales.iter().flat_map(|full_sale| &full_sale.sale_products) {
+        let row = rows.entry(line.product.id).or_insert_with(|| ProductRow {
+            product_id: line.product.id,
+            name: line.product.name.clone(),
+            amount: 0.0,
+            revenue: Money::ZERO,
+        });
+        row.amount += line.sale_product.amount;
+        row.revenue += Money::from_f64(line.sale_product.total);
+    }
+    let mut rows: Vec<ProductRow> = rows.into_iter().map(|(_, row)| row).collect();
+    rows.sort_by(|a, b| b.revenue.cmp(&a.revenue).then_with(|| a.name.cmp(&b.name)));
+    rows
+}
+
+/// The `limit` products sold in the largest amounts.
+pub fn top_products(sales: &[&FullSale], limit: usize) -> Vec<ProductRow> {
+    let mut rows = by_product(sales);
+    rows.sort_by(|a, b| {
+        b.amount
+            .partial_cmp(&a.amount)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    rows.truncate(limit);
+    rows
+}
+
+/// Revenue per price list, highest first. Sale lines do not record their
+/// list, so a line goes to the first of its product's lists whose amount
+/// matches the price it was sold at, or to `NO_PRICE_LIST`.
+pub fn by_price_list(sales: &[&FullSale], products: &[FullProduct]) -> Vec<PriceListRow> {
+    let mut rows: BTreeMap<Option<i64>, PriceListRow> = BTreeMap::new();
+    for line in sales.iter().flat_map(|full_sale| &full_sale.sale_products) {
+        let price = products
+            .iter()
+            .find(|full_product| full_product.product.id == line.product.id)
+            .and_then(|full_product| {
+                full_product.price_products.iter().find(|full_price| {
+                    full_price.price_product.amount == Some(line.sale_product.price)
+                })
+            })
+            .map(|full_price| &full_price.price);
+        let row = rows
+            .entry(price.map(|price| price.id))
+            .or_insert_with(|| PriceListRow {
+                price_id: price.map(|price| price.id),
+                name: price
+                    .map(|price| price.name.clone())
+                    .unwrap_or_else(|| NO_PRICE_LIST.to_string()),
+                lines: 0,
+                revenue: Money::ZERO,
+            });
+        row.lines += 1;
+        row.revenue += Money::from_f64(line.sale_product.total);
+    }
+    let mut rows: Vec<PriceListRow> = rows.into_iter().map(|(_, row)| row).collect();
+    rows.sort_by(|a, b| b.revenue.cmp(&a.revenue).then_with(|| a.name.cmp(&b.name)));
+    rows
+}
@@ -0,0 +1,261 @@
+use chrono::{Duration, NaiveDate};
+use yew::prelude::{html, ChangeData, Component, ComponentLink, Html, ShouldRender};
+use yew::virtual_dom::VNode;
+
+use crate::cache::{query_body, CachedQuery, Response};
+use crate::dashboard::kpi::Period;
+use crate::dates::{parse_date, today, DATE_FORMAT};
+use crate::fetching::FetchState;
+use crate::graphql;
+use crate::models::{FullSale, ListProduct, ListSale};
+use crate::navbar::Model as Navbar;
+use crate::views::{failed, loading, truncated};
+
+pub mod aggregate;
+
+use aggregate::{
+    by_period, by_price_list, by_product, in_range, summarize, top_products, ProductRow,
+};
+
+/// Sales fetched to report on; the range is applied to these.
+const SALES_LIMIT: i64 = 10_000;
+const PRODUCTS_LIMIT: i64 = 1000;
+const DEFAULT_DAYS: i64 = 30;
+const TOP_PRODUCTS: usize = 10;
+
+/// Revenue, discount and tax figures over a date range, broken down by
+/// period, product and price list.
+pub struct Model {
+    link: ComponentLink<Self>,
+    sales_query: CachedQuery,
+    products_query: CachedQuery,
+    sales: FetchState<ListSale>,
+    products: FetchState<ListProduct>,
+    period: Period,
+    from: NaiveDate,
+    to: NaiveDate,
+}
+
+pub enum Msg {
+    Cache(Response),
+    Retry,
+    Period(Period),
+    From(ChangeData),
+    To(ChangeData),
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let sales = query_body::<graphql::ListSale>(graphql::list_sale::Variables {
+            search: None,
+            limit: SALES_LIMIT,
+        });
+        let products = query_body::<graphql::ListProduct>(graphql::list_product::Variables {
+            search: String::new(),
+            limit: PRODUCTS_LIMIT,
+            rank: 0.0,
+        });
+        let to = today();
+        Self {
+            sales_query: CachedQuery::new(sales, link.callback(Msg::Cache)),
+            products_query: CachedQuery::new(products, link.callback(Msg::Cache)),
+            link,
+            sales: FetchState::Fetching,
+            products: FetchState::Fetching,
+            period: Period::Day,
+            from: to - Duration::days(DEFAULT_DAYS - 1),
+            to,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Cache(response) => {
+                if let Some(state) = self.sales_query.state(response.clone(), "listSale") {
+                    self.sales = state;
+                    true
+                } else if let Some(state) = self.products_query.state(response, "listProduct") {
+                    self.products = state;
+                    true
+                } else {
+                    false
+                }
+            }
+            Msg::Retry => {
+                if let FetchState::Failed(_) = self.sales {
+                    self.sales_query.refetch();
+                }
+                if let FetchState::Failed(_) = self.products {
+                    self.products_query.refetch();
+                }
+                false
+            }
+            Msg::Period(period) => {
+                self.period = period;
+                true
+            }
+            Msg::From(data) => {
+                if let Some(from) = parse_date(data).filter(|from| *from <= self.to) {
+                    self.from = from;
+                }
+                true
+            }
+            Msg::To(data) => {
+                if let Some(to) = parse_date(data).filter(|to| *to >= self.from) {
+                    self.to = to;
+                }
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> VNode {
+        let body = match (&self.sales, &self.products) {
+            (FetchState::Failed(error), _) | (_, FetchState::Failed(error)) => {
+                failed(error, self.link.callback(|_| Msg::Retry))
+            }
+            (FetchState::Success(sales), FetchState::Success(products)) => {
+                self.report(&sales.data, products)
+            }
+            _ => loading(),
+        };
+        html! {
+            <div>
+                <Navbar />
+                <h1 class="my-4"> { "Reports" } </h1>
+                { self.controls() }
+                { body }
+            </div>
+        }
+    }
+}
+
+impl Model {
+    fn controls(&self) -> Html {
+        html! {
+            <div class="form-inline mb-4 report-controls">
+                <label class="mr-2">{ "From" }</label>
+                <input type="date" class="form-control form-control-sm mr-3 report-from"
+                       value=self.from.format(DATE_FORMAT).to_string()
+                       onchange=self.link.callback(Msg::From) />
+                <label class="mr-2">{ "To" }</label>
+                <input type="date" class="form-control form-control-sm mr-3 report-to"
+                       value=self.to.format(DATE_FORMAT).to_string()
+                       onchange=self.link.callback(Msg::To) />
+                <div class="btn-group report-period">
+                    { for Period::ALL.iter().map(|period| {
+                        let period = *period;
+                        let class = if self.period == period {
+                            "btn btn-sm btn-secondary"
+                        } else {
+                            "btn btn-sm btn-outline-secondary"
+                        };
+                        html! {
+                            <button type="button" class=class
+                                    onclick=self.link.callback(move |_| Msg::Period(period))>
+                                { period.label() }
+                            </button>
+                        }
+                    }) }
+                </div>
+            </div>
+        }
+    }
+
+    fn report(&self, sales: &[FullSale], products: &ListProduct) -> Html {
+        let selected = in_range(sales, self.from, self.to);
+        let summary = summarize(&selected);
+        let figure = |label: &str, value: String| {
+            html! {
+                <div class="col">
+                    <h4>{ value }</h4>
+                    <small class="text-muted">{ label }</small>
+                </div>
+            }
+        };
+        html! {
+            <div class="report">
+                { truncated(sales.len(), SALES_LIMIT) }
+                <div class="row text-center mb-4 report-summary">
+                    { figure("Sales", summary.sales.to_string()) }
+                    { figure("Revenue", summary.revenue.to_string()) }
+                    { figure("Average ticket", summary.average_ticket.to_string()) }
+                    { figure("Discounts", summary.lines.discount.to_string()) }
+                    { figure("Taxes", summary.lines.tax.to_string()) }
+                </div>
+                <div class="row">
+                    <div class="col-md-6 mb-4">
+                        <h5>{ format!("Revenue per {}", self.period.label().to_lowercase()) }</h5>
+                        <table class="table table-sm report-periods">
+                            <tbody>
+                                { for by_period(&selected, self.period, self.from, self.to)
+                                    .into_iter()
+                                    .map(|(start, revenue)| html! {
+                                        <tr>
+                                            <td>{ self.period.format(start) }</td>
+                                            <td class="text-right">{ revenue.to_string() }</td>
+                                        </tr>
+                                    }) }
+                            </tbody>
+                        </table>
+                    </div>
+                    <div class="col-md-6 mb-4">
+                        <h5>{ format!("Top {} products by amount sold", TOP_PRODUCTS) }</h5>
+                        { product_table("report-top-products", &top_products(&selected, TOP_PRODUCTS)) }
+                        <h5>{ "Revenue per price list" }</h5>
+                        <table class="table table-sm report-price-lists">
+                            <thead>
+                                <tr>
+                                    <th>{ "Price list" }</th>
+                                    <th class="text-right">{ "Lines" }</th>
+                                    <th class="text-right">{ "Revenue" }</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                { for by_price_list(&selected, &products.data).into_iter().map(|row| html! {
+                                    <tr>
+                                        <td>{ row.name }</td>
+                                        <td class="text-right">{ row.lines }</td>
+                                        <td class="text-right">{ row.revenue.to_string() }</td>
+                                    </tr>
+                                }) }
+                            </tbody>
+                        </table>
+                    </div>
+                </div>
+                <h5>{ "Revenue per product" }</h5>
+                { product_table("report-products", &by_product(&selected)) }
+            </div>
+        }
+    }
+}
+
+fn product_table(class: &str, rows: &[ProductRow]) -> Html {
+    html! {
+        <table class=format!("table table-sm {}", class)>
+            <thead>
+                <tr>
+                    <th>{ "Product" }</th>
+                    <th class="text-right">{ "Amount" }</th>
+                    <th class="text-right">{ "Revenue" }</th>
+                </tr>
+            </thead>
+            <tbody>
+                { for rows.iter().map(|row| html! {
+                    <tr>
+                        <td>{ &row.name }</td>
+                        <td class="text-right">{ row.amount }</td>
+                        <td class="text-right">{ row.revenue.to_string() }</td>
+                    </tr>
+                }) }
+            </tbody>
+        </table>
+    }
+}
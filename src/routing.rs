@@ -11,6 +11,20 @@ pub enum AppRoute {
     Login,
     #[to = "/dashboard"]
     Dashboard,
+    #[to = "/reports"]
+    Reports,
+    #[to = "/sales/{id}"]
+    Sale(i64),
+    #[to = "/sales"]
+    Sales,
+    #[to = "/products/import"]
+    ProductImport,
+    #[to = "/products"]
+    Products,
+    #[to = "/prices"]
+    Prices,
+    #[to = "/pos"]
+    Pos,
     #[to = "/"]
     Index,
     #[to = "/page-not-found"]
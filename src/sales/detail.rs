@@ -0,0 +1,138 @@
+use yew::prelude::{html, ChangeData, Component, ComponentLink, Properties, ShouldRender};
+use yew::virtual_dom::VNode;
+use yew_router::prelude::RouterAnchor;
+
+use super::invoice::{print, Layout, Model as Invoice};
+use crate::cache::{query_body, CachedQuery, Response};
+use crate::fetching::{current_user, FetchState};
+use crate::graphql;
+use crate::models::FullSale;
+use crate::navbar::Model as Navbar;
+use crate::routing::AppRoute;
+use crate::views::{failed, loading};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub id: i64,
+}
+
+/// One sale, shown as its invoice, with a print action.
+pub struct Model {
+    link: ComponentLink<Self>,
+    props: Props,
+    query: CachedQuery,
+    state: FetchState<FullSale>,
+    layout: Layout,
+}
+
+pub enum Msg {
+    Cache(Response),
+    Retry,
+    Layout(ChangeData),
+    Print,
+}
+
+fn query(id: i64, link: &ComponentLink<Model>) -> CachedQuery {
+    let body = query_body::<graphql::ShowSale>(graphql::show_sale::Variables { sale_id: id });
+    CachedQuery::new(body, link.callback(Msg::Cache))
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            query: query(props.id, &link),
+            link,
+            props,
+            state: FetchState::Fetching,
+            layout: Layout::A4,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Cache(response) => match self.query.state(response, "showSale") {
+                Some(state) => {
+                    self.state = state;
+                    true
+                }
+                None => false,
+            },
+            Msg::Retry => {
+                self.query.refetch();
+                false
+            }
+            Msg::Layout(ChangeData::Select(select)) => {
+                if let Some(layout) = Layout::ALL
+                    .iter()
+                    .find(|layout| layout.label() == select.value())
+                {
+                    self.layout = *layout;
+                }
+                true
+            }
+            Msg::Layout(_) => false,
+            Msg::Print => {
+                print();
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.query = query(props.id, &self.link);
+        self.state = FetchState::Fetching;
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> VNode {
+        let body = match &self.state {
+            FetchState::Fetching => loading(),
+            FetchState::Failed(error) => failed(error, self.link.callback(|_| Msg::Retry)),
+            FetchState::Success(sale) => {
+                let company = current_user().map(|user| user.company);
+                html! {
+                    <>
+                        <div class="form-inline mb-3 d-print-none sale-actions">
+                            <select class="custom-select custom-select-sm mr-2 print-layout"
+                                    onchange=self.link.callback(Msg::Layout)>
+                                { for Layout::ALL.iter().map(|layout| html! {
+                                    <option value=layout.label() selected={*layout == self.layout}>
+                                        { layout.label() }
+                                    </option>
+                                }) }
+                            </select>
+                            <button type="button" class="btn btn-sm btn-primary print"
+                                    onclick=self.link.callback(|_| Msg::Print)>
+                                <i class="fas fa-print"></i>{ " Print" }
+                            </button>
+                        </div>
+                        <div class="print-area">
+                            <Invoice sale=sale.clone() company=company layout=self.layout />
+                        </div>
+                    </>
+                }
+            }
+        };
+        html! {
+            <div>
+                <Navbar />
+                <div class="d-print-none">
+                    <h1 class="my-4"> { "Sale" } </h1>
+                    <p>
+                        <RouterAnchor<AppRoute> route=AppRoute::Sales>
+                            { "Back to sales" }
+                        </RouterAnchor<AppRoute>>
+                    </p>
+                </div>
+                { body }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use yew::prelude::{html, Component, ComponentLink, Html, Properties, ShouldRender};
+use yew::services::ConsoleService;
+use yew::virtual_dom::VNode;
+
+use crate::dates::DATE_FORMAT;
+use crate::models::FullSale;
+use crate::pricing::{LineTotals, Money, SaleTotals};
+
+/// Heads the invoice when no signed-in user was saved at login.
+pub const UNKNOWN_SELLER: &str = "Unknown seller";
+
+/// Paper the invoice is laid out for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    A4,
+    /// Thermal receipt paper, 80mm wide.
+    Receipt80,
+    /// Thermal receipt paper, 58mm wide.
+    Receipt58,
+}
+
+impl Layout {
+    pub const ALL: [Layout; 3] = [Layout::A4, Layout::Receipt80, Layout::Receipt58];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Layout::A4 => "A4",
+            Layout::Receipt80 => "Receipt 80mm",
+            Layout::Receipt58 => "Receipt 58mm",
+        }
+    }
+
+    /// The last class also picks the paper size in print.css.
+    pub fn class(self) -> &'static str {
+        match self {
+            Layout::A4 => "invoice invoice-a4",
+            Layout::Receipt80 => "invoice invoice-receipt invoice-80mm",
+            Layout::Receipt58 => "invoice invoice-receipt invoice-58mm",
+        }
+    }
+
+    pub fn is_receipt(self) -> bool {
+        self != Layout::A4
+    }
+}
+
+/// Opens the browser's print dialog; the print stylesheet leaves only the
+/// `.print-area` on paper.
+pub fn print() {
+    if let Some(Err(error)) = web_sys::window().map(|window| window.print()) {
+        ConsoleService::new().log(&format!("Error: {:?}", error));
+    }
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub sale: FullSale,
+    /// The seller, from the user saved at login; `None` when nothing was
+    /// saved, which the header says rather than staying blank.
+    pub company: Option<String>,
+    pub layout: Layout,
+}
+
+/// A sale as it is printed: the company, the bill, its lines and totals.
+pub struct Model {
+    props: Props,
+}
+
+impl Component for Model {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> VNode {
+        let layout = self.props.layout;
+        let sale = &self.props.sale.sale;
+        let SaleTotals {
+            lines,
+            totals,
+            taxes,
+        } = SaleTotals::of(&self.props.sale);
+        html! {
+            <div class=layout.class()>
+                <header class="invoice-header">
+                    { match &self.props.company {
+                        Some(company) => html! { <h2 class="invoice-company">{ company }</h2> },
+                        None => html! {
+                            <h2 class="invoice-company invoice-company-unknown">{ UNKNOWN_SELLER }</h2>
+                        },
+                    } }
+                    <div class="invoice-bill">
+                        { format!("Invoice {}", sale.bill_number.clone().unwrap_or_else(|| format!("#{}", sale.id))) }
+                    </div>
+                    <div class="invoice-date">{ sale.sale_date.format(DATE_FORMAT).to_string() }</div>
+                </header>
+                { if layout.is_receipt() { self.receipt_lines(&lines) } else { self.table_lines(&lines) } }
+                <table class="invoice-totals">
+                    <tbody>
+                        { row("Subtotal", totals.gross) }
+                        { if totals.discount != Money::ZERO { row("Discount", -totals.discount) } else { html! {} } }
+                        { for taxes.iter().filter(|tax| tax.rate != 0).map(|tax| {
+                            row(&format!("Tax {}% on {}", tax.rate, tax.base), tax.tax)
+                        }) }
+                        <tr class="invoice-total">
+                            <th>{ "Total" }</th>
+                            <td>{ totals.total.to_string() }</td>
+                        </tr>
+                    </tbody>
+                </table>
+                <footer class="invoice-footer">{ "Thank you for your purchase" }</footer>
+            </div>
+        }
+    }
+}
+
+fn row(label: &str, amount: Money) -> Html {
+    html! {
+        <tr>
+            <th>{ label }</th>
+            <td>{ amount.to_string() }</td>
+        </tr>
+    }
+}
+
+impl Model {
+    fn table_lines(&self, totals: &[LineTotals]) -> Html {
+        html! {
+            <table class="invoice-lines">
+                <thead>
+                    <tr>
+                        <th>{ "Product" }</th>
+                        <th>{ "Amount" }</th>
+                        <th>{ "Price" }</th>
+                        <th>{ "Discount" }</th>
+                        <th>{ "Tax" }</th>
+                        <th>{ "Total" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for self.props.sale.sale_products.iter().zip(totals).map(|(line, totals)| {
+                        let item = &line.sale_product;
+                        html! {
+                            <tr>
+                                <td>{ &line.product.name }</td>
+                                <td>{ item.amount }</td>
+                                <td>{ Money::from_units(item.price).to_string() }</td>
+                                <td>{ format!("{}%", item.discount) }</td>
+                                <td>{ format!("{}%", item.tax) }</td>
+                                <td>{ totals.total.to_string() }</td>
+                            </tr>
+                        }
+                    }) }
+                </tbody>
+            </table>
+        }
+    }
+
+    /// One block per line, as a narrow roll has no room for columns.
+    fn receipt_lines(&self, totals: &[LineTotals]) -> Html {
+        html! {
+            <div class="invoice-lines">
+                { for self.props.sale.sale_products.iter().zip(totals).map(|(line, totals)| {
+                    let item = &line.sale_product;
+                    html! {
+                        <div class="invoice-line">
+                            <div>{ &line.product.name }</div>
+                            <div class="invoice-line-amounts">
+                                <span>{ format!("{} x {}", item.amount, Money::from_units(item.price)) }</span>
+                                <span>{ totals.total.to_string() }</span>
+                            </div>
+                            { if item.discount != 0 {
+                                html! { <div class="small">{ format!("Discount {}%", item.discount) }</div> }
+                            } else {
+                                html! {}
+                            } }
+                        </div>
+                    }
+                }) }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use chrono::NaiveDate;
+use yew::prelude::{html, Callback, ChangeData, Component, ComponentLink, InputData, ShouldRender};
+use yew::virtual_dom::VNode;
+use yew_router::prelude::RouterAnchor;
+
+use crate::cache::{query_body, CachedQuery, Response};
+use crate::dates::{parse_date, DATE_FORMAT};
+use crate::export::{tables, ExportMenu};
+use crate::fetching::FetchState;
+use crate::graphql;
+use crate::graphql::list_sale::{FormSale, SaleState as FormSaleState, Variables};
+use crate::models::{format_money, ListSale, SaleState};
+use crate::navbar::Model as Navbar;
+use crate::routing::AppRoute;
+use crate::views::{failed, loading};
+
+pub mod detail;
+pub mod invoice;
+
+const SALES_LIMIT: i64 = 1000;
+
+/// The filter of the sales list, sent as the `FormSale` search.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SaleSearch {
+    pub bill_number: String,
+    pub state: Option<SaleState>,
+    pub sale_date: Option<NaiveDate>,
+}
+
+impl SaleSearch {
+    pub fn is_empty(&self) -> bool {
+        self.bill_number.trim().is_empty() && self.state.is_none() && self.sale_date.is_none()
+    }
+
+    fn form(&self) -> Option<FormSale> {
+        if self.is_empty() {
+            return None;
+        }
+        let bill_number = self.bill_number.trim();
+        Some(FormSale {
+            id: None,
+            sale_date: self.sale_date,
+            user_id: None,
+            total: None,
+            bill_number: if bill_number.is_empty() {
+                None
+            } else {
+                Some(bill_number.to_string())
+            },
+            state: self.state.map(|state| match state {
+                SaleState::Draft => FormSaleState::DRAFT,
+                SaleState::Approved => FormSaleState::APPROVED,
+                SaleState::PartiallyPayed => FormSaleState::PARTIALLY_PAYED,
+                SaleState::Payed => FormSaleState::PAYED,
+                SaleState::Cancelled => FormSaleState::CANCELLED,
+            }),
+        })
+    }
+}
+
+fn query(search: &SaleSearch, callback: Callback<Response>) -> CachedQuery {
+    let body = query_body::<graphql::ListSale>(Variables {
+        search: search.form(),
+        limit: SALES_LIMIT,
+    });
+    CachedQuery::new(body, callback)
+}
+
+/// The sales, filtered by bill number, state and date.
+pub struct Model {
+    link: ComponentLink<Self>,
+    query: CachedQuery,
+    state: FetchState<ListSale>,
+    /// What the form shows; applied on search.
+    draft: SaleSearch,
+}
+
+pub enum Msg {
+    Cache(Response),
+    Retry,
+    BillNumber(String),
+    State(ChangeData),
+    Date(ChangeData),
+    Search,
+    Clear,
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let draft = SaleSearch::default();
+        Self {
+            query: query(&draft, link.callback(Msg::Cache)),
+            link,
+            state: FetchState::Fetching,
+            draft,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Cache(response) => match self.query.state(response, "listSale") {
+                Some(state) => {
+                    self.state = state;
+                    true
+                }
+                None => false,
+            },
+            Msg::Retry => {
+                self.query.refetch();
+                false
+            }
+            Msg::BillNumber(bill_number) => {
+                self.draft.bill_number = bill_number;
+                false
+            }
+            Msg::State(ChangeData::Select(select)) => {
+                self.draft.state = SaleState::ALL
+                    .iter()
+                    .find(|state| format!("{:?}", state) == select.value())
+                    .copied();
+                false
+            }
+            Msg::State(_) => false,
+            Msg::Date(data) => {
+                self.draft.sale_date = parse_date(data);
+                false
+            }
+            Msg::Search => {
+                self.state = FetchState::Fetching;
+                self.query = query(&self.draft, self.link.callback(Msg::Cache));
+                true
+            }
+            Msg::Clear => {
+                self.draft = SaleSearch::default();
+                self.link.send_message(Msg::Search);
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> VNode {
+        let body = match &self.state {
+            FetchState::Fetching => loading(),
+            FetchState::Failed(error) => failed(error, self.link.callback(|_| Msg::Retry)),
+            FetchState::Success(list) => html! {
+                <>
+                    <div class="d-flex justify-content-end mb-2">
+                        <ExportMenu table=tables::sales(&list.data) name="sales" />
+                    </div>
+                    <table class="table table-sm sales">
+                        <thead>
+                            <tr>
+                                <th>{ "Date" }</th>
+                                <th>{ "Bill number" }</th>
+                                <th>{ "State" }</th>
+                                <th class="text-right">{ "Total" }</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for list.data.iter().map(|full_sale| {
+                                let sale = &full_sale.sale;
+                                html! {
+                                    <tr>
+                                        <td>{ sale.sale_date.format(DATE_FORMAT).to_string() }</td>
+                                        <td>
+                                            <RouterAnchor<AppRoute> route=AppRoute::Sale(sale.id)>
+                                                { sale.bill_number.clone().unwrap_or_else(|| format!("#{}", sale.id)) }
+                                            </RouterAnchor<AppRoute>>
+                                        </td>
+                                        <td><span class=sale.state.badge()>{ sale.state.label() }</span></td>
+                                        <td class="text-right">{ format_money(sale.total) }</td>
+                                    </tr>
+                                }
+                            }) }
+                        </tbody>
+                    </table>
+                </>
+            },
+        };
+        html! {
+            <div>
+                <Navbar />
+                <h1 class="my-4"> { "Sales" } </h1>
+                <div class="form-inline mb-3 sale-search">
+                    <input type="text" class="form-control form-control-sm mr-2 search-bill-number"
+                           placeholder="Bill number" value=&self.draft.bill_number
+                           oninput=self.link.callback(|input: InputData| Msg::BillNumber(input.value)) />
+                    <select class="custom-select custom-select-sm mr-2 search-state"
+                            onchange=self.link.callback(Msg::State)>
+                        <option value="" selected={self.draft.state.is_none()}>{ "Any state" }</option>
+                        { for SaleState::ALL.iter().map(|state| html! {
+                            <option value=format!("{:?}", state)
+                                    selected={self.draft.state == Some(*state)}>
+                                { state.label() }
+                            </option>
+                        }) }
+                    </select>
+                    <input type="date" class="form-control form-control-sm mr-2 search-date"
+                           value={self.draft.sale_date
+                               .map(|date| date.format(DATE_FORMAT).to_string())
+                               .unwrap_or_default()}
+                           onchange=self.link.callback(Msg::Date) />
+                    <button type="button" class="btn btn-sm btn-primary mr-2 search"
+                            onclick=self.link.callback(|_| Msg::Search)>{ "Search" }</button>
+                    <button type="button" class="btn btn-sm btn-outline-secondary clear"
+                            onclick=self.link.callback(|_| Msg::Clear)>{ "Clear" }</button>
+                </div>
+                { body }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use wasm_bindgen::prelude::JsValue;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Headers, Request, RequestCredentials, RequestInit, RequestMode, Response};
+
+use crate::fetching::{FetchError, FetchResponse};
+
+const BASE_URL: &str = "http://localhost:8088";
+/// `<meta name="mystore-api-url" content="">` in index.html overrides
+/// `BASE_URL`; an empty value makes requests same-origin. The server adds
+/// it to the index when it proxies or mocks the API.
+const BASE_URL_META: &str = "meta[name=mystore-api-url]";
+
+pub type TransportFuture = Pin<Box<dyn Future<Output = Result<FetchResponse<JsValue>, FetchError>>>>;
+
+/// A request as seen by a `Transport`, before it goes out on the wire.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportRequest {
+    pub url: String,
+    pub method: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+/// Sends a `TransportRequest` and resolves to the response headers and its
+/// JSON body.
+pub trait Transport {
+    fn fetch(&self, request: TransportRequest) -> TransportFuture;
+
+    /// Whether requests go to the page's own origin, where custom headers do
+    /// not need a CORS preflight the API may not answer.
+    fn is_same_origin(&self) -> bool {
+        true
+    }
+}
+
+thread_local! {
+    static TRANSPORT: RefCell<Rc<dyn Transport>> = RefCell::new(Rc::new(WebTransport::default()));
+}
+
+/// Replaces the transport used by `send_request`. Call it before the app
+/// starts, e.g. from a test, to swap the browser fetch for a `MockTransport`.
+pub fn set_transport(transport: Rc<dyn Transport>) {
+    TRANSPORT.with(|current| *current.borrow_mut() = transport);
+}
+
+pub fn current_transport() -> Rc<dyn Transport> {
+    TRANSPORT.with(|current| current.borrow().clone())
+}
+
+pub struct WebTransport {
+    base_url: String,
+}
+
+impl WebTransport {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.to_string(),
+        }
+    }
+}
+
+impl Default for WebTransport {
+    fn default() -> Self {
+        let configured = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.query_selector(BASE_URL_META).ok().flatten())
+            .and_then(|meta| meta.get_attribute("content"));
+        Self::new(configured.as_deref().unwrap_or(BASE_URL))
+    }
+}
+
+impl Transport for WebTransport {
+    fn is_same_origin(&self) -> bool {
+        let origin = web_sys::window().and_then(|window| window.location().origin().ok());
+        self.base_url.is_empty() || origin.map_or(false, |origin| self.base_url == origin)
+    }
+
+    fn fetch(&self, request: TransportRequest) -> TransportFuture {
+        let url = format!("{}{}", self.base_url, request.url);
+        Box::pin(async move {
+            let mut opts = RequestInit::new();
+            opts.method(&request.method);
+            opts.mode(RequestMode::Cors);
+            opts.credentials(RequestCredentials::SameOrigin);
+            if let Some(body) = &request.body {
+                opts.body(Some(&JsValue::from_str(body)));
+            }
+
+            let web_request = Request::new_with_str_and_init(&url, &opts)?;
+            for (name, value) in &request.headers {
+                web_request.headers().set(name, value)?;
+            }
+
+            let window = web_sys::window()
+                .ok_or_else(|| JsValue::from_str("Could not get a window object"))?;
+            let resp_value = JsFuture::from(window.fetch_with_request(&web_request)).await?;
+            let resp: Response = resp_value.dyn_into()?;
+
+            let headers = resp.headers();
+            let data = JsFuture::from(resp.json()?).await?;
+            Ok(FetchResponse { headers, data })
+        })
+    }
+}
+
+#[derive(Clone)]
+struct MockResponse {
+    headers: Vec<(String, String)>,
+    body: serde_json::Value,
+}
+
+/// Method, url and, for responses to one GraphQL operation only, its name.
+type MockKey = (String, String, Option<String>);
+
+/// In-memory transport that replays canned responses keyed by method, url
+/// and GraphQL operation, and records every request it receives.
+#[derive(Default)]
+pub struct MockTransport {
+    responses: RefCell<HashMap<MockKey, VecDeque<MockResponse>>>,
+    requests: RefCell<Vec<TransportRequest>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a response for `method url`, whatever operation a request
+    /// body names. Responses for the same key are replayed in order; the
+    /// last one is repeated once the queue runs dry.
+    pub fn respond<T: Serialize>(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: &T,
+    ) -> &Self {
+        self.queue((method.to_string(), url.to_string(), None), headers, body)
+    }
+
+    /// Queues a response for `method url` requests whose body names the
+    /// GraphQL `operation`. These win over the ones queued by `respond`.
+    pub fn respond_to<T: Serialize>(
+        &self,
+        method: &str,
+        url: &str,
+        operation: &str,
+        headers: &[(&str, &str)],
+        body: &T,
+    ) -> &Self {
+        let key = (
+            method.to_string(),
+            url.to_string(),
+            Some(operation.to_string()),
+        );
+        self.queue(key, headers, body)
+    }
+
+    pub fn requests(&self) -> Vec<TransportRequest> {
+        self.requests.borrow().clone()
+    }
+
+    fn queue<T: Serialize>(&self, key: MockKey, headers: &[(&str, &str)], body: &T) -> &Self {
+        let response = MockResponse {
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            body: serde_json::to_value(body).unwrap_or(serde_json::Value::Null),
+        };
+        self.responses
+            .borrow_mut()
+            .entry(key)
+            .or_insert_with(VecDeque::new)
+            .push_back(response);
+        self
+    }
+
+    fn next_response(&self, request: &TransportRequest) -> Option<MockResponse> {
+        let operation = request
+            .body
+            .as_ref()
+            .and_then(|body| serde_json::from_str::<serde_json::Value>(body).ok())
+            .and_then(|body| body["operationName"].as_str().map(str::to_string));
+        let mut responses = self.responses.borrow_mut();
+        let for_operation = (request.method.clone(), request.url.clone(), operation);
+        let key = if responses.contains_key(&for_operation) {
+            for_operation
+        } else {
+            (request.method.clone(), request.url.clone(), None)
+        };
+        let queue = responses.get_mut(&key)?;
+        if queue.len() > 1 {
+            queue.pop_front()
+        } else {
+            queue.front().cloned()
+        }
+    }
+}
+
+impl Transport for MockTransport {
+    fn fetch(&self, request: TransportRequest) -> TransportFuture {
+        let response = self.next_response(&request);
+        let description = format!("{} {}", request.method, request.url);
+        self.requests.borrow_mut().push(request);
+        Box::pin(async move {
+            let response = response.ok_or_else(|| {
+                FetchError::from(format!("No canned response for {}", description).as_str())
+            })?;
+            let headers = Headers::new()?;
+            for (name, value) in &response.headers {
+                headers.set(name, value)?;
+            }
+            let data = JsValue::from_serde(&response.body).map_err(|error| FetchError {
+                err: JsValue::from_str(&error.to_string()),
+            })?;
+            Ok(FetchResponse { headers, data })
+        })
+    }
+}
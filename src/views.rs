@@ -0,0 +1,36 @@
+use yew::prelude::{html, Callback, Html, MouseEvent};
+
+use crate::fetching::FetchError;
+
+pub fn loading() -> Html {
+    html! {
+        <div class="text-center text-muted">
+            <i class="fas fa-spinner fa-spin"></i>
+            { " Loading..." }
+        </div>
+    }
+}
+
+/// The error of a failed query, with a button to ask again.
+pub fn failed(error: &FetchError, retry: Callback<MouseEvent>) -> Html {
+    html! {
+        <div class="alert alert-danger mb-0">
+            { format!("Could not load: {} ", error) }
+            <button class="btn btn-sm btn-outline-danger" onclick=retry>{ "Retry" }</button>
+        </div>
+    }
+}
+
+/// Warns that a list of sales came back as long as it was allowed to be, so
+/// figures worked out from it may leave some sales out. The API does not
+/// say which sales it sends first, so the warning does not either.
+pub fn truncated(shown: usize, limit: i64) -> Html {
+    if (shown as i64) < limit {
+        return html! {};
+    }
+    html! {
+        <div class="alert alert-warning py-1 small sales-truncated">
+            { format!("Only the first {} sales are shown.", limit) }
+        </div>
+    }
+}
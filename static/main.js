@@ -0,0 +1,7 @@
+import init from "./wasm.js";
+
+init();
+
+if ("serviceWorker" in navigator) {
+    navigator.serviceWorker.register("/sw.js", { scope: "/" });
+}
@@ -0,0 +1,80 @@
+use serde_json::json;
+
+use yew_mystore::cache::{query_key, Store};
+
+fn sale_response(total: f64) -> serde_json::Value {
+    json!({
+        "showSale": {
+            "sale": { "__typename": "Sale", "id": 1, "total": total, "state": "DRAFT" },
+            "saleProducts": [{
+                "product": { "__typename": "Product", "id": 7, "name": "Coffee" },
+                "saleProduct": { "__typename": "SaleProduct", "id": 3, "amount": 2.0 }
+            }]
+        }
+    })
+}
+
+#[test]
+fn reads_back_normalized_query() {
+    let mut store = Store::new();
+    store.write_query("ShowSale:{\"saleId\":1}", &sale_response(10.0));
+
+    assert_eq!(
+        store.read_query("ShowSale:{\"saleId\":1}"),
+        Some(sale_response(10.0))
+    );
+    assert_eq!(store.entities().len(), 3);
+}
+
+#[test]
+fn shares_entities_between_queries() {
+    let mut store = Store::new();
+    store.write_query("ShowSale:{\"saleId\":1}", &sale_response(10.0));
+    store.write_query(
+        "ListProduct:{}",
+        &json!({ "listProduct": { "data": [{
+            "product": { "__typename": "Product", "id": 7, "name": "Espresso" }
+        }]}}),
+    );
+
+    let sale = store.read_query("ShowSale:{\"saleId\":1}").unwrap();
+    assert_eq!(
+        sale["showSale"]["saleProducts"][0]["product"]["name"],
+        "Espresso"
+    );
+}
+
+#[test]
+fn mutation_results_update_cached_entities() {
+    let mut store = Store::new();
+    store.write_query("ShowSale:{\"saleId\":1}", &sale_response(10.0));
+    store.write_entities(&json!({
+        "updateSale": { "sale": { "__typename": "Sale", "id": 1, "total": 25.0 } }
+    }));
+
+    let sale = store.read_query("ShowSale:{\"saleId\":1}").unwrap();
+    assert_eq!(sale["showSale"]["sale"]["total"], 25.0);
+    assert_eq!(sale["showSale"]["sale"]["state"], "DRAFT");
+}
+
+#[test]
+fn evicted_entities_read_as_null() {
+    let mut store = Store::new();
+    store.write_query("ShowSale:{\"saleId\":1}", &sale_response(10.0));
+    store.evict("Sale", 1);
+
+    let sale = store.read_query("ShowSale:{\"saleId\":1}").unwrap();
+    assert!(sale["showSale"]["sale"].is_null());
+    assert_eq!(store.entity("Product", 7).unwrap()["name"], "Coffee");
+}
+
+#[test]
+fn keys_queries_by_operation_and_variables() {
+    let body = json!({
+        "operationName": "ShowSale",
+        "query": "query ShowSale { ... }",
+        "variables": { "saleId": 1 }
+    });
+
+    assert_eq!(query_key(&body), "ShowSale:{\"saleId\":1}");
+}
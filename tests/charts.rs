@@ -0,0 +1,58 @@
+use std::f64::consts::PI;
+
+use yew_mystore::charts::{angles, arc_path, nice_max, short_number, shown_labels, ticks, Plot};
+
+#[test]
+fn rounds_the_axis_up_to_a_nice_number() {
+    assert_eq!(nice_max(0.0), 1.0);
+    assert_eq!(nice_max(7.0), 10.0);
+    assert_eq!(nice_max(12.0), 20.0);
+    assert_eq!(nice_max(340.0), 500.0);
+    assert_eq!(nice_max(1000.0), 1000.0);
+    assert_eq!(ticks(500.0), vec![0.0, 125.0, 250.0, 375.0, 500.0]);
+}
+
+#[test]
+fn places_points_inside_the_plot() {
+    let plot = Plot::default();
+    assert_eq!(plot.x(0, 3), plot.left);
+    assert_eq!(plot.x(2, 3), plot.left + plot.width);
+    assert_eq!(plot.x(0, 1), plot.left + plot.width / 2.0);
+    assert_eq!(plot.y(0.0, 10.0), plot.bottom());
+    assert_eq!(plot.y(10.0, 10.0), plot.top);
+    assert_eq!(plot.y(-5.0, 10.0), plot.bottom());
+}
+
+#[test]
+fn thins_out_x_labels() {
+    assert_eq!(shown_labels(5), vec![0, 1, 2, 3, 4]);
+    assert_eq!(shown_labels(30), vec![0, 4, 8, 12, 16, 20, 24, 28]);
+    assert!(shown_labels(0).is_empty());
+}
+
+#[test]
+fn splits_the_circle_by_value() {
+    let slices = angles(&[1.0, 3.0, 0.0, -2.0]);
+    assert_eq!(slices[0], (0.0, PI / 2.0));
+    assert_eq!(slices[1], (PI / 2.0, 2.0 * PI));
+    assert_eq!(slices[2].0, slices[2].1);
+    assert_eq!(slices[3].0, slices[3].1);
+    assert!(angles(&[0.0, 0.0]).iter().all(|(start, end)| start == end));
+}
+
+#[test]
+fn draws_ring_segments() {
+    assert_eq!(
+        arc_path(100.0, 100.0, 90.0, 50.0, 0.0, PI / 2.0),
+        "M100.00,10.00 A90,90 0 0 1 190.00,100.00 L150.00,100.00 A50,50 0 0 0 100.00,50.00 Z"
+    );
+    assert!(arc_path(100.0, 100.0, 90.0, 50.0, 0.0, 2.0 * PI).contains(" 0 1 1 "));
+}
+
+#[test]
+fn shortens_axis_numbers() {
+    assert_eq!(short_number(0.0), "0");
+    assert_eq!(short_number(250.0), "250");
+    assert_eq!(short_number(1200.0), "1.2k");
+    assert_eq!(short_number(3_000_000.0), "3M");
+}
@@ -0,0 +1,136 @@
+//! Model factories shared by the tests. `common/mod.rs` needs a browser, so
+//! plain `#[test]` files include this file on its own:
+//!
+//! ```ignore
+//! #[path = "common/fixtures.rs"]
+//! mod fixtures;
+//! ```
+#![allow(dead_code)]
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use serde_json::Value;
+
+use yew_mystore::models::{
+    FullPriceProduct, FullProduct, FullSale, FullSaleProduct, Price, PriceProduct, Product, Sale,
+    SaleProduct, SaleState,
+};
+
+pub fn date(text: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+}
+
+/// Ten in stock, costing 100.
+pub fn product(id: i64, name: &str) -> Product {
+    Product {
+        id,
+        name: name.to_string(),
+        stock: 10.0,
+        cost: Some(100),
+        description: None,
+        user_id: 1,
+    }
+}
+
+/// `product` on no price list.
+pub fn unpriced(product: Product) -> FullProduct {
+    FullProduct {
+        product,
+        price_products: vec![],
+    }
+}
+
+/// `product` on the "Retail" price list `price_id`, at `amount`.
+pub fn priced(product: Product, price_id: i64, amount: Option<i64>) -> FullProduct {
+    FullProduct {
+        price_products: vec![FullPriceProduct {
+            price_product: PriceProduct {
+                id: product.id,
+                price_id,
+                product_id: product.id,
+                user_id: 1,
+                amount,
+            },
+            price: Price {
+                id: price_id,
+                name: "Retail".to_string(),
+                user_id: 1,
+            },
+        }],
+        product,
+    }
+}
+
+/// A sale line of `amount` at `price`, totalling `total`.
+pub fn line(
+    product: Product,
+    amount: f64,
+    price: i64,
+    discount: i64,
+    tax: i64,
+    total: f64,
+) -> FullSaleProduct {
+    let sale_product = SaleProduct {
+        id: 0,
+        product_id: product.id,
+        sale_id: 0,
+        amount,
+        discount,
+        tax,
+        price,
+        total,
+    };
+    FullSaleProduct {
+        sale_product,
+        product,
+    }
+}
+
+/// A sale of `lines`, totalling them.
+pub fn sale(id: i64, day: &str, state: SaleState, mut lines: Vec<FullSaleProduct>) -> FullSale {
+    for line in lines.iter_mut() {
+        line.sale_product.sale_id = id;
+    }
+    FullSale {
+        sale: Sale {
+            id,
+            user_id: 1,
+            sale_date: date(day),
+            total: lines.iter().map(|line| line.sale_product.total).sum(),
+            bill_number: Some(format!("B-{}", id)),
+            state,
+        },
+        sale_products: lines,
+    }
+}
+
+/// `model` as the API sends it, with the `__typename` of every entity the
+/// cache normalizes.
+pub fn graphql<T: Serialize>(model: &T) -> Value {
+    let mut value = serde_json::to_value(model).unwrap();
+    add_typenames(&mut value);
+    value
+}
+
+fn add_typenames(value: &mut Value) {
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(add_typenames),
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                add_typenames(field);
+                let typename = match name.as_str() {
+                    "sale" => "Sale",
+                    "saleProduct" => "SaleProduct",
+                    "product" => "Product",
+                    "priceProduct" => "PriceProduct",
+                    "price" => "Price",
+                    _ => continue,
+                };
+                if let Value::Object(entity) = field {
+                    entity.insert("__typename".to_string(), Value::from(typename));
+                }
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,112 @@
+#![allow(dead_code)]
+
+pub mod fixtures;
+
+use std::rc::Rc;
+use wasm_bindgen::prelude::JsValue;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Element, Event, HtmlElement, HtmlInputElement, HtmlSelectElement, KeyboardEvent,
+    KeyboardEventInit, Storage,
+};
+use yew::html::ComponentLink;
+use yew::{App, Component};
+
+use yew_mystore::transport::{set_transport, MockTransport};
+
+pub const TOKEN_KEY: &str = "mystore.key";
+
+/// Installs a fresh `MockTransport` and clears any session left behind by a
+/// previous test.
+pub fn setup() -> Rc<MockTransport> {
+    local_storage().clear().unwrap();
+    navigate("/");
+    let transport = Rc::new(MockTransport::new());
+    set_transport(transport.clone());
+    transport
+}
+
+/// Mounts `COMP` into a detached `div` and returns that root element.
+pub fn mount<COMP>() -> (Element, ComponentLink<COMP>)
+where
+    COMP: Component,
+    COMP::Properties: Default,
+{
+    let root = document().create_element("div").unwrap();
+    let link = App::<COMP>::new().mount(root.clone());
+    (root, link)
+}
+
+pub fn type_into(root: &Element, selector: &str, value: &str) {
+    let input: HtmlInputElement = query(root, selector).unchecked_into();
+    input.set_value(value);
+    input.dispatch_event(&Event::new("input").unwrap()).unwrap();
+}
+
+pub fn choose(root: &Element, selector: &str, value: &str) {
+    let select: HtmlSelectElement = query(root, selector).unchecked_into();
+    select.set_value(value);
+    select
+        .dispatch_event(&Event::new("change").unwrap())
+        .unwrap();
+}
+
+/// Presses `key`, e.g. `"Enter"` or `"ArrowDown"`, on the element.
+pub fn press(root: &Element, selector: &str, key: &str) {
+    let mut init = KeyboardEventInit::new();
+    init.key(key).bubbles(true).cancelable(true);
+    let event = KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init).unwrap();
+    query(root, selector).dispatch_event(&event).unwrap();
+}
+
+pub fn click(root: &Element, selector: &str) {
+    let element: HtmlElement = query(root, selector).unchecked_into();
+    element.click();
+}
+
+pub fn query(root: &Element, selector: &str) -> Element {
+    root.query_selector(selector)
+        .unwrap()
+        .unwrap_or_else(|| panic!("No element matches {}", selector))
+}
+
+/// Lets pending futures, and the component updates they trigger, run.
+pub async fn flush() {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        web_sys::window()
+            .unwrap()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, 10)
+            .unwrap();
+    });
+    JsFuture::from(promise).await.unwrap();
+}
+
+pub fn navigate(path: &str) {
+    web_sys::window()
+        .unwrap()
+        .history()
+        .unwrap()
+        .replace_state_with_url(&JsValue::NULL, "", Some(path))
+        .unwrap();
+}
+
+pub fn current_path() -> String {
+    web_sys::window().unwrap().location().pathname().unwrap()
+}
+
+pub fn stored_token() -> Option<String> {
+    local_storage().get_item(TOKEN_KEY).unwrap()
+}
+
+pub fn store_token(token: &str) {
+    local_storage().set_item(TOKEN_KEY, token).unwrap();
+}
+
+fn local_storage() -> Storage {
+    web_sys::window().unwrap().local_storage().unwrap().unwrap()
+}
+
+fn document() -> web_sys::Document {
+    web_sys::window().unwrap().document().unwrap()
+}
@@ -0,0 +1,349 @@
+mod common;
+
+use serde_json::json;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+use yew_mystore::dashboard::Model as Dashboard;
+use yew_mystore::dates::today;
+use yew_mystore::fetching::REQUEST_ID_HEADER;
+use yew_mystore::offline::load_snapshot;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn sale(id: i64, date: &str, total: f64, state: &str) -> serde_json::Value {
+    json!({
+        "sale": {
+            "__typename": "Sale",
+            "id": id,
+            "userId": 1,
+            "saleDate": date,
+            "total": total,
+            "billNumber": format!("B-{}", id),
+            "state": state,
+        },
+        "saleProducts": [],
+    })
+}
+
+fn product(id: i64, name: &str, stock: f64) -> serde_json::Value {
+    json!({
+        "product": {
+            "__typename": "Product",
+            "id": id,
+            "name": name,
+            "stock": stock,
+            "cost": 100,
+            "description": null,
+            "userId": 1,
+        },
+        "priceProducts": [],
+    })
+}
+
+/// One answer for every dashboard query, as they all go to `/graphql`.
+fn dashboard_data() -> serde_json::Value {
+    json!({
+        "data": {
+            "dashboard": "{\"todaySales\":120.5,\"monthSales\":3400,\"salesCount\":3,\"productsCount\":2}",
+            "listSale": { "data": [
+                sale(1, "2020-05-01", 10.0, "DRAFT"),
+                sale(2, "2020-05-03", 20.0, "PAYED"),
+                sale(3, "2020-05-02", 30.0, "PAYED"),
+            ] },
+            "listProduct": { "data": [
+                product(1, "Coffee", 2.0),
+                product(2, "Tea", 40.0),
+            ] },
+        }
+    })
+}
+
+#[wasm_bindgen_test]
+async fn loads_dashboard_query_on_mount() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "secret-token")],
+        &dashboard_data(),
+    );
+    let (root, _) = common::mount::<Dashboard>();
+    common::flush().await;
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests.iter().all(|request| request.url == "/graphql"
+        && request
+            .headers
+            .contains(&("x-csrf-token".to_string(), "secret-token".to_string()))));
+    assert!(root.inner_html().contains("Dashboard"));
+}
+
+#[wasm_bindgen_test]
+async fn renders_kpis_from_sales_and_products() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "secret-token")],
+        &dashboard_data(),
+    );
+    let (root, _) = common::mount::<Dashboard>();
+    common::flush().await;
+
+    assert_eq!(
+        common::query(&root, ".sales-today").text_content().unwrap(),
+        "120.50"
+    );
+    assert_eq!(
+        common::query(&root, ".sales-month").text_content().unwrap(),
+        "3400.00"
+    );
+    let states = common::query(&root, ".sale-states").text_content().unwrap();
+    assert!(states.contains("Draft1"));
+    assert!(states.contains("Paid2"));
+    let low_stock = common::query(&root, ".low-stock").text_content().unwrap();
+    assert!(low_stock.contains("Coffee"));
+    assert!(!low_stock.contains("Tea"));
+    let recent = common::query(&root, ".recent-sales tr")
+        .text_content()
+        .unwrap();
+    assert!(recent.contains("2020-05-03"));
+    assert!(recent.contains("B-2"));
+}
+
+#[wasm_bindgen_test]
+async fn works_the_totals_out_when_the_dashboard_string_is_not_json() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    let today = today().format("%Y-%m-%d").to_string();
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "secret-token")],
+        &json!({
+            "data": {
+                "dashboard": "Welcome back",
+                "listSale": { "data": [
+                    sale(1, &today, 10.0, "PAYED"),
+                    sale(2, &today, 2.5, "DRAFT"),
+                    sale(3, &today, 99.0, "CANCELLED"),
+                ] },
+                "listProduct": { "data": [product(1, "Coffee", 2.0)] },
+            }
+        }),
+    );
+    let (root, _) = common::mount::<Dashboard>();
+    common::flush().await;
+
+    assert_eq!(
+        common::query(&root, ".sales-today").text_content().unwrap(),
+        "12.50"
+    );
+    assert_eq!(
+        common::query(&root, ".sales-month").text_content().unwrap(),
+        "12.50"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn draws_sales_charts_and_switches_to_bars() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "secret-token")],
+        &dashboard_data(),
+    );
+    let (root, _) = common::mount::<Dashboard>();
+    common::flush().await;
+
+    assert_eq!(
+        root.query_selector_all(".chart-donut path")
+            .unwrap()
+            .length(),
+        2
+    );
+    assert!(root
+        .query_selector(".sales-chart .chart-line polyline")
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        root.query_selector_all(".sales-chart circle")
+            .unwrap()
+            .length(),
+        30
+    );
+
+    common::click(&root, ".chart-kind button:last-child");
+    common::flush().await;
+
+    assert!(root
+        .query_selector(".sales-chart .chart-line")
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        root.query_selector_all(".sales-chart .chart-bar rect")
+            .unwrap()
+            .length(),
+        30
+    );
+}
+
+#[wasm_bindgen_test]
+async fn shows_an_error_with_retry_when_a_query_fails() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "secret-token")],
+        &json!({ "errors": [{ "message": "boom" }] }),
+    );
+    let (root, _) = common::mount::<Dashboard>();
+    common::flush().await;
+
+    assert!(root.inner_html().contains("boom"));
+    let before = transport.requests().len();
+    common::click(&root, ".alert button");
+    common::flush().await;
+
+    // The failed sales and products queries, not the dashboard one.
+    assert_eq!(transport.requests().len(), before + 2);
+}
+
+#[wasm_bindgen_test]
+async fn retries_only_the_query_that_failed() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    let headers = [("x-csrf-token", "secret-token")];
+    transport
+        .respond("POST", "/graphql", &headers, &dashboard_data())
+        .respond_to(
+            "POST",
+            "/graphql",
+            "ListSale",
+            &headers,
+            &json!({ "errors": [{ "message": "boom" }] }),
+        );
+    let (root, _) = common::mount::<Dashboard>();
+    common::flush().await;
+
+    assert!(root.inner_html().contains("boom"));
+    assert!(root.query_selector(".low-stock").unwrap().is_some());
+    let before = transport.requests().len();
+    common::click(&root, ".alert button");
+    common::flush().await;
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), before + 1);
+    assert!(requests[before]
+        .body
+        .as_ref()
+        .unwrap()
+        .contains(r#""operationName":"ListSale""#));
+}
+
+#[wasm_bindgen_test]
+async fn redirects_to_login_on_token_mismatch() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    common::navigate("/dashboard");
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "another-token")],
+        &dashboard_data(),
+    );
+    common::mount::<Dashboard>();
+    common::flush().await;
+
+    assert_eq!(common::current_path(), "/login");
+}
+
+#[wasm_bindgen_test]
+async fn logs_out_and_redirects_to_login() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    common::navigate("/dashboard");
+    transport
+        .respond(
+            "POST",
+            "/graphql",
+            &[("x-csrf-token", "secret-token")],
+            &dashboard_data(),
+        )
+        .respond("DELETE", "/logout", &[], &json!("logged out"));
+    let (root, _) = common::mount::<Dashboard>();
+    common::flush().await;
+
+    common::click(&root, "button");
+    common::flush().await;
+
+    let requests = transport.requests();
+    assert_eq!(requests.last().unwrap().method, "DELETE");
+    assert_eq!(common::stored_token(), None);
+    assert_eq!(common::current_path(), "/login");
+}
+
+#[wasm_bindgen_test]
+async fn logging_out_leaves_nothing_to_restore() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    common::navigate("/dashboard");
+    transport
+        .respond(
+            "POST",
+            "/graphql",
+            &[("x-csrf-token", "secret-token")],
+            &dashboard_data(),
+        )
+        .respond("DELETE", "/logout", &[], &json!("logged out"));
+    let (root, _) = common::mount::<Dashboard>();
+    common::flush().await;
+
+    let snapshot = load_snapshot().await.unwrap().expect("the cache is saved");
+    assert!(!snapshot.contains("secret-token"));
+
+    common::click(&root, "button");
+    common::flush().await;
+
+    assert_eq!(load_snapshot().await.unwrap(), None);
+    assert_eq!(common::stored_token(), None);
+}
+
+#[wasm_bindgen_test]
+async fn attaches_a_fresh_request_id_to_each_request() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "secret-token")],
+        &dashboard_data(),
+    );
+    common::mount::<Dashboard>();
+    common::mount::<Dashboard>();
+    common::flush().await;
+
+    let ids: Vec<String> = transport
+        .requests()
+        .iter()
+        .filter_map(|request| {
+            request
+                .headers
+                .iter()
+                .find(|(name, _)| name == REQUEST_ID_HEADER)
+                .map(|(_, value)| value.clone())
+        })
+        .collect();
+    assert_eq!(ids.len(), 10);
+    assert!(ids.iter().all(|id| id.len() == 36));
+    let mut unique = ids.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), ids.len());
+}
@@ -0,0 +1,173 @@
+#[path = "common/fixtures.rs"]
+mod fixtures;
+
+use serde_json::json;
+
+use yew_mystore::dashboard::kpi::{
+    chart_range, count_by_state, low_stock, recent_sales, sales_over_time, DashboardSummary, Period,
+};
+use yew_mystore::models::{decode, FullProduct, FullSale, ListSale, Product, SaleState};
+
+use fixtures::{date, line, product, unpriced};
+
+/// A sale of 10.00.
+fn sale(id: i64, day: &str, state: SaleState) -> FullSale {
+    fixtures::sale(
+        id,
+        day,
+        state,
+        vec![line(product(1, "Coffee"), 1.0, 10, 0, 0, 10.0)],
+    )
+}
+
+fn stocked(id: i64, name: &str, stock: f64) -> FullProduct {
+    unpriced(Product {
+        stock,
+        ..product(id, name)
+    })
+}
+
+#[test]
+fn parses_the_dashboard_summary() {
+    let summary = DashboardSummary::parse(r#"{"todaySales":1.5,"salesCount":2}"#).unwrap();
+    assert_eq!(summary.today_sales, 1.5);
+    assert_eq!(summary.month_sales, 0.0);
+    assert_eq!(summary.sales_count, 2);
+    assert!(DashboardSummary::parse("dashboard").is_err());
+}
+
+#[test]
+fn works_the_summary_out_from_the_lists() {
+    let sales = vec![
+        sale(1, "2020-05-14", SaleState::Payed),
+        sale(2, "2020-05-14", SaleState::Cancelled),
+        sale(3, "2020-05-02", SaleState::Draft),
+        sale(4, "2020-04-30", SaleState::Payed),
+    ];
+    let products = vec![stocked(1, "Tea", 4.0), stocked(2, "Coffee", 1.0)];
+
+    assert_eq!(
+        DashboardSummary::from_lists(&sales, &products, date("2020-05-14")),
+        DashboardSummary {
+            today_sales: 10.0,
+            month_sales: 20.0,
+            sales_count: 3,
+            products_count: 2,
+        }
+    );
+}
+
+#[test]
+fn counts_every_state_even_when_empty() {
+    let sales = vec![
+        sale(1, "2020-05-01", SaleState::Payed),
+        sale(2, "2020-05-01", SaleState::Payed),
+        sale(3, "2020-05-01", SaleState::Draft),
+    ];
+    let counts = count_by_state(&sales);
+    assert_eq!(counts.len(), SaleState::ALL.len());
+    assert!(counts.contains(&(SaleState::Payed, 2)));
+    assert!(counts.contains(&(SaleState::Draft, 1)));
+    assert!(counts.contains(&(SaleState::Cancelled, 0)));
+}
+
+#[test]
+fn lists_low_stock_emptiest_first() {
+    let products = vec![
+        stocked(1, "Tea", 4.0),
+        stocked(2, "Coffee", 1.0),
+        stocked(3, "Sugar", 50.0),
+        stocked(4, "Milk", 5.0),
+    ];
+    let names: Vec<&str> = low_stock(&products, 5.0, 2)
+        .iter()
+        .map(|product| product.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Coffee", "Tea"]);
+}
+
+#[test]
+fn lists_recent_sales_newest_first() {
+    let sales = vec![
+        sale(1, "2020-05-01", SaleState::Draft),
+        sale(2, "2020-05-03", SaleState::Draft),
+        sale(3, "2020-05-03", SaleState::Draft),
+        sale(4, "2020-05-02", SaleState::Draft),
+    ];
+    let ids: Vec<i64> = recent_sales(&sales, 3)
+        .iter()
+        .map(|full_sale| full_sale.sale.id)
+        .collect();
+    assert_eq!(ids, vec![3, 2, 4]);
+}
+
+#[test]
+fn decodes_a_field_of_the_response_data() {
+    let data = json!({ "listSale": { "data": [] } });
+    let list: ListSale = decode(&data, "listSale").unwrap();
+    assert!(list.data.is_empty());
+    assert!(decode::<ListSale>(&data, "listProduct").is_err());
+}
+
+#[test]
+fn buckets_start_on_the_day_monday_or_first_of_month() {
+    // 2020-05-14 is a Thursday.
+    assert_eq!(Period::Day.start(date("2020-05-14")), date("2020-05-14"));
+    assert_eq!(Period::Week.start(date("2020-05-14")), date("2020-05-11"));
+    assert_eq!(Period::Month.start(date("2020-05-14")), date("2020-05-01"));
+    assert_eq!(Period::Month.next(date("2020-12-01")), date("2021-01-01"));
+}
+
+#[test]
+fn totals_sales_per_period_within_the_range() {
+    let mut sales = vec![
+        sale(1, "2020-05-01", SaleState::Payed),
+        sale(2, "2020-05-01", SaleState::Draft),
+        sale(3, "2020-05-03", SaleState::Payed),
+        sale(4, "2020-05-03", SaleState::Cancelled),
+        sale(5, "2020-04-30", SaleState::Payed),
+    ];
+    sales[2].sale.total = 25.0;
+
+    let daily = sales_over_time(&sales, Period::Day, date("2020-05-01"), date("2020-05-03"));
+    assert_eq!(
+        daily,
+        vec![
+            (date("2020-05-01"), 20.0),
+            (date("2020-05-02"), 0.0),
+            (date("2020-05-03"), 25.0),
+        ]
+    );
+
+    let monthly = sales_over_time(
+        &sales,
+        Period::Month,
+        date("2020-04-15"),
+        date("2020-05-31"),
+    );
+    assert_eq!(
+        monthly,
+        vec![(date("2020-04-01"), 10.0), (date("2020-05-01"), 45.0)]
+    );
+}
+
+#[test]
+fn draws_wide_ranges_with_longer_periods() {
+    let to = date("2020-05-31");
+    assert_eq!(
+        chart_range(Period::Day, date("2020-03-01"), to),
+        (Period::Day, date("2020-03-01"))
+    );
+    assert_eq!(
+        chart_range(Period::Day, date("2020-01-01"), to),
+        (Period::Week, date("2020-01-01"))
+    );
+    assert_eq!(
+        chart_range(Period::Week, date("2015-01-01"), to),
+        (Period::Month, date("2015-01-01"))
+    );
+    assert_eq!(
+        chart_range(Period::Month, date("1990-01-01"), to),
+        (Period::Month, date("2012-10-01"))
+    );
+}
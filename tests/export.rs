@@ -0,0 +1,162 @@
+use chrono::NaiveDate;
+
+use yew_mystore::export::csv::{self, CsvOptions, NumberLocale};
+use yew_mystore::export::zip::{crc32, ZipWriter};
+use yew_mystore::export::{tables, xlsx, Cell, Table};
+use yew_mystore::models::{FullPriceProduct, FullProduct, Price, PriceProduct, Product};
+
+fn table() -> Table {
+    Table {
+        headers: vec!["Name".to_string(), "Amount".to_string(), "Date".to_string()],
+        rows: vec![
+            vec![
+                Cell::from("Coffee, \"dark\""),
+                Cell::from(1.5),
+                Cell::from(NaiveDate::from_ymd(2020, 5, 1)),
+            ],
+            vec![Cell::from("Tea\nbags"), Cell::from(2i64), Cell::Empty],
+        ],
+    }
+}
+
+#[test]
+fn writes_rfc_4180_csv() {
+    assert_eq!(
+        csv::write(&table(), CsvOptions::default()),
+        "Name,Amount,Date\r\n\"Coffee, \"\"dark\"\"\",1.5,2020-05-01\r\n\"Tea\nbags\",2,\r\n"
+    );
+}
+
+#[test]
+fn follows_the_locale_for_numbers_and_delimiter() {
+    let options = CsvOptions::for_locale(NumberLocale::for_language("es-CO"));
+    assert_eq!(options.delimiter, ';');
+    assert_eq!(
+        csv::write(&table(), options),
+        "Name;Amount;Date\r\n\"Coffee, \"\"dark\"\"\";1,5;2020-05-01\r\n\"Tea\nbags\";2;\r\n"
+    );
+    let comma_decimal = CsvOptions {
+        delimiter: ',',
+        locale: NumberLocale::COMMA,
+    };
+    assert!(csv::write(&table(), comma_decimal).contains(",\"1,5\","));
+    assert_eq!(NumberLocale::for_language("en-US"), NumberLocale::POINT);
+    assert_eq!(NumberLocale::for_language("de"), NumberLocale::COMMA);
+}
+
+#[test]
+fn keeps_formula_like_text_as_text() {
+    let table = Table {
+        headers: vec!["Name".to_string(), "Amount".to_string()],
+        rows: vec![
+            vec![Cell::from("=1+2"), Cell::from(-1.5)],
+            vec![Cell::from("@SUM(A1)"), Cell::from(-2i64)],
+            vec![Cell::from("+57 300"), Cell::from("-, dash")],
+            vec![Cell::from("a=b"), Cell::Empty],
+        ],
+    };
+    assert_eq!(
+        csv::write(&table, CsvOptions::default()),
+        "Name,Amount\r\n'=1+2,-1.5\r\n'@SUM(A1),-2\r\n'+57 300,\"'-, dash\"\r\na=b,\r\n"
+    );
+}
+
+#[test]
+fn names_spreadsheet_columns_and_dates() {
+    assert_eq!(xlsx::column_name(0), "A");
+    assert_eq!(xlsx::column_name(25), "Z");
+    assert_eq!(xlsx::column_name(26), "AA");
+    assert_eq!(xlsx::column_name(701), "ZZ");
+    assert_eq!(xlsx::column_name(702), "AAA");
+    assert_eq!(xlsx::serial(NaiveDate::from_ymd(1900, 3, 1)), 61);
+    assert_eq!(xlsx::serial(NaiveDate::from_ymd(2020, 5, 1)), 43952);
+}
+
+#[test]
+fn writes_the_sheet_cells() {
+    let xml = xlsx::sheet_xml(&table());
+    assert!(xml.contains(
+        r#"<c r="A1" s="2" t="inlineStr"><is><t xml:space="preserve">Name</t></is></c>"#
+    ));
+    assert!(xml.contains(r#"<t xml:space="preserve">Coffee, &quot;dark&quot;</t>"#));
+    assert!(xml.contains(r#"<c r="B2"><v>1.5</v></c>"#));
+    assert!(xml.contains(r#"<c r="C2" s="1"><v>43952</v></c>"#));
+    assert!(!xml.contains(r#"r="C3""#));
+
+    let odd = Table {
+        headers: vec!["Amount".to_string()],
+        rows: vec![
+            vec![Cell::from(f64::NAN)],
+            vec![Cell::from(f64::INFINITY)],
+            vec![Cell::from(f64::NEG_INFINITY)],
+        ],
+    };
+    let xml = xlsx::sheet_xml(&odd);
+    assert!(!xml.contains("<v>"));
+    assert!(xml.contains(r#"<row r="2"></row>"#));
+}
+
+#[test]
+fn packages_the_workbook_as_a_zip() {
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+    let bytes = xlsx::write(&table(), "Sales");
+    assert_eq!(&bytes[..4], b"PK\x03\x04");
+    let end = bytes.len() - 22;
+    assert_eq!(&bytes[end..end + 4], b"PK\x05\x06");
+    assert_eq!(u16::from_le_bytes([bytes[end + 10], bytes[end + 11]]), 6);
+
+    let mut zip = ZipWriter::new();
+    zip.add("a.txt", b"abc");
+    let bytes = zip.finish();
+    assert_eq!(&bytes[14..18], &crc32(b"abc").to_le_bytes());
+    assert_eq!(&bytes[30..35], b"a.txt");
+    assert_eq!(&bytes[35..38], b"abc");
+}
+
+#[test]
+fn gives_each_price_list_a_product_column() {
+    let full_price = |price_id: i64, name: &str, amount: i64| FullPriceProduct {
+        price_product: PriceProduct {
+            id: price_id,
+            price_id,
+            product_id: 1,
+            user_id: 1,
+            amount: Some(amount),
+        },
+        price: Price {
+            id: price_id,
+            name: name.to_string(),
+            user_id: 1,
+        },
+    };
+    let product = |id: i64, prices: Vec<FullPriceProduct>| FullProduct {
+        product: Product {
+            id,
+            name: format!("Product {}", id),
+            stock: 1.0,
+            cost: None,
+            description: None,
+            user_id: 1,
+        },
+        price_products: prices,
+    };
+    let products = vec![
+        product(
+            1,
+            vec![full_price(2, "Wholesale", 80), full_price(1, "Retail", 100)],
+        ),
+        product(2, vec![full_price(1, "Retail", 50)]),
+    ];
+    let table = tables::products(&products);
+
+    assert_eq!(
+        &table.headers[5..],
+        &["Retail".to_string(), "Wholesale".to_string()]
+    );
+    assert_eq!(
+        &table.rows[0][5..],
+        &[Cell::Integer(100), Cell::Integer(80)]
+    );
+    assert_eq!(&table.rows[1][5..], &[Cell::Integer(50), Cell::Empty]);
+}
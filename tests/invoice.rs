@@ -0,0 +1,67 @@
+#[path = "common/fixtures.rs"]
+mod fixtures;
+
+use yew_mystore::models::SaleState;
+use yew_mystore::pricing::{LineTotals, Money, SaleTotals, TaxLine};
+use yew_mystore::sales::invoice::Layout;
+
+use fixtures::{line, product, sale};
+
+#[test]
+fn groups_tax_by_rate() {
+    let sale = sale(
+        1,
+        "2020-05-01",
+        SaleState::Payed,
+        vec![
+            line(product(1, "Coffee"), 2.0, 100, 10, 20, 216.0),
+            line(product(2, "Tea"), 1.0, 50, 0, 10, 55.0),
+            line(product(3, "Milk"), 1.0, 40, 0, 20, 48.0),
+        ],
+    );
+    let totals = SaleTotals::of(&sale);
+
+    assert_eq!(totals.lines.len(), 3);
+    assert_eq!(totals.totals.gross, Money::from_units(290));
+    assert_eq!(totals.totals.discount, Money::from_units(20));
+    assert_eq!(totals.totals.tax, Money::from_units(5 + 36 + 8));
+    assert_eq!(totals.totals.total, Money::from_f64(sale.sale.total));
+    assert_eq!(
+        totals.taxes,
+        vec![
+            TaxLine {
+                rate: 10,
+                base: Money::from_units(50),
+                tax: Money::from_units(5),
+            },
+            TaxLine {
+                rate: 20,
+                base: Money::from_units(220),
+                tax: Money::from_units(44),
+            },
+        ]
+    );
+}
+
+#[test]
+fn totals_an_empty_sale() {
+    let totals = SaleTotals::of(&sale(1, "2020-05-01", SaleState::Payed, vec![]));
+
+    assert_eq!(totals.totals, LineTotals::default());
+    assert!(totals.taxes.is_empty());
+}
+
+#[test]
+fn sizes_the_page_for_the_layout() {
+    let print_css = include_str!("../static/css/print.css");
+    for layout in Layout::ALL.iter() {
+        let class = layout.class().rsplit(' ').next().unwrap();
+        assert!(
+            print_css.contains(&format!("body:has(.{}) {{\n    page: ", class)),
+            "{}",
+            class
+        );
+    }
+    assert!(!Layout::A4.is_receipt());
+    assert!(Layout::Receipt80.is_receipt());
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use serde_json::json;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+use yew_mystore::login::Model as Login;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn current_user() -> serde_json::Value {
+    json!({
+        "id": 1,
+        "email": "jhon@doe.com",
+        "company": "My Store",
+        "created_at": "2020-06-01T00:00:00"
+    })
+}
+
+#[wasm_bindgen_test]
+fn renders_login_form() {
+    common::setup();
+    let (root, _) = common::mount::<Login>();
+
+    let html = root.inner_html();
+    assert!(html.contains("name=\"email\""));
+    assert!(html.contains("name=\"password\""));
+    assert!(html.contains("LogIn"));
+}
+
+#[wasm_bindgen_test]
+async fn logs_in_and_redirects_to_dashboard() {
+    let transport = common::setup();
+    transport.respond(
+        "POST",
+        "/login",
+        &[("x-csrf-token", "secret-token")],
+        &current_user(),
+    );
+    let (root, _) = common::mount::<Login>();
+
+    common::type_into(&root, "input[name=email]", "jhon@doe.com");
+    common::type_into(&root, "input[name=password]", "12345678");
+    common::click(&root, "button");
+    common::flush().await;
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    let body: serde_json::Value =
+        serde_json::from_str(requests[0].body.as_ref().unwrap()).unwrap();
+    assert_eq!(
+        body,
+        json!({ "email": "jhon@doe.com", "password": "12345678" })
+    );
+    assert_eq!(common::stored_token(), Some("secret-token".to_string()));
+    assert_eq!(common::current_path(), "/dashboard");
+}
+
+#[wasm_bindgen_test]
+async fn does_not_send_invalid_credentials() {
+    let transport = common::setup();
+    let (root, _) = common::mount::<Login>();
+
+    common::type_into(&root, "input[name=email]", "not an email");
+    common::type_into(&root, "input[name=password]", "short");
+    common::click(&root, "button");
+    common::flush().await;
+
+    assert!(transport.requests().is_empty());
+    assert_eq!(common::stored_token(), None);
+    assert_eq!(common::current_path(), "/");
+}
@@ -0,0 +1,100 @@
+use serde_json::json;
+
+use yew_mystore::offline::{created_id, MutationQueue};
+
+fn create_sale() -> serde_json::Value {
+    json!({
+        "operationName": "CreateSale",
+        "query": "mutation CreateSale { ... }",
+        "variables": {
+            "form": { "id": null, "saleDate": "2020-06-01", "total": 10.0 },
+            "formSaleProducts": { "data": [] }
+        }
+    })
+}
+
+fn pay_sale(sale_id: i64) -> serde_json::Value {
+    json!({
+        "operationName": "PaySale",
+        "query": "mutation PaySale { ... }",
+        "variables": { "saleId": sale_id }
+    })
+}
+
+#[test]
+fn assigns_temporary_ids_to_created_entities() {
+    let mut queue = MutationQueue::new();
+    let first = queue.enqueue(create_sale());
+    let second = queue.enqueue(create_sale());
+    let paid = queue.enqueue(pay_sale(-1));
+
+    assert_eq!(first.temp_id.unwrap().id, -1);
+    assert_eq!(second.temp_id.unwrap().id, -2);
+    assert_eq!(paid.temp_id, None);
+    assert_eq!(queue.len(), 3);
+}
+
+#[test]
+fn strips_temporary_id_before_sending() {
+    let mut queue = MutationQueue::new();
+    let mutation = queue.enqueue(create_sale());
+
+    assert_eq!(mutation.body["variables"]["form"]["id"], -1);
+    assert!(mutation.outgoing_body()["variables"]["form"]["id"].is_null());
+    assert_eq!(mutation.optimistic_entity().unwrap()["__typename"], "Sale");
+}
+
+#[test]
+fn reconciles_server_ids_in_queued_mutations() {
+    let mut queue = MutationQueue::new();
+    queue.enqueue(create_sale());
+    queue.enqueue(pay_sale(-1));
+
+    let created = queue.pop_front().unwrap();
+    let response = json!({
+        "createSale": { "sale": { "__typename": "Sale", "id": 42 }, "saleProducts": [] }
+    });
+    let real_id = created_id(&response, &created.temp_id.unwrap().typename).unwrap();
+    queue.reconcile(-1, real_id);
+
+    assert_eq!(queue.front().unwrap().body["variables"]["saleId"], 42);
+}
+
+#[test]
+fn restores_previous_session_in_front() {
+    let mut previous = MutationQueue::new();
+    previous.enqueue(create_sale());
+    let mut queue = MutationQueue::new();
+    queue.enqueue(pay_sale(7));
+
+    queue.restore(previous);
+    let next = queue.enqueue(create_sale());
+
+    assert_eq!(queue.front().unwrap().operation(), "CreateSale");
+    assert_eq!(next.temp_id.unwrap().id, -2);
+}
+
+#[test]
+fn renumbers_restored_temporary_ids_below_current_ones() {
+    let mut previous = MutationQueue::new();
+    previous.enqueue(create_sale());
+    previous.enqueue(create_sale());
+    previous.enqueue(pay_sale(-1));
+    let mut queue = MutationQueue::new();
+    let current = queue.enqueue(create_sale());
+
+    queue.restore(previous);
+    let next = queue.enqueue(create_sale());
+
+    let mut ids = Vec::new();
+    while let Some(mutation) = queue.pop_front() {
+        ids.push((mutation.temp_id.map(|temp_id| temp_id.id), mutation.body));
+    }
+    assert_eq!(current.temp_id.unwrap().id, -1);
+    assert_eq!(ids[0].0, Some(-2));
+    assert_eq!(ids[0].1["variables"]["form"]["id"], -2);
+    assert_eq!(ids[1].0, Some(-3));
+    assert_eq!(ids[2].1["variables"]["saleId"], -2);
+    assert_eq!(ids[3].0, Some(-1));
+    assert_eq!(next.temp_id.unwrap().id, -4);
+}
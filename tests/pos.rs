@@ -0,0 +1,169 @@
+mod common;
+
+use serde_json::json;
+use wasm_bindgen::prelude::JsValue;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+use yew_mystore::models::SaleState;
+use yew_mystore::pos::Model as Pos;
+
+use common::fixtures::{graphql, line, priced, product, sale, unpriced};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// One answer for the till's queries and the checkout's mutations, as
+/// they all go to `/graphql`.
+fn pos_data() -> serde_json::Value {
+    let coffee = product(1, "Coffee");
+    json!({
+        "data": {
+            "ListPrice": { "data": [
+                { "__typename": "Price", "id": 7, "name": "Retail", "userId": 1 },
+            ] },
+            "listProduct": { "data": [
+                graphql(&priced(coffee.clone(), 7, Some(250))),
+                graphql(&unpriced(product(2, "Tea"))),
+            ] },
+            "createSale": graphql(&sale(
+                9,
+                "2020-05-01",
+                SaleState::Draft,
+                vec![line(coffee, 2.0, 250, 0, 0, 500.0)],
+            )),
+            "approveSale": true,
+            "paySale": true,
+        }
+    })
+}
+
+/// Replaces `window.print` with a counter, so no dialog opens.
+fn stub_print() {
+    let window = web_sys::window().unwrap();
+    let print = js_sys::Function::new_no_args("window.printed = (window.printed || 0) + 1;");
+    js_sys::Reflect::set(&window, &JsValue::from("print"), &print).unwrap();
+    js_sys::Reflect::set(&window, &JsValue::from("printed"), &JsValue::from(0)).unwrap();
+}
+
+fn printed() -> f64 {
+    js_sys::Reflect::get(&web_sys::window().unwrap(), &JsValue::from("printed"))
+        .unwrap()
+        .as_f64()
+        .unwrap_or_default()
+}
+
+async fn mount_till() -> (
+    web_sys::Element,
+    std::rc::Rc<yew_mystore::transport::MockTransport>,
+) {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "secret-token")],
+        &pos_data(),
+    );
+    let (root, _) = common::mount::<Pos>();
+    common::flush().await;
+    (root, transport)
+}
+
+#[wasm_bindgen_test]
+async fn rings_up_scanned_codes_and_searches() {
+    let (root, _) = mount_till().await;
+
+    common::type_into(&root, ".pos-search", "2*1");
+    common::press(&root, ".pos-search", "Enter");
+    common::type_into(&root, ".pos-search", "te");
+    common::press(&root, ".pos-search", "Enter");
+    common::flush().await;
+
+    let cart = common::query(&root, ".pos-cart tbody")
+        .text_content()
+        .unwrap();
+    assert!(cart.contains("Coffee"));
+    assert!(cart.contains("Tea"));
+    assert_eq!(
+        root.query_selector_all(".pos-cart tbody tr")
+            .unwrap()
+            .length(),
+        2
+    );
+    let total = common::query(&root, ".pos-total").text_content().unwrap();
+    assert!(total.contains("600.00"));
+
+    common::type_into(&root, ".pos-search", "nothing");
+    common::press(&root, ".pos-search", "Enter");
+    common::flush().await;
+    assert!(common::query(&root, ".pos-notice")
+        .text_content()
+        .unwrap()
+        .contains("nothing"));
+}
+
+#[wasm_bindgen_test]
+async fn needs_enough_cash_tendered() {
+    let (root, _) = mount_till().await;
+
+    common::type_into(&root, ".pos-search", "1");
+    common::press(&root, ".pos-search", "Enter");
+    common::press(&root, ".pos-search", "Enter");
+    common::flush().await;
+    common::type_into(&root, ".pos-tendered", "200");
+    common::flush().await;
+
+    assert!(common::query(&root, ".pos-pay").has_attribute("disabled"));
+
+    common::type_into(&root, ".pos-tendered", "300");
+    common::flush().await;
+    assert!(!common::query(&root, ".pos-pay").has_attribute("disabled"));
+    assert!(common::query(&root, ".pos-change")
+        .text_content()
+        .unwrap()
+        .contains("50.00"));
+}
+
+#[wasm_bindgen_test]
+async fn creates_approves_and_pays_then_prints_the_receipt() {
+    stub_print();
+    let (root, transport) = mount_till().await;
+
+    common::type_into(&root, ".pos-search", "2*Coffee");
+    common::press(&root, ".pos-search", "Enter");
+    common::press(&root, ".pos-search", "Enter");
+    common::flush().await;
+    common::type_into(&root, ".pos-tendered", "1000");
+    common::press(&root, ".pos-tendered", "Enter");
+    for _ in 0..4 {
+        common::flush().await;
+    }
+
+    let operations: Vec<String> = transport
+        .requests()
+        .iter()
+        .filter_map(|request| request.body.as_ref())
+        .filter_map(|body| serde_json::from_str::<serde_json::Value>(body).ok())
+        .filter_map(|body| body["operationName"].as_str().map(str::to_string))
+        .filter(|operation| !operation.starts_with("List"))
+        .collect();
+    assert_eq!(operations, vec!["CreateSale", "ApproveSale", "PaySale"]);
+
+    let pay: serde_json::Value =
+        serde_json::from_str(transport.requests().last().unwrap().body.as_ref().unwrap()).unwrap();
+    assert_eq!(pay["variables"]["saleId"], 9);
+
+    assert!(common::query(&root, ".pos-done")
+        .text_content()
+        .unwrap()
+        .contains("Change 500.00"));
+    let receipt = common::query(&root, ".print-area .invoice-80mm")
+        .text_content()
+        .unwrap();
+    assert!(receipt.contains("B-9"));
+    assert_eq!(printed(), 1.0);
+
+    common::click(&root, ".pos-new-sale");
+    common::flush().await;
+    assert!(root.query_selector(".pos-search").unwrap().is_some());
+    assert!(root.query_selector(".pos-cart tbody tr").unwrap().is_none());
+}
@@ -0,0 +1,143 @@
+#[path = "common/fixtures.rs"]
+mod fixtures;
+
+use chrono::NaiveDate;
+
+use yew_mystore::models::{FullProduct, Product, SaleState};
+use yew_mystore::pos::cart::{change_due, find_code, search, split_quantity, unit_price, Cart};
+use yew_mystore::pricing::Money;
+
+use fixtures::{priced, product};
+
+fn described(id: i64, name: &str, description: &str) -> Product {
+    Product {
+        description: Some(description.to_string()),
+        ..product(id, name)
+    }
+}
+
+fn catalogue() -> Vec<FullProduct> {
+    vec![
+        priced(described(1, "Coffee beans", "Dark roast"), 1, Some(120)),
+        priced(product(2, "Iced coffee"), 1, Some(90)),
+        priced(described(42, "Tea", "Green"), 1, None),
+    ]
+}
+
+#[test]
+fn adds_a_scanned_product_to_its_line() {
+    let coffee = product(1, "Coffee");
+    let mut cart = Cart::default();
+
+    assert_eq!(cart.add(&coffee, 100, 1.0), 0);
+    assert_eq!(cart.add(&product(2, "Tea"), 50, 1.0), 1);
+    assert_eq!(cart.add(&coffee, 100, 2.0), 0);
+
+    assert_eq!(cart.lines.len(), 2);
+    assert_eq!(cart.lines[0].amount, 3.0);
+    assert_eq!(cart.items(), 4.0);
+    assert_eq!(cart.total(), Money::from_units(350));
+}
+
+#[test]
+fn keeps_a_line_per_price() {
+    let coffee = product(1, "Coffee");
+    let mut cart = Cart::default();
+    cart.add(&coffee, 100, 1.0);
+    cart.add(&coffee, 90, 1.0);
+
+    assert_eq!(cart.lines.len(), 2);
+}
+
+#[test]
+fn adjusts_amounts_and_discounts() {
+    let mut cart = Cart::default();
+    cart.add(&product(1, "Coffee"), 100, 1.0);
+    cart.add(&product(2, "Tea"), 50, 1.0);
+
+    cart.set_amount(0, 2.5);
+    cart.set_discount(0, 10);
+    assert_eq!(cart.lines[0].totals().total, Money::from_units(225));
+
+    cart.set_discount(1, 150);
+    assert_eq!(cart.lines[1].discount, 100);
+    cart.set_discount(1, -5);
+    assert_eq!(cart.lines[1].discount, 0);
+
+    cart.set_amount(1, 0.0);
+    assert_eq!(cart.lines.len(), 1);
+    cart.remove(5);
+    assert_eq!(cart.lines.len(), 1);
+}
+
+#[test]
+fn turns_the_cart_into_a_paid_sale() {
+    let mut cart = Cart::default();
+    cart.add(&product(1, "Coffee"), 100, 2.0);
+    cart.set_discount(0, 50);
+    let sale = cart.sale(-1, NaiveDate::from_ymd(2020, 5, 1));
+
+    assert_eq!(sale.sale.id, -1);
+    assert_eq!(sale.sale.state, SaleState::Payed);
+    assert_eq!(sale.sale.total, 100.0);
+    assert_eq!(sale.sale_products[0].sale_product.sale_id, -1);
+    assert_eq!(sale.sale_products[0].sale_product.total, 100.0);
+}
+
+#[test]
+fn prices_from_the_list_or_the_cost() {
+    let products = catalogue();
+
+    assert_eq!(unit_price(&products[0], Some(1)), 120);
+    assert_eq!(unit_price(&products[0], Some(2)), 100);
+    assert_eq!(unit_price(&products[2], Some(1)), 100);
+    assert_eq!(unit_price(&products[0], None), 100);
+}
+
+#[test]
+fn finds_exact_codes() {
+    let products = catalogue();
+
+    assert_eq!(find_code(&products, "42").unwrap().product.name, "Tea");
+    assert_eq!(find_code(&products, " tea ").unwrap().product.id, 42);
+    assert!(find_code(&products, "coffee").is_none());
+    assert!(find_code(&products, "").is_none());
+}
+
+#[test]
+fn searches_names_then_descriptions() {
+    let products = catalogue();
+    let names = |query: &str, limit: usize| -> Vec<String> {
+        search(&products, query, limit)
+            .iter()
+            .map(|product| product.product.name.clone())
+            .collect()
+    };
+
+    assert_eq!(names("coffee", 5), vec!["Coffee beans", "Iced coffee"]);
+    assert_eq!(names("ICED", 5), vec!["Iced coffee"]);
+    assert_eq!(names("green", 5), vec!["Tea"]);
+    assert_eq!(names("coffee", 1), vec!["Coffee beans"]);
+    assert!(names("  ", 5).is_empty());
+}
+
+#[test]
+fn splits_quantities_off_codes() {
+    assert_eq!(split_quantity("3*42"), (3.0, "42"));
+    assert_eq!(split_quantity("1.5 * tea"), (1.5, "tea"));
+    assert_eq!(split_quantity("42"), (1.0, "42"));
+    assert_eq!(split_quantity("0*42"), (1.0, "0*42"));
+    assert_eq!(split_quantity("a*b"), (1.0, "a*b"));
+}
+
+#[test]
+fn calculates_change() {
+    let cents = Money::from_cents;
+    assert_eq!(change_due(cents(1250), cents(2000)), Some(cents(750)));
+    assert_eq!(change_due(cents(1250), cents(1250)), Some(Money::ZERO));
+    assert_eq!(
+        change_due(Money::from_f64(0.3), Money::from_f64(0.1 + 0.2)),
+        Some(Money::ZERO)
+    );
+    assert_eq!(change_due(cents(1250), cents(1200)), None);
+}
@@ -0,0 +1,399 @@
+use yew_mystore::models::{FullSale, FullSaleProduct, Product, Sale, SaleProduct, SaleState};
+use yew_mystore::pricing::{
+    line_totals, sale_totals, Discount, LineInput, LineTotals, Money, Quantity, Rounding, Rules,
+    SaleTotals, TaxLine, TaxMode,
+};
+
+const MODES: [Rounding; 4] = [
+    Rounding::HalfUp,
+    Rounding::HalfEven,
+    Rounding::Down,
+    Rounding::Up,
+];
+
+fn cents(cents: i64) -> Money {
+    Money::from_cents(cents)
+}
+
+fn input(price: i64, millis: i64, discount: Discount, tax_rate: i64) -> LineInput {
+    LineInput {
+        price: cents(price),
+        quantity: Quantity::from_millis(millis),
+        discount,
+        tax_rate,
+    }
+}
+
+fn exclusive(rounding: Rounding) -> Rules {
+    Rules {
+        tax_mode: TaxMode::Exclusive,
+        rounding,
+    }
+}
+
+fn inclusive(rounding: Rounding) -> Rules {
+    Rules {
+        tax_mode: TaxMode::Inclusive,
+        rounding,
+    }
+}
+
+fn totals(gross: i64, discount: i64, net: i64, tax: i64, total: i64) -> LineTotals {
+    LineTotals {
+        gross: cents(gross),
+        discount: cents(discount),
+        net: cents(net),
+        tax: cents(tax),
+        total: cents(total),
+    }
+}
+
+#[test]
+fn rounds_quotients_in_every_mode() {
+    // (numerator, denominator, [half up, half even, down, up])
+    let cases: [(i128, i128, [i64; 4]); 12] = [
+        (6, 3, [2, 2, 2, 2]),
+        (7, 2, [4, 4, 3, 4]),
+        (5, 2, [3, 2, 2, 3]),
+        (9, 4, [2, 2, 2, 3]),
+        (11, 4, [3, 3, 2, 3]),
+        (-5, 2, [-3, -2, -2, -3]),
+        (-7, 2, [-4, -4, -3, -4]),
+        (-9, 4, [-2, -2, -2, -3]),
+        (-11, 4, [-3, -3, -2, -3]),
+        (5, -2, [-3, -2, -2, -3]),
+        (-5, -2, [3, 2, 2, 3]),
+        (0, 7, [0, 0, 0, 0]),
+    ];
+    for (numerator, denominator, expected) in cases.iter() {
+        for (mode, expected) in MODES.iter().zip(expected.iter()) {
+            assert_eq!(
+                mode.divide(*numerator, *denominator),
+                Some(*expected),
+                "{} / {} rounded {:?}",
+                numerator,
+                denominator,
+                mode
+            );
+        }
+    }
+}
+
+#[test]
+fn refuses_to_divide_by_zero_or_past_an_i64() {
+    assert_eq!(Rounding::HalfUp.divide(1, 0), None);
+    assert_eq!(cents(100).divide(0, Rounding::HalfUp), None);
+    assert_eq!(Rounding::Down.divide(i128::from(i64::MAX) + 1, 1), None);
+    assert_eq!(Rounding::Up.divide(i128::from(i64::MAX) * 2 + 1, 2), None);
+    assert_eq!(Rounding::Down.divide(i128::MIN, -1), None);
+    assert_eq!(
+        Rounding::HalfUp.divide(i128::from(i64::MIN), 1),
+        Some(i64::MIN)
+    );
+    assert_eq!(Rounding::HalfUp.divide(i128::MAX - 1, i128::MAX), Some(1));
+    assert_eq!(cents(i64::MAX).percent(200, Rounding::HalfUp), None);
+}
+
+#[test]
+fn rounds_half_up_by_default() {
+    assert_eq!(Rounding::default(), Rounding::HalfUp);
+    assert_eq!(Rules::default(), exclusive(Rounding::HalfUp));
+    assert_eq!(Discount::default(), Discount::Percent(0));
+}
+
+#[test]
+fn converts_money_and_quantities() {
+    assert_eq!(Money::from_units(12).cents(), 1200);
+    assert_eq!(Money::from_f64(12.346).cents(), 1235);
+    assert_eq!(Money::from_f64(-0.004).cents(), 0);
+    assert_eq!(Money::from_f64(0.1 + 0.2), cents(30));
+    assert_eq!(cents(1250).to_f64(), 12.5);
+    assert_eq!(Quantity::from_units(3).millis(), 3000);
+    assert_eq!(Quantity::from_f64(0.3756).millis(), 376);
+    assert_eq!(Quantity::from_millis(1500).to_f64(), 1.5);
+}
+
+#[test]
+fn adds_and_shares_out_money() {
+    assert_eq!(cents(150) + cents(25), cents(175));
+    assert_eq!(cents(150) - cents(200), cents(-50));
+    assert_eq!(-cents(150), cents(-150));
+    assert_eq!(cents(-150).abs(), cents(150));
+    let mut money = cents(100);
+    money += cents(5);
+    money -= cents(10);
+    assert_eq!(money, cents(95));
+    assert_eq!(
+        vec![cents(1), cents(2), cents(3)]
+            .into_iter()
+            .sum::<Money>(),
+        cents(6)
+    );
+    assert_eq!([cents(1), cents(2)].iter().sum::<Money>(), cents(3));
+
+    assert_eq!(cents(1999).percent(10, Rounding::HalfUp), Some(cents(200)));
+    assert_eq!(cents(1999).percent(10, Rounding::Down), Some(cents(199)));
+    assert_eq!(cents(1000).divide(3, Rounding::HalfUp), Some(cents(333)));
+    assert_eq!(cents(1000).divide(3, Rounding::Up), Some(cents(334)));
+}
+
+#[test]
+fn writes_money_with_two_decimals() {
+    assert_eq!(Money::ZERO.to_string(), "0.00");
+    assert_eq!(cents(5).to_string(), "0.05");
+    assert_eq!(cents(-5).to_string(), "-0.05");
+    assert_eq!(cents(123456).to_string(), "1234.56");
+    assert_eq!(cents(-100).to_string(), "-1.00");
+    assert_eq!(format!("{:>6}", cents(150)), "  1.50");
+}
+
+#[test]
+fn takes_a_percentage_discount_off_before_the_tax() {
+    let line = input(1999, 3000, Discount::Percent(10), 21);
+    assert_eq!(
+        line_totals(&line, Rules::default()),
+        totals(5997, 600, 5397, 1133, 6530)
+    );
+}
+
+#[test]
+fn takes_an_absolute_discount_off_the_whole_line() {
+    let line = input(1000, 2000, Discount::Amount(cents(550)), 10);
+    assert_eq!(
+        line_totals(&line, Rules::default()),
+        totals(2000, 550, 1450, 145, 1595)
+    );
+}
+
+#[test]
+fn never_discounts_more_than_the_line() {
+    let rules = Rules::default();
+    let line = input(1000, 2000, Discount::Amount(cents(3000)), 10);
+    assert_eq!(line_totals(&line, rules), totals(2000, 2000, 0, 0, 0));
+
+    let line = input(1000, 2000, Discount::Amount(cents(-300)), 0);
+    assert_eq!(line_totals(&line, rules).discount, cents(300));
+
+    let line = input(1000, 1000, Discount::Percent(150), 10);
+    assert_eq!(line_totals(&line, rules), totals(1000, 1000, 0, 0, 0));
+
+    let line = input(1000, 1000, Discount::Percent(-5), 0);
+    assert_eq!(line_totals(&line, rules).discount, Money::ZERO);
+}
+
+#[test]
+fn prices_refund_lines_like_sale_lines() {
+    let rules = Rules::default();
+    let line = input(1000, -1000, Discount::Amount(cents(200)), 20);
+    assert_eq!(
+        line_totals(&line, rules),
+        totals(-1000, -200, -800, -160, -960)
+    );
+
+    let line = input(1000, -1000, Discount::Percent(10), 20);
+    assert_eq!(
+        line_totals(&line, rules),
+        totals(-1000, -100, -900, -180, -1080)
+    );
+}
+
+#[test]
+fn rounds_each_step_by_the_rules() {
+    // 0.25 at 10% tax is 2.5 cents of tax.
+    let line = input(25, 1000, Discount::Percent(0), 10);
+    let taxes: Vec<Money> = MODES
+        .iter()
+        .map(|mode| line_totals(&line, exclusive(*mode)).tax)
+        .collect();
+    assert_eq!(taxes, vec![cents(3), cents(2), cents(2), cents(3)]);
+
+    // 0.35 at 10% tax is 3.5 cents: half even goes up to 4.
+    let line = input(35, 1000, Discount::Percent(0), 10);
+    assert_eq!(
+        line_totals(&line, exclusive(Rounding::HalfEven)).tax,
+        cents(4)
+    );
+
+    // 0.375 kg at 2.99 is 112.125 cents.
+    let line = input(299, 375, Discount::Percent(0), 0);
+    let grosses: Vec<Money> = MODES
+        .iter()
+        .map(|mode| line_totals(&line, exclusive(*mode)).gross)
+        .collect();
+    assert_eq!(
+        grosses,
+        vec![cents(112), cents(112), cents(112), cents(113)]
+    );
+}
+
+#[test]
+fn works_the_tax_out_of_inclusive_prices() {
+    let line = input(1200, 1000, Discount::Percent(0), 20);
+    assert_eq!(
+        line_totals(&line, inclusive(Rounding::HalfUp)),
+        totals(1200, 0, 1000, 200, 1200)
+    );
+
+    let line = input(1000, 1000, Discount::Percent(0), 7);
+    assert_eq!(
+        line_totals(&line, inclusive(Rounding::HalfUp)),
+        totals(1000, 0, 935, 65, 1000)
+    );
+    assert_eq!(
+        line_totals(&line, inclusive(Rounding::Down)),
+        totals(1000, 0, 934, 66, 1000)
+    );
+
+    let line = input(1200, 2000, Discount::Percent(10), 20);
+    assert_eq!(
+        line_totals(&line, inclusive(Rounding::HalfUp)),
+        totals(2400, 240, 1800, 360, 2160)
+    );
+}
+
+#[test]
+fn charges_no_tax_at_zero_or_negative_rates() {
+    for rate in [0, -10].iter() {
+        for rules in [exclusive(Rounding::HalfUp), inclusive(Rounding::HalfUp)].iter() {
+            let line = input(999, 1000, Discount::Percent(0), *rate);
+            assert_eq!(line_totals(&line, *rules), totals(999, 0, 999, 0, 999));
+        }
+    }
+}
+
+#[test]
+fn keeps_every_line_consistent() {
+    let discounts = [
+        Discount::Percent(0),
+        Discount::Percent(7),
+        Discount::Percent(33),
+        Discount::Percent(100),
+        Discount::Amount(cents(1)),
+        Discount::Amount(cents(250)),
+        Discount::Amount(cents(100_000)),
+    ];
+    for price in (0..=1000).step_by(37) {
+        for millis in [-1500, 1, 333, 1000, 2500, 12_345].iter() {
+            for discount in discounts.iter() {
+                for tax_rate in [0, 4, 10, 21, 27].iter() {
+                    for mode in MODES.iter() {
+                        let line = input(price, *millis, *discount, *tax_rate);
+
+                        let sum = line_totals(&line, exclusive(*mode));
+                        assert!(sum.discount.abs() <= sum.gross.abs(), "{:?}", line);
+                        assert_eq!(sum.net, sum.gross - sum.discount, "{:?}", line);
+                        assert_eq!(sum.total, sum.net + sum.tax, "{:?}", line);
+                        assert!(sum.tax.abs() <= sum.net.abs(), "{:?}", line);
+
+                        let sum = line_totals(&line, inclusive(*mode));
+                        assert!(sum.discount.abs() <= sum.gross.abs(), "{:?}", line);
+                        assert_eq!(sum.total, sum.gross - sum.discount, "{:?}", line);
+                        assert_eq!(sum.total, sum.net + sum.tax, "{:?}", line);
+                        assert!(sum.net.abs() <= sum.total.abs(), "{:?}", line);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn totals_a_sale_from_its_rounded_lines() {
+    // Each line has half a cent of tax, rounded up on its own.
+    let lines = vec![input(10, 1000, Discount::Percent(0), 5); 3];
+    let sale = sale_totals(&lines, Rules::default());
+
+    assert_eq!(sale.lines.len(), 3);
+    assert_eq!(sale.totals, totals(30, 0, 30, 3, 33));
+    assert_eq!(
+        sale.taxes,
+        vec![TaxLine {
+            rate: 5,
+            base: cents(30),
+            tax: cents(3),
+        }]
+    );
+}
+
+#[test]
+fn breaks_a_sale_down_by_tax_rate() {
+    let lines = vec![
+        input(1000, 1000, Discount::Percent(0), 21),
+        input(500, 2000, Discount::Amount(cents(100)), 10),
+        input(300, 1000, Discount::Percent(0), 0),
+        input(2000, 1000, Discount::Percent(50), 21),
+    ];
+    let sale = sale_totals(&lines, Rules::default());
+
+    assert_eq!(sale.totals, totals(4300, 1100, 3200, 510, 3710));
+    assert_eq!(
+        sale.taxes,
+        vec![
+            TaxLine {
+                rate: 0,
+                base: cents(300),
+                tax: Money::ZERO,
+            },
+            TaxLine {
+                rate: 10,
+                base: cents(900),
+                tax: cents(90),
+            },
+            TaxLine {
+                rate: 21,
+                base: cents(2000),
+                tax: cents(420),
+            },
+        ]
+    );
+}
+
+#[test]
+fn totals_an_empty_sale() {
+    let sale = sale_totals(&[], Rules::default());
+    assert_eq!(sale, SaleTotals::default());
+}
+
+#[test]
+fn prices_sale_lines_as_the_api_stores_them() {
+    let sale_product = SaleProduct {
+        id: 1,
+        product_id: 1,
+        sale_id: 1,
+        amount: 1.5,
+        discount: 10,
+        tax: 20,
+        price: 12,
+        total: 19.44,
+    };
+    assert_eq!(
+        LineInput::of(&sale_product),
+        input(1200, 1500, Discount::Percent(10), 20)
+    );
+    let line = LineTotals::of(&sale_product);
+    assert_eq!(line, totals(1800, 180, 1620, 324, 1944));
+    assert_eq!(line.total, Money::from_f64(sale_product.total));
+
+    let sale = FullSale {
+        sale: Sale {
+            id: 1,
+            user_id: 1,
+            sale_date: chrono::NaiveDate::from_ymd(2020, 5, 1),
+            total: 19.44,
+            bill_number: None,
+            state: SaleState::Payed,
+        },
+        sale_products: vec![FullSaleProduct {
+            sale_product,
+            product: Product {
+                id: 1,
+                name: "Coffee".to_string(),
+                stock: 1.0,
+                cost: None,
+                description: None,
+                user_id: 1,
+            },
+        }],
+    };
+    assert_eq!(SaleTotals::of(&sale).totals, line);
+}
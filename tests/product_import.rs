@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use yew_mystore::export::csv::{detect_delimiter, parse, NumberLocale};
+use yew_mystore::export::Cell;
+use yew_mystore::models::{FullPriceProduct, FullProduct, Price, PriceProduct, Product};
+use yew_mystore::products::import::plan::{
+    check_mapping, error_report, guess_mapping, plan, Action, Field, OnDuplicate, PriceAmount,
+};
+
+fn retail() -> Price {
+    Price {
+        id: 7,
+        name: "Retail".to_string(),
+        user_id: 1,
+    }
+}
+
+fn existing() -> Vec<FullProduct> {
+    vec![FullProduct {
+        product: Product {
+            id: 3,
+            name: "Coffee".to_string(),
+            stock: 1.0,
+            cost: None,
+            description: None,
+            user_id: 1,
+        },
+        price_products: vec![FullPriceProduct {
+            price_product: PriceProduct {
+                id: 30,
+                price_id: 7,
+                product_id: 3,
+                user_id: 1,
+                amount: Some(90),
+            },
+            price: retail(),
+        }],
+    }]
+}
+
+fn records(text: &str) -> Vec<Vec<String>> {
+    parse(text, detect_delimiter(text)).unwrap()
+}
+
+#[test]
+fn parses_rfc_4180_records() {
+    let text = "\u{feff}name,description\r\n\"Tea, green\",\"says \"\"hi\"\"\nthere\"\nMilk,\r\n";
+    assert_eq!(
+        parse(text, ',').unwrap(),
+        vec![
+            vec!["name".to_string(), "description".to_string()],
+            vec!["Tea, green".to_string(), "says \"hi\"\nthere".to_string()],
+            vec!["Milk".to_string(), String::new()],
+        ]
+    );
+    assert!(parse("name\n\"open", ',').is_err());
+    assert_eq!(parse("", ',').unwrap(), Vec::<Vec<String>>::new());
+}
+
+#[test]
+fn detects_the_delimiter_from_the_header() {
+    assert_eq!(detect_delimiter("name;stock;cost\n1,5;2;3"), ';');
+    assert_eq!(detect_delimiter("name\tstock\n"), '\t');
+    assert_eq!(detect_delimiter("\"a;b\",c\n"), ',');
+    assert_eq!(detect_delimiter("name\n"), ',');
+}
+
+#[test]
+fn reads_numbers_in_the_file_locale() {
+    assert_eq!(NumberLocale::COMMA.parse(" 1,5 "), Some(1.5));
+    assert_eq!(NumberLocale::POINT.parse("1.5"), Some(1.5));
+    assert_eq!(NumberLocale::POINT.parse("1,5"), None);
+    assert_eq!(NumberLocale::POINT.parse("NaN"), None);
+}
+
+#[test]
+fn guesses_the_mapping_from_headers() {
+    let headers: Vec<String> = ["Name", " stock ", "Retail", "Name", "Notes"]
+        .iter()
+        .map(|header| header.to_string())
+        .collect();
+    assert_eq!(
+        guess_mapping(&headers, &[retail()]),
+        vec![
+            Field::Name,
+            Field::Stock,
+            Field::Price(7),
+            Field::Ignore,
+            Field::Ignore
+        ]
+    );
+}
+
+#[test]
+fn requires_a_name_column_mapped_once() {
+    assert!(check_mapping(&[Field::Stock]).is_err());
+    assert!(check_mapping(&[Field::Name, Field::Cost, Field::Cost]).is_err());
+    assert!(check_mapping(&[Field::Name, Field::Ignore, Field::Ignore]).is_ok());
+}
+
+#[test]
+fn validates_rows_and_matches_existing_products() {
+    let records = records(
+        "name,stock,cost,retail\n\
+         Tea,4.5,100,120\n\
+         coffee ,2,,95\n\
+         ,1,1,1\n\
+         Milk,-1,2.5,x\n\
+         ,,,\n\
+         TEA,1,1,1\n",
+    );
+    let mapping = [Field::Name, Field::Stock, Field::Cost, Field::Price(7)];
+    let rows = plan(
+        &records,
+        &mapping,
+        NumberLocale::POINT,
+        &existing(),
+        OnDuplicate::Update,
+    );
+
+    assert_eq!(rows.len(), 5);
+    assert_eq!(rows[0].record, 2);
+    assert!(rows[0].is_valid());
+    assert_eq!(rows[0].action, Action::Create);
+    assert_eq!(rows[0].form.stock, Some(4.5));
+    assert_eq!(rows[0].form.cost, Some(100));
+    assert_eq!(
+        rows[0].prices,
+        vec![PriceAmount {
+            id: None,
+            price_id: 7,
+            amount: 120
+        }]
+    );
+
+    assert_eq!(rows[1].action, Action::Update(3));
+    assert_eq!(rows[1].form.cost, None);
+    assert_eq!(rows[1].prices[0].id, Some(30));
+
+    assert_eq!(rows[2].errors, vec!["Name is required".to_string()]);
+    assert_eq!(rows[3].errors.len(), 3);
+    assert_eq!(rows[4].record, 7);
+    assert_eq!(rows[4].errors, vec!["Same name as record 2".to_string()]);
+
+    let skipped = plan(
+        &records,
+        &mapping,
+        NumberLocale::POINT,
+        &existing(),
+        OnDuplicate::Skip,
+    );
+    assert_eq!(skipped[1].action, Action::Skip);
+    assert!(!skipped[1].is_imported());
+}
+
+#[test]
+fn reports_invalid_and_failed_records() {
+    let records = records("name,stock\nTea,x\nMilk,1\nSugar,2\n");
+    let mapping = [Field::Name, Field::Stock];
+    let rows = plan(
+        &records,
+        &mapping,
+        NumberLocale::POINT,
+        &[],
+        OnDuplicate::Update,
+    );
+    let mut failures = BTreeMap::new();
+    failures.insert(4, "Name taken".to_string());
+    let report = error_report(&records, &rows, &failures);
+
+    assert_eq!(report.headers, vec!["Record", "name", "stock", "Errors"]);
+    assert_eq!(report.rows.len(), 2);
+    assert_eq!(
+        report.rows[0],
+        vec![
+            Cell::Integer(2),
+            Cell::from("Tea"),
+            Cell::from("x"),
+            Cell::from("Stock \"x\" is not a number"),
+        ]
+    );
+    assert_eq!(report.rows[1][0], Cell::Integer(4));
+    assert_eq!(report.rows[1][3], Cell::from("Name taken"));
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use serde_json::json;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+use yew_mystore::register::Model as Register;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn fill_form(root: &web_sys::Element, password_confirmation: &str) {
+    common::type_into(root, "input[name=email]", "jhon@doe.com");
+    common::type_into(root, "input[name=company]", "My Store");
+    common::type_into(root, "input[name=password]", "12345678");
+    common::type_into(
+        root,
+        "input[name=password_confirmation]",
+        password_confirmation,
+    );
+}
+
+#[wasm_bindgen_test]
+fn renders_register_form() {
+    common::setup();
+    let (root, _) = common::mount::<Register>();
+
+    let html = root.inner_html();
+    for field in &["email", "company", "password", "password_confirmation"] {
+        assert!(html.contains(&format!("name=\"{}\"", field)));
+    }
+}
+
+#[wasm_bindgen_test]
+async fn registers_and_redirects_to_index() {
+    let transport = common::setup();
+    common::navigate("/register");
+    transport.respond(
+        "POST",
+        "/register",
+        &[],
+        &json!({
+            "id": 1,
+            "email": "jhon@doe.com",
+            "company": "My Store",
+            "created_at": "2020-06-01T00:00:00"
+        }),
+    );
+    let (root, _) = common::mount::<Register>();
+
+    fill_form(&root, "12345678");
+    common::click(&root, "button");
+    common::flush().await;
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].url, "/register");
+    let body: serde_json::Value =
+        serde_json::from_str(requests[0].body.as_ref().unwrap()).unwrap();
+    assert_eq!(body["company"], "My Store");
+    assert_eq!(common::current_path(), "/");
+}
+
+#[wasm_bindgen_test]
+async fn does_not_register_with_short_password_confirmation() {
+    let transport = common::setup();
+    common::navigate("/register");
+    let (root, _) = common::mount::<Register>();
+
+    fill_form(&root, "1234");
+    common::click(&root, "button");
+    common::flush().await;
+
+    assert!(transport.requests().is_empty());
+    assert_eq!(common::current_path(), "/register");
+}
@@ -0,0 +1,152 @@
+#[path = "common/fixtures.rs"]
+mod fixtures;
+
+use yew_mystore::dashboard::kpi::Period;
+use yew_mystore::models::{FullSale, SaleState};
+use yew_mystore::pricing::{LineTotals, Money};
+use yew_mystore::reports::aggregate::{
+    by_period, by_price_list, by_product, in_range, summarize, top_products, NO_PRICE_LIST,
+};
+
+use fixtures::{date, line, priced, product, sale};
+
+fn sales() -> Vec<FullSale> {
+    vec![
+        sale(
+            1,
+            "2020-05-01",
+            SaleState::Payed,
+            vec![
+                line(product(1, "Coffee"), 2.0, 100, 10, 0, 180.0),
+                line(product(2, "Tea"), 1.0, 50, 0, 20, 60.0),
+            ],
+        ),
+        sale(
+            2,
+            "2020-05-02",
+            SaleState::Approved,
+            vec![line(product(2, "Tea"), 3.0, 50, 0, 0, 150.0)],
+        ),
+        sale(
+            3,
+            "2020-05-02",
+            SaleState::Cancelled,
+            vec![line(product(1, "Coffee"), 9.0, 100, 0, 0, 900.0)],
+        ),
+        sale(
+            4,
+            "2020-06-01",
+            SaleState::Payed,
+            vec![line(product(1, "Coffee"), 1.0, 100, 0, 0, 100.0)],
+        ),
+    ]
+}
+
+#[test]
+fn takes_the_discount_off_before_the_tax() {
+    let totals = LineTotals::of(&line(product(1, "Coffee"), 2.0, 100, 10, 20, 216.0).sale_product);
+    assert_eq!(
+        totals,
+        LineTotals {
+            gross: Money::from_units(200),
+            discount: Money::from_units(20),
+            net: Money::from_units(180),
+            tax: Money::from_units(36),
+            total: Money::from_units(216),
+        }
+    );
+}
+
+#[test]
+fn leaves_out_cancelled_and_out_of_range_sales() {
+    let sales = sales();
+    let ids: Vec<i64> = in_range(&sales, date("2020-05-01"), date("2020-05-31"))
+        .iter()
+        .map(|full_sale| full_sale.sale.id)
+        .collect();
+    assert_eq!(ids, vec![1, 2]);
+}
+
+#[test]
+fn summarizes_revenue_ticket_discount_and_tax() {
+    let sales = sales();
+    let summary = summarize(&in_range(&sales, date("2020-05-01"), date("2020-05-31")));
+    assert_eq!(summary.sales, 2);
+    assert_eq!(summary.revenue, Money::from_units(180 + 60 + 150));
+    assert_eq!(summary.average_ticket, Money::from_units(195));
+    assert_eq!(summary.lines.discount, Money::from_units(20));
+    assert_eq!(summary.lines.tax, Money::from_units(10));
+    assert_eq!(summarize(&[]).average_ticket, Money::ZERO);
+}
+
+#[test]
+fn ranks_products_by_revenue_and_by_amount() {
+    let sales = sales();
+    let selected = in_range(&sales, date("2020-05-01"), date("2020-05-31"));
+
+    let rows = by_product(&selected);
+    assert_eq!(rows[0].name, "Tea");
+    assert_eq!(rows[0].amount, 4.0);
+    assert_eq!(rows[0].revenue, Money::from_units(210));
+    assert_eq!(rows[1].name, "Coffee");
+    assert_eq!(rows[1].amount, 2.0);
+
+    let top: Vec<String> = top_products(&selected, 1)
+        .into_iter()
+        .map(|row| row.name)
+        .collect();
+    assert_eq!(top, vec!["Tea"]);
+}
+
+#[test]
+fn attributes_lines_to_the_price_list_with_their_price() {
+    let products = vec![priced(product(1, "Coffee"), 7, Some(100))];
+    let sales = sales();
+    let rows = by_price_list(
+        &in_range(&sales, date("2020-05-01"), date("2020-05-31")),
+        &products,
+    );
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].name, NO_PRICE_LIST);
+    assert_eq!(rows[0].lines, 2);
+    assert_eq!(rows[0].revenue, Money::from_units(210));
+    assert_eq!(rows[1].price_id, Some(7));
+    assert_eq!(rows[1].lines, 1);
+    assert_eq!(rows[1].revenue, Money::from_units(180));
+}
+
+#[test]
+fn sums_revenue_per_period_in_cents() {
+    let sales = sales();
+    let rows = by_period(
+        &in_range(&sales, date("2020-05-01"), date("2020-05-03")),
+        Period::Day,
+        date("2020-05-01"),
+        date("2020-05-03"),
+    );
+    assert_eq!(
+        rows,
+        vec![
+            (date("2020-05-01"), Money::from_units(240)),
+            (date("2020-05-02"), Money::from_units(150)),
+            (date("2020-05-03"), Money::ZERO),
+        ]
+    );
+
+    let dimes: Vec<FullSale> = (1..=3)
+        .map(|id| sale(id, "2020-05-01", SaleState::Payed, vec![]))
+        .map(|mut dime| {
+            dime.sale.total = 0.1;
+            dime
+        })
+        .collect();
+    let selected: Vec<&FullSale> = dimes.iter().collect();
+    let rows = by_period(
+        &selected,
+        Period::Month,
+        date("2020-05-01"),
+        date("2020-05-31"),
+    );
+    assert_eq!(rows, vec![(date("2020-05-01"), Money::from_cents(30))]);
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use serde_json::json;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+use yew_mystore::dates::{today, DATE_FORMAT};
+use yew_mystore::reports::Model as Reports;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn report_data() -> serde_json::Value {
+    let day = today().format(DATE_FORMAT).to_string();
+    json!({
+        "data": {
+            "listSale": { "data": [{
+                "sale": {
+                    "__typename": "Sale",
+                    "id": 1,
+                    "userId": 1,
+                    "saleDate": day,
+                    "total": 216.0,
+                    "billNumber": "B-1",
+                    "state": "PAYED",
+                },
+                "saleProducts": [{
+                    "product": {
+                        "__typename": "Product",
+                        "id": 1,
+                        "name": "Coffee",
+                        "stock": 10.0,
+                        "cost": null,
+                        "description": null,
+                        "userId": 1,
+                    },
+                    "saleProduct": {
+                        "__typename": "SaleProduct",
+                        "id": 1,
+                        "productId": 1,
+                        "saleId": 1,
+                        "amount": 2.0,
+                        "discount": 10,
+                        "tax": 20,
+                        "price": 100,
+                        "total": 216.0,
+                    },
+                }],
+            }] },
+            "listProduct": { "data": [] },
+        }
+    })
+}
+
+#[wasm_bindgen_test]
+async fn reports_on_sales_in_the_range() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "secret-token")],
+        &report_data(),
+    );
+    let (root, _) = common::mount::<Reports>();
+    common::flush().await;
+
+    let summary = common::query(&root, ".report-summary")
+        .text_content()
+        .unwrap();
+    assert!(summary.contains("216.00"));
+    assert!(summary.contains("20.00"));
+    assert!(summary.contains("36.00"));
+    let products = common::query(&root, ".report-products tbody")
+        .text_content()
+        .unwrap();
+    assert!(products.contains("Coffee"));
+    assert_eq!(
+        root.query_selector_all(".report-periods tr")
+            .unwrap()
+            .length(),
+        30
+    );
+
+    common::click(&root, ".report-period button:last-child");
+    common::flush().await;
+
+    assert!(
+        root.query_selector_all(".report-periods tr")
+            .unwrap()
+            .length()
+            <= 2
+    );
+}
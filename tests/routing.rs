@@ -0,0 +1,58 @@
+mod common;
+
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+use yew_router::route::Route;
+use yew_router::Switch;
+
+use yew_mystore::routing::AppRoute;
+use yew_mystore::Model as App;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn switch(path: &str) -> Option<AppRoute> {
+    AppRoute::switch(Route::new_no_state(path))
+}
+
+#[wasm_bindgen_test]
+fn matches_app_routes() {
+    assert!(matches!(switch("/register"), Some(AppRoute::Register)));
+    assert!(matches!(switch("/login"), Some(AppRoute::Login)));
+    assert!(matches!(switch("/dashboard"), Some(AppRoute::Dashboard)));
+    assert!(matches!(switch("/reports"), Some(AppRoute::Reports)));
+    assert!(matches!(switch("/sales"), Some(AppRoute::Sales)));
+    assert!(matches!(switch("/sales/5"), Some(AppRoute::Sale(5))));
+    assert!(matches!(switch("/products"), Some(AppRoute::Products)));
+    assert!(matches!(
+        switch("/products/import"),
+        Some(AppRoute::ProductImport)
+    ));
+    assert!(matches!(switch("/prices"), Some(AppRoute::Prices)));
+    assert!(matches!(switch("/pos"), Some(AppRoute::Pos)));
+    assert!(matches!(switch("/"), Some(AppRoute::Index)));
+}
+
+#[wasm_bindgen_test]
+fn renders_login_route() {
+    common::setup();
+    common::navigate("/login");
+    let (root, _) = common::mount::<App>();
+
+    assert!(root.inner_html().contains("name=\"password\""));
+}
+
+#[wasm_bindgen_test]
+fn renders_register_route() {
+    common::setup();
+    common::navigate("/register");
+    let (root, _) = common::mount::<App>();
+
+    assert!(root.inner_html().contains("password_confirmation"));
+}
+
+#[wasm_bindgen_test]
+fn renders_index_route() {
+    common::setup();
+    let (root, _) = common::mount::<App>();
+
+    assert!(root.inner_html().contains("Sign Up"));
+}
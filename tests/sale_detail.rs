@@ -0,0 +1,127 @@
+mod common;
+
+use serde_json::json;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+use web_sys::window;
+
+use yew_mystore::Model as App;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn sale_data() -> serde_json::Value {
+    json!({
+        "data": {
+            "showSale": {
+                "sale": {
+                    "__typename": "Sale",
+                    "id": 5,
+                    "userId": 1,
+                    "saleDate": "2020-05-01",
+                    "total": 24.0,
+                    "billNumber": "B-5",
+                    "state": "PAYED",
+                },
+                "saleProducts": [{
+                    "product": {
+                        "__typename": "Product",
+                        "id": 1,
+                        "name": "Coffee",
+                        "stock": 10.0,
+                        "cost": null,
+                        "description": null,
+                        "userId": 1,
+                    },
+                    "saleProduct": {
+                        "__typename": "SaleProduct",
+                        "id": 1,
+                        "productId": 1,
+                        "saleId": 5,
+                        "amount": 2.0,
+                        "discount": 0,
+                        "tax": 20,
+                        "price": 10,
+                        "total": 24.0,
+                    },
+                }],
+            },
+        }
+    })
+}
+
+#[wasm_bindgen_test]
+async fn renders_the_sale_as_an_invoice() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    window()
+        .unwrap()
+        .local_storage()
+        .unwrap()
+        .unwrap()
+        .set_item(
+            "mystore.user",
+            &json!({
+                "id": 1,
+                "email": "shop@example.com",
+                "company": "Corner Shop",
+                "created_at": "2020-01-01",
+            })
+            .to_string(),
+        )
+        .unwrap();
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "secret-token")],
+        &sale_data(),
+    );
+    common::navigate("/sales/5");
+    let (root, _) = common::mount::<App>();
+    common::flush().await;
+
+    let invoice = common::query(&root, ".invoice").text_content().unwrap();
+    assert!(invoice.contains("Corner Shop"));
+    assert!(invoice.contains("B-5"));
+    assert!(invoice.contains("Coffee"));
+    assert!(invoice.contains("Tax 20%"));
+    assert!(root.query_selector("button.print").unwrap().is_some());
+}
+
+#[wasm_bindgen_test]
+async fn switches_to_a_receipt_layout() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "secret-token")],
+        &sale_data(),
+    );
+    common::navigate("/sales/5");
+    let (root, _) = common::mount::<App>();
+    common::flush().await;
+    assert!(root.query_selector(".invoice-a4").unwrap().is_some());
+
+    common::choose(&root, "select.print-layout", "Receipt 58mm");
+    common::flush().await;
+
+    assert!(root.query_selector(".invoice-58mm").unwrap().is_some());
+}
+
+#[wasm_bindgen_test]
+async fn says_the_seller_is_unknown_without_a_saved_user() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "secret-token")],
+        &sale_data(),
+    );
+    common::navigate("/sales/5");
+    let (root, _) = common::mount::<App>();
+    common::flush().await;
+
+    let company = common::query(&root, ".invoice-company");
+    assert_eq!(company.text_content().unwrap(), "Unknown seller");
+    assert!(company.class_name().contains("invoice-company-unknown"));
+}
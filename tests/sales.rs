@@ -0,0 +1,73 @@
+mod common;
+
+use serde_json::json;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+use yew_mystore::sales::Model as Sales;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn sales_data() -> serde_json::Value {
+    json!({
+        "data": {
+            "listSale": { "data": [{
+                "sale": {
+                    "__typename": "Sale",
+                    "id": 1,
+                    "userId": 1,
+                    "saleDate": "2020-05-01",
+                    "total": 12.5,
+                    "billNumber": "B-1",
+                    "state": "APPROVED",
+                },
+                "saleProducts": [],
+            }] },
+        }
+    })
+}
+
+#[wasm_bindgen_test]
+async fn lists_sales_with_export_actions() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "secret-token")],
+        &sales_data(),
+    );
+    let (root, _) = common::mount::<Sales>();
+    common::flush().await;
+
+    let rows = common::query(&root, ".sales tbody").text_content().unwrap();
+    assert!(rows.contains("B-1"));
+    assert!(rows.contains("12.50"));
+    assert!(root.query_selector(".export-csv").unwrap().is_some());
+    assert!(root.query_selector(".export-xlsx").unwrap().is_some());
+}
+
+#[wasm_bindgen_test]
+async fn sends_the_search_as_a_form_sale() {
+    let transport = common::setup();
+    common::store_token("secret-token");
+    transport.respond(
+        "POST",
+        "/graphql",
+        &[("x-csrf-token", "secret-token")],
+        &sales_data(),
+    );
+    let (root, _) = common::mount::<Sales>();
+    common::flush().await;
+
+    common::type_into(&root, ".search-bill-number", "B-1");
+    common::click(&root, "button.search");
+    common::flush().await;
+
+    let body: serde_json::Value =
+        serde_json::from_str(transport.requests().last().unwrap().body.as_ref().unwrap()).unwrap();
+    assert_eq!(body["variables"]["search"]["billNumber"], "B-1");
+    assert_eq!(
+        body["variables"]["search"]["state"],
+        serde_json::Value::Null
+    );
+}